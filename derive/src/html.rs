@@ -0,0 +1,304 @@
+//! `telegram_html!`: a Telegram HTML template checked at compile time.
+
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitStr, Token};
+
+/// Tags Telegram accepts without attributes.
+const SIMPLE_TAGS: &[&str] = &[
+    "b", "strong", "i", "em", "u", "ins", "s", "strike", "del", "code", "pre", "blockquote", "tg-spoiler",
+];
+
+pub(crate) struct Template {
+    template: LitStr,
+    positional: Vec<TokenStream>,
+    named: Vec<(Ident, TokenStream)>,
+}
+
+impl Parse for Template {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let template = input.parse()?;
+        let mut positional = Vec::new();
+        let mut named = Vec::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            if input.peek(syn::Ident) && input.peek2(Token![=]) && !input.peek2(Token![==]) {
+                let name: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                named.push((name, argument(input)?));
+            } else if named.is_empty() {
+                positional.push(argument(input)?);
+            } else {
+                return Err(input.error("positional arguments must come before named arguments"));
+            }
+        }
+        Ok(Self {
+            template,
+            positional,
+            named,
+        })
+    }
+}
+
+/// Takes the tokens up to the next top level comma.
+fn argument(input: ParseStream) -> syn::Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+    while !input.is_empty() && !input.peek(Token![,]) {
+        tokens.extend([input.parse::<TokenTree>()?]);
+    }
+    if tokens.is_empty() {
+        return Err(input.error("expected an expression"));
+    }
+    Ok(tokens)
+}
+
+/// Argument of a placeholder.
+enum Arg {
+    Index(usize),
+    Name(String),
+}
+
+enum Segment {
+    Literal(String),
+    Hole { arg: Arg, spec: String, attribute: bool },
+}
+
+pub(crate) fn expand(input: Template) -> syn::Result<TokenStream> {
+    let span = input.template.span();
+    let error = |message: String| syn::Error::new(span, message);
+    let mut segments = parse_template(&input.template.value()).map_err(error)?;
+    check_html(&mut segments).map_err(error)?;
+
+    let names: HashMap<String, usize> = input
+        .named
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| (name.to_string(), input.positional.len() + i))
+        .collect();
+    let count = input.positional.len() + input.named.len();
+    let bindings: Vec<Ident> = (0..count).map(|i| format_ident!("__arg{}", i, span = Span::mixed_site())).collect();
+    let mut used = vec![false; count];
+
+    let mut pushes = Vec::with_capacity(segments.len());
+    for segment in &segments {
+        match segment {
+            Segment::Literal(text) => pushes.push(quote! { __html.push_str(#text); }),
+            Segment::Hole { arg, spec, attribute } => {
+                let value = match arg {
+                    Arg::Index(index) if *index < count => {
+                        used[*index] = true;
+                        let binding = &bindings[*index];
+                        quote! { #binding }
+                    }
+                    Arg::Index(index) => {
+                        return Err(error(format!("missing argument {} of the template", index)));
+                    }
+                    Arg::Name(name) => match names.get(name) {
+                        Some(&index) => {
+                            used[index] = true;
+                            let binding = &bindings[index];
+                            quote! { #binding }
+                        }
+                        None => {
+                            let ident = Ident::new(name, span);
+                            quote! { &#ident }
+                        }
+                    },
+                };
+                let format = LitStr::new(&format!("{{{}}}", spec), span);
+                let escape = if *attribute {
+                    quote! { ::shared_types::telegram::escape_html_attribute }
+                } else {
+                    quote! { ::shared_types::telegram::escape_html }
+                };
+                pushes.push(quote! { __html.push_str(&#escape(&::std::format!(#format, #value))); });
+            }
+        }
+    }
+    if let Some(unused) = used.iter().position(|used| !used) {
+        return Err(error(format!("argument {} is never used", unused)));
+    }
+
+    let capacity: usize = segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Literal(text) => text.len(),
+            Segment::Hole { .. } => 16,
+        })
+        .sum();
+    let values = input
+        .positional
+        .iter()
+        .chain(input.named.iter().map(|(_, value)| value))
+        .map(|value| quote_spanned! { span => &(#value) });
+    Ok(quote! {
+        match (#(#values,)*) {
+            (#(#bindings,)*) => {
+                let mut __html = ::std::string::String::with_capacity(#capacity);
+                #(#pushes)*
+                __html
+            }
+        }
+    })
+}
+
+/// Splits a format string into literal text and placeholders.
+fn parse_template(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut next_index = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("unmatched `}` in template, escape it as `}}`".to_string()),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("unterminated placeholder in template".to_string()),
+                    }
+                }
+                let (arg, spec) = match placeholder.split_once(':') {
+                    Some((arg, spec)) => (arg.trim(), format!(":{}", spec)),
+                    None => (placeholder.trim(), String::new()),
+                };
+                if spec.contains(['$', '*']) {
+                    return Err(format!("unsupported format spec `{}`", spec));
+                }
+                let arg = if arg.is_empty() {
+                    next_index += 1;
+                    Arg::Index(next_index - 1)
+                } else if let Ok(index) = arg.parse() {
+                    Arg::Index(index)
+                } else if syn::parse_str::<Ident>(arg).is_ok() {
+                    Arg::Name(arg.to_string())
+                } else {
+                    return Err(format!("invalid placeholder `{{{}}}`", placeholder));
+                };
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Hole {
+                    arg,
+                    spec,
+                    attribute: false,
+                });
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+/// Checks that the template only uses Telegram tags, properly nested, and that
+/// placeholders are in text or in quoted attribute values, which it marks.
+fn check_html(segments: &mut [Segment]) -> Result<(), String> {
+    let mut open: Vec<String> = Vec::new();
+    // Text of the tag being read, placeholders as `\0`.
+    let mut tag: Option<String> = None;
+    for segment in segments.iter_mut() {
+        match segment {
+            Segment::Hole { attribute, .. } => match &mut tag {
+                Some(tag) if tag.matches('"').count() % 2 == 1 => {
+                    tag.push('\0');
+                    *attribute = true;
+                }
+                Some(_) => return Err("placeholders inside a tag must be in a quoted attribute value".to_string()),
+                None => {}
+            },
+            Segment::Literal(text) => {
+                for (i, c) in text.char_indices() {
+                    if let Some(current) = &mut tag {
+                        if c == '>' && current.matches('"').count() % 2 == 0 {
+                            check_tag(current, &mut open)?;
+                            tag = None;
+                        } else {
+                            current.push(c);
+                        }
+                        continue;
+                    }
+                    match c {
+                        '<' => tag = Some(String::new()),
+                        '>' => return Err("unescaped `>` in template, write `&gt;`".to_string()),
+                        '&' if !starts_with_entity(&text[i..]) => {
+                            return Err("unescaped `&` in template, write `&amp;`".to_string());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    if tag.is_some() {
+        return Err("unterminated tag in template".to_string());
+    }
+    match open.pop() {
+        Some(name) => Err(format!("unclosed `<{}>` in template", name)),
+        None => Ok(()),
+    }
+}
+
+fn check_tag(tag: &str, open: &mut Vec<String>) -> Result<(), String> {
+    if let Some(name) = tag.strip_prefix('/') {
+        let name = name.trim();
+        return match open.pop() {
+            Some(expected) if expected == name => Ok(()),
+            Some(expected) => Err(format!("`</{}>` closes `<{}>` in template", name, expected)),
+            None => Err(format!("`</{}>` has no opening tag in template", name)),
+        };
+    }
+    let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    let attributes = attributes.trim();
+    let quoted = |prefix: &str| {
+        attributes
+            .strip_prefix(prefix)
+            .and_then(|value| value.strip_suffix('"'))
+            .is_some_and(|value| !value.contains('"'))
+    };
+    let allowed = match name {
+        "a" => quoted("href=\""),
+        "blockquote" => attributes.is_empty() || attributes == "expandable",
+        "code" => attributes.is_empty() || quoted("class=\"language-"),
+        "span" => attributes == "class=\"tg-spoiler\"",
+        name => SIMPLE_TAGS.contains(&name) && attributes.is_empty(),
+    };
+    if !allowed {
+        return Err(format!("`<{}>` is not a Telegram HTML tag", tag.replace('\0', "{}")));
+    }
+    open.push(name.to_string());
+    Ok(())
+}
+
+/// Returns true when `text` starts with a character reference like `&amp;` or `&#39;`.
+fn starts_with_entity(text: &str) -> bool {
+    let Some(end) = text.find(';') else {
+        return false;
+    };
+    let name = &text[1..end];
+    match name.strip_prefix('#') {
+        Some(code) => match code.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()),
+        },
+        None => !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
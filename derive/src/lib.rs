@@ -0,0 +1,95 @@
+//! Procedural macros for `shared_types`, enabled by its `derive` feature.
+
+mod html;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, LitStr, parse_macro_input};
+
+/// Implements `shared_types::payload::QueuePayload`.
+///
+/// The kind defaults to the type name in snake_case without a trailing
+/// `QueueMessage` or `Message`, e.g. `FaxQueueMessage` is `fax` and
+/// `HTTPWebhookMessage` is `http_webhook`; set it with
+/// `#[queue_payload(kind = "...")]`. The JSON content type and byte encoding
+/// come from the trait defaults; `#[queue_payload(content_type = "...")]`
+/// overrides the content type for payloads that override the encoding.
+#[proc_macro_derive(QueuePayload, attributes(queue_payload))]
+pub fn derive_queue_payload(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut kind = None;
+    let mut content_type = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("queue_payload")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("kind") {
+                kind = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("content_type") {
+                content_type = Some(meta.value()?.parse::<LitStr>()?);
+            } else {
+                return Err(meta.error("expected `kind` or `content_type`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let name = &input.ident;
+    let kind = match kind {
+        Some(kind) if kind.value().is_empty() => {
+            return Err(syn::Error::new(kind.span(), "the payload kind cannot be empty"));
+        }
+        Some(kind) => kind,
+        None => LitStr::new(&default_kind(&name.to_string()), name.span()),
+    };
+    let content_type = content_type.map(|content_type| {
+        quote! {
+            fn content_type() -> &'static str {
+                #content_type
+            }
+        }
+    });
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::shared_types::payload::QueuePayload for #name #ty_generics #where_clause {
+            const KIND: &'static str = #kind;
+            #content_type
+        }
+    })
+}
+
+/// Returns the snake_case type name without a `QueueMessage` or `Message` suffix.
+///
+/// A run of capitals is one word, ending before the capital that starts the
+/// next word: `HTTPWebhook` is `http_webhook`.
+fn default_kind(name: &str) -> String {
+    let stem = name
+        .strip_suffix("QueueMessage")
+        .or_else(|| name.strip_suffix("Message"))
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(name);
+    let chars: Vec<char> = stem.chars().collect();
+    let mut kind = String::with_capacity(stem.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let after_word = !chars[i - 1].is_uppercase();
+            let starts_word = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_word || starts_word {
+                kind.push('_');
+            }
+        }
+        kind.extend(c.to_lowercase());
+    }
+    kind
+}
+
+/// Builds a Telegram HTML string from a template checked at compile time.
+///
+/// See `shared_types::telegram_html!` for the syntax.
+#[proc_macro]
+pub fn telegram_html(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as html::Template);
+    html::expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Comparison between a metric value and a threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Comparison {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+    Ne,
+}
+
+impl Comparison {
+    /// Returns true when `value` compared to `threshold` holds.
+    pub fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Gt => value > threshold,
+            Comparison::Gte => value >= threshold,
+            Comparison::Lt => value < threshold,
+            Comparison::Lte => value <= threshold,
+            Comparison::Eq => value == threshold,
+            Comparison::Ne => value != threshold,
+        }
+    }
+
+    /// Returns the operator symbol, e.g. `>=`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Gt => ">",
+            Comparison::Gte => ">=",
+            Comparison::Lt => "<",
+            Comparison::Lte => "<=",
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+        }
+    }
+}
+
+/// A threshold alert on a metric, as written in the monitoring job config.
+///
+/// The rule fires when the comparison held on every sample for at least
+/// `duration`, like the `for` clause of Prometheus rules.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use chrono::{TimeZone, Utc};
+/// use shared_types::MessageStatus;
+/// use shared_types::alert::{AlertRule, Comparison};
+///
+/// let rule = AlertRule::new("disk-full", "disk_used_percent", Comparison::Gte, 90.0, -100123)
+///     .with_duration(Duration::from_secs(300))
+///     .with_severity(MessageStatus::Error);
+///
+/// let at = |minute| Utc.with_ymd_and_hms(2024, 5, 17, 3, minute, 0).unwrap();
+/// let samples = [(at(0), 85.0), (at(2), 91.0), (at(5), 93.5), (at(7), 95.0)];
+/// let alert = rule.evaluate(samples).unwrap();
+/// assert_eq!(alert.since, at(2));
+/// assert_eq!(
+///     alert.to_telegram_message().message,
+///     "🚨 - <i>disk-full</i>\ndisk_used_percent = 95 (&gt;= 90) for 5m 00s"
+/// );
+///
+/// assert!(rule.evaluate([(at(0), 91.0), (at(4), 92.0)]).is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AlertRule {
+    pub name: String,
+    /// Name of the metric the rule watches.
+    pub metric: String,
+    pub operator: Comparison,
+    pub threshold: f64,
+    /// How long the condition must hold before firing; zero fires on the first sample.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+    #[cfg_attr(feature = "serde", serde(default, rename = "durationMs", with = "crate::duration_ms", alias = "duration_ms"))]
+    pub duration: Duration,
+    #[cfg_attr(feature = "serde", serde(default = "default_severity"))]
+    pub severity: MessageStatus,
+    /// Chat the firing alert is sent to.
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "chat_id"))]
+    pub chat_id: i64,
+}
+
+fn default_severity() -> MessageStatus {
+    MessageStatus::Warn
+}
+
+impl AlertRule {
+    /// Creates a rule firing immediately with `Warn` severity.
+    pub fn new(
+        name: impl Into<String>,
+        metric: impl Into<String>,
+        operator: Comparison,
+        threshold: f64,
+        chat_id: i64,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            metric: metric.into(),
+            operator,
+            threshold,
+            duration: Duration::ZERO,
+            severity: default_severity(),
+            chat_id,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_severity(mut self, severity: MessageStatus) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Evaluates the rule on `(timestamp, value)` samples in time order,
+    /// returning the firing alert when the condition held on the latest samples
+    /// for at least `duration`.
+    pub fn evaluate(&self, samples: impl IntoIterator<Item = (DateTime<Utc>, f64)>) -> Option<FiringAlert> {
+        let mut breach_since = None;
+        let mut latest = None;
+        for (timestamp, value) in samples {
+            if self.operator.holds(value, self.threshold) {
+                breach_since.get_or_insert(timestamp);
+            } else {
+                breach_since = None;
+            }
+            latest = Some((timestamp, value));
+        }
+        let since = breach_since?;
+        let (timestamp, value) = latest?;
+        let held_for = (timestamp - since).to_std().ok()?;
+        (held_for >= self.duration).then(|| FiringAlert {
+            rule: self.name.clone(),
+            metric: self.metric.clone(),
+            operator: self.operator,
+            threshold: self.threshold,
+            value,
+            since,
+            held_for,
+            severity: self.severity,
+            chat_id: self.chat_id,
+        })
+    }
+}
+
+/// An alert whose rule condition currently holds.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct FiringAlert {
+    /// Name of the rule.
+    pub rule: String,
+    pub metric: String,
+    pub operator: Comparison,
+    pub threshold: f64,
+    /// Latest value of the metric.
+    pub value: f64,
+    /// Timestamp of the first sample of the breach.
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub since: DateTime<Utc>,
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+    #[cfg_attr(feature = "serde", serde(rename = "heldForMs", with = "crate::duration_ms", alias = "held_for_ms"))]
+    pub held_for: Duration,
+    pub severity: MessageStatus,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "chat_id"))]
+    pub chat_id: i64,
+}
+
+impl FiringAlert {
+    /// Formats the alert for the rule's chat, deduplicated by rule name.
+    pub fn to_telegram_message(&self) -> TelegramQueueMessage {
+        TelegramMessageBuilder::new(self.chat_id)
+            .status(self.severity)
+            .job_name(&self.rule)
+            .summary(format!(
+                "{} = {} ({} {}) for {}",
+                render::escape_text(&self.metric),
+                self.value,
+                render::escape_text(self.operator.symbol()),
+                self.threshold,
+                render::duration(self.held_for)
+            ))
+            .dedup_key(format!("alert:{}", self.rule))
+            .build()
+    }
+}
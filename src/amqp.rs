@@ -0,0 +1,185 @@
+//! AMQP properties of queue envelopes, for publishing through lapin or any
+//! client built on `amq-protocol`.
+//!
+//! The envelope body stays the source of truth: [`QueueEnvelope::to_amqp_at`]
+//! derives the properties from it, and [`QueueEnvelope::from_amqp`] rejects
+//! deliveries whose properties disagree with the body they carry.
+//!
+//! Priorities map to 0 (`Low`) through [`AMQP_MAX_PRIORITY`] (`Critical`), so
+//! priority queues are declared with `x-max-priority` set to that value. The
+//! trace context, idempotency key and delay are sent as headers.
+
+use amq_protocol::protocol::BasicProperties;
+use amq_protocol::types::{AMQPValue, FieldTable, ShortString};
+use chrono::{DateTime, Utc};
+
+use crate::envelope::{QueueEnvelope, RABBITMQ_DELAY_HEADER};
+use crate::error::SharedTypesError;
+use crate::payload::{JSON_CONTENT_TYPE, QueuePayload};
+use crate::priority::Priority;
+
+/// Highest AMQP priority used, the `x-max-priority` of priority queues.
+pub const AMQP_MAX_PRIORITY: u8 = 3;
+
+/// Header holding the W3C `traceparent`.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Header holding the W3C `tracestate`.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Header holding the idempotency key.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Persistent delivery mode.
+const PERSISTENT: u8 = 2;
+
+/// Returns the AMQP priority of `priority`.
+pub fn amqp_priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::Critical => AMQP_MAX_PRIORITY,
+        priority => priority.rank(),
+    }
+}
+
+/// Returns the priority of an AMQP priority; values above
+/// [`AMQP_MAX_PRIORITY`] are `Critical`, as the broker caps them.
+pub fn priority_from_amqp(priority: u8) -> Priority {
+    Priority::from_rank(priority).unwrap_or(Priority::Critical)
+}
+
+fn mismatch(property: &str, value: impl std::fmt::Display, expected: impl std::fmt::Display) -> SharedTypesError {
+    SharedTypesError::Validation(format!(
+        "AMQP {} is {} but the body says {}",
+        property, value, expected
+    ))
+}
+
+impl<T: QueuePayload> QueueEnvelope<T> {
+    /// Returns the AMQP properties of the envelope at `now`.
+    ///
+    /// The `type` is the [`QueuePayload::KIND`], the timestamp is the creation
+    /// time, the `expiration` is the time left until `expires_at`, and messages
+    /// are persistent.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use shared_types::{Priority, QueueEnvelope, TelegramQueueMessage};
+    ///
+    /// let envelope = QueueEnvelope::new("ftp-sync", TelegramQueueMessage::new(123, "done".into(), false))
+    ///     .with_correlation_id("run-42")
+    ///     .with_priority(Priority::High)
+    ///     .expires_in(Duration::from_secs(60))
+    ///     .deliver_after(Duration::from_secs(5));
+    /// let properties = envelope.amqp_properties_at(envelope.created_at);
+    /// assert_eq!(properties.kind().as_ref().unwrap().as_str(), "telegram");
+    /// assert_eq!(properties.correlation_id().as_ref().unwrap().as_str(), "run-42");
+    /// assert_eq!(properties.expiration().as_ref().unwrap().as_str(), "60000");
+    /// assert_eq!(*properties.priority(), Some(2));
+    /// assert!(properties.headers().as_ref().unwrap().contains_key("x-delay"));
+    /// ```
+    pub fn amqp_properties_at(&self, now: DateTime<Utc>) -> BasicProperties {
+        let mut headers = FieldTable::default();
+        let mut header = |name: &str, value: &Option<String>| {
+            if let Some(value) = value {
+                headers.insert(name.into(), AMQPValue::LongString(value.as_str().into()));
+            }
+        };
+        header(TRACEPARENT_HEADER, &self.traceparent);
+        header(TRACESTATE_HEADER, &self.tracestate);
+        header(IDEMPOTENCY_KEY_HEADER, &self.idempotency_key);
+        if let Some(delay) = self.rabbitmq_x_delay_at(now) {
+            headers.insert(RABBITMQ_DELAY_HEADER.into(), AMQPValue::LongLongInt(delay));
+        }
+
+        let mut properties = BasicProperties::default()
+            .with_content_type(JSON_CONTENT_TYPE.into())
+            .with_type(T::KIND.into())
+            .with_message_id(self.message_id.to_string().into())
+            .with_app_id(self.producer.as_str().into())
+            .with_priority(amqp_priority(self.priority))
+            .with_delivery_mode(PERSISTENT)
+            .with_headers(headers);
+        if let Ok(timestamp) = u64::try_from(self.created_at.timestamp()) {
+            properties = properties.with_timestamp(timestamp);
+        }
+        if let Some(correlation_id) = &self.correlation_id {
+            properties = properties.with_correlation_id(correlation_id.as_str().into());
+        }
+        if let Some(expires_at) = self.expires_at {
+            let millis = (expires_at - now).num_milliseconds().max(0);
+            properties = properties.with_expiration(millis.to_string().into());
+        }
+        properties
+    }
+
+    /// Returns the AMQP properties of the envelope, see
+    /// [`QueueEnvelope::amqp_properties_at`].
+    pub fn amqp_properties(&self) -> BasicProperties {
+        self.amqp_properties_at(Utc::now())
+    }
+
+    /// Returns the properties and body to publish at `now`.
+    pub fn to_amqp_at(&self, now: DateTime<Utc>) -> Result<(BasicProperties, Vec<u8>), SharedTypesError> {
+        Ok((self.amqp_properties_at(now), serde_json::to_vec(self)?))
+    }
+
+    /// Returns the properties and body to publish, see
+    /// [`QueueEnvelope::to_amqp_at`].
+    pub fn to_amqp(&self) -> Result<(BasicProperties, Vec<u8>), SharedTypesError> {
+        self.to_amqp_at(Utc::now())
+    }
+
+    /// Parses a delivery, checking its properties against the body.
+    ///
+    /// Fails when the content type, `type`, message id, correlation id or
+    /// priority set on the delivery disagree with the body. A correlation id
+    /// only set on the delivery is copied to the envelope; the relative
+    /// `expiration` is not checked.
+    ///
+    /// ```rust
+    /// use amq_protocol::types::ShortString;
+    /// use shared_types::{QueueEnvelope, TelegramQueueMessage};
+    ///
+    /// let envelope = QueueEnvelope::new("ftp-sync", TelegramQueueMessage::new(123, "done".into(), false));
+    /// let (properties, body) = envelope.to_amqp().unwrap();
+    ///
+    /// let restored = QueueEnvelope::<TelegramQueueMessage>::from_amqp(&properties, &body).unwrap();
+    /// assert_eq!(restored.message_id, envelope.message_id);
+    ///
+    /// let properties = properties.with_message_id(ShortString::from("another-id"));
+    /// assert!(QueueEnvelope::<TelegramQueueMessage>::from_amqp(&properties, &body).is_err());
+    /// ```
+    pub fn from_amqp(properties: &BasicProperties, body: &[u8]) -> Result<Self, SharedTypesError> {
+        let property = |value: &Option<ShortString>| value.as_ref().map(|value| value.as_str().to_string());
+        if let Some(content_type) = property(properties.content_type()) {
+            let essence = content_type.split(';').next().unwrap_or_default().trim();
+            if !essence.eq_ignore_ascii_case(JSON_CONTENT_TYPE) {
+                return Err(mismatch("content type", content_type, JSON_CONTENT_TYPE));
+            }
+        }
+        if let Some(kind) = property(properties.kind()).filter(|kind| kind != T::KIND) {
+            return Err(mismatch("type", kind, T::KIND));
+        }
+
+        let mut envelope: Self = serde_json::from_slice(body)?;
+        if let Some(message_id) = property(properties.message_id())
+            .filter(|message_id| *message_id != envelope.message_id.to_string())
+        {
+            return Err(mismatch("message id", message_id, envelope.message_id));
+        }
+        match (property(properties.correlation_id()), &envelope.correlation_id) {
+            (Some(correlation_id), Some(expected)) if correlation_id != *expected => {
+                return Err(mismatch("correlation id", correlation_id, expected));
+            }
+            (Some(correlation_id), None) => envelope.correlation_id = Some(correlation_id),
+            _ => {}
+        }
+        if let Some(priority) = properties
+            .priority()
+            .filter(|priority| priority_from_amqp(*priority) != envelope.priority)
+        {
+            return Err(mismatch("priority", priority, amqp_priority(envelope.priority)));
+        }
+        Ok(envelope)
+    }
+}
@@ -0,0 +1,150 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// What an admin did to a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Login,
+    Logout,
+    /// Permissions granted to the resource, e.g. a user.
+    Grant,
+    /// Permissions revoked from the resource.
+    Revoke,
+    Export,
+}
+
+impl AuditAction {
+    /// Returns the past tense verb shown in messages, e.g. `deleted`.
+    pub fn verb(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "created",
+            AuditAction::Update => "updated",
+            AuditAction::Delete => "deleted",
+            AuditAction::Login => "logged in to",
+            AuditAction::Logout => "logged out of",
+            AuditAction::Grant => "granted permissions on",
+            AuditAction::Revoke => "revoked permissions on",
+            AuditAction::Export => "exported",
+        }
+    }
+
+    /// `Warn` for destructive and permission changing actions, `Info` otherwise.
+    pub fn status(&self) -> MessageStatus {
+        match self {
+            AuditAction::Delete | AuditAction::Grant | AuditAction::Revoke => MessageStatus::Warn,
+            _ => MessageStatus::Info,
+        }
+    }
+}
+
+/// An admin action, recorded and announced in the same format by every service.
+///
+/// ```rust
+/// use chrono::Utc;
+/// use shared_types::audit::{AuditAction, AuditEvent};
+///
+/// let event = AuditEvent::new("alice", AuditAction::Update, "chat-directory/ops", Utc::now())
+///     .with_change("chatId=-100123", "chatId=-100456")
+///     .with_source_ip("10.0.0.7".parse().unwrap());
+/// assert_eq!(
+///     event.to_telegram_message(123).message,
+///     "ℹ️ - <i>audit</i>\nalice updated chat-directory/ops from 10.0.0.7\n\
+///      <pre>- chatId=-100123\n+ chatId=-100456</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AuditEvent {
+    /// User or service account that acted.
+    pub actor: String,
+    pub action: AuditAction,
+    /// What was acted on, e.g. `users/42`.
+    pub resource: String,
+    /// Summary of the resource before the action.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub before: Option<String>,
+    /// Summary of the resource after the action.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub after: Option<String>,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub timestamp: DateTime<Utc>,
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<String>))]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "source_ip"))]
+    pub source_ip: Option<IpAddr>,
+}
+
+impl AuditEvent {
+    pub fn new(
+        actor: impl Into<String>,
+        action: AuditAction,
+        resource: impl Into<String>,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            actor: actor.into(),
+            action,
+            resource: resource.into(),
+            before: None,
+            after: None,
+            timestamp,
+            source_ip: None,
+        }
+    }
+
+    /// Sets the summaries of the resource before and after the action.
+    pub fn with_change(mut self, before: impl Into<String>, after: impl Into<String>) -> Self {
+        self.before = Some(before.into());
+        self.after = Some(after.into());
+        self
+    }
+
+    pub fn with_source_ip(mut self, source_ip: IpAddr) -> Self {
+        self.source_ip = Some(source_ip);
+        self
+    }
+
+    /// Formats the event for the `audit` job: who did what as summary and the
+    /// change as a `-`/`+` diff in a `<pre>` block.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let mut summary = format!(
+            "{} {} {}",
+            render::escape_text(&self.actor),
+            self.action.verb(),
+            render::escape_text(&self.resource)
+        );
+        if let Some(source_ip) = self.source_ip {
+            summary.push_str(&format!(" from {}", source_ip));
+        }
+        let change = [("- ", &self.before), ("+ ", &self.after)]
+            .into_iter()
+            .filter_map(|(prefix, summary)| summary.as_ref().map(|summary| format!("{}{}", prefix, summary)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.action.status())
+            .job_name("audit")
+            .summary(summary)
+            .code_block(change)
+            .build()
+    }
+}
@@ -0,0 +1,430 @@
+//! Avro schemas and binary encoding of [`TelegramQueueMessage`] and its
+//! [`QueueEnvelope`], for the Kafka notification topic.
+//!
+//! The encoding is written by hand against the schemas below so the crate
+//! keeps no Avro dependency; field order in the encoders must follow the
+//! schemas. [`AvroRecord::to_confluent`] adds the schema registry framing
+//! (magic byte and schema id) expected by Confluent compatible consumers.
+//! Timestamps are `timestamp-micros`, so nanoseconds are dropped, and the send
+//! policy is not carried: `forceSend` holds its fallback value.
+//!
+//! ```rust
+//! use shared_types::avro::AvroRecord;
+//! use shared_types::{MessageStatus, QueueEnvelope, TelegramQueueMessage};
+//!
+//! let msg = TelegramQueueMessage::builder(-100)
+//!     .status(MessageStatus::Warn)
+//!     .job_name("ftp")
+//!     .content("slow upload")
+//!     .dedup_key("ftp:slow")
+//!     .build();
+//! let envelope = QueueEnvelope::new("ftp-sync", msg).with_correlation_id("run-42");
+//!
+//! let bytes = envelope.to_confluent(7);
+//! let (schema_id, restored) = QueueEnvelope::<TelegramQueueMessage>::from_confluent(&bytes).unwrap();
+//! assert_eq!(schema_id, 7);
+//! assert_eq!(restored.message_id, envelope.message_id);
+//! assert_eq!(restored.created_at.timestamp_micros(), envelope.created_at.timestamp_micros());
+//! assert_eq!(restored.correlation_id.as_deref(), Some("run-42"));
+//! assert_eq!(restored.payload.message, envelope.payload.message);
+//! assert_eq!(restored.payload.dedup_key.as_deref(), Some("ftp:slow"));
+//!
+//! let schema: serde_json::Value = serde_json::from_str(TelegramQueueMessage::SCHEMA).unwrap();
+//! assert_eq!(schema["name"], "TelegramQueueMessage");
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::envelope::QueueEnvelope;
+use crate::error::SharedTypesError;
+use crate::priority::Priority;
+use crate::producer::ProducerInfo;
+use crate::retry::RetryInfo;
+use crate::telegram::{MessageStatus, TelegramQueueMessage};
+
+/// First byte of a payload framed for a schema registry.
+pub const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+macro_rules! telegram_queue_message_schema {
+    () => {
+        r#"{"type":"record","name":"TelegramQueueMessage","namespace":"shared_types.v1","fields":[
+{"name":"schemaVersion","type":"long"},
+{"name":"chatId","type":"long"},
+{"name":"message","type":"string"},
+{"name":"forceSend","type":"boolean"},
+{"name":"status","type":{"type":"enum","name":"MessageStatus","symbols":["None","Info","Ok","Warn","Error"]}},
+{"name":"dedupKey","type":["null","string"],"default":null},
+{"name":"metadata","type":{"type":"map","values":"string"}}]}"#
+    };
+}
+
+const TELEGRAM_ENVELOPE_SCHEMA: &str = concat!(
+    r#"{"type":"record","name":"TelegramEnvelope","namespace":"shared_types.v1","fields":[
+{"name":"schemaVersion","type":"long"},
+{"name":"messageId","type":{"type":"string","logicalType":"uuid"}},
+{"name":"createdAt","type":{"type":"long","logicalType":"timestamp-micros"}},
+{"name":"correlationId","type":["null","string"],"default":null},
+{"name":"producer","type":"string"},
+{"name":"producerInfo","type":["null",{"type":"record","name":"ProducerInfo","fields":[
+{"name":"service","type":"string"},
+{"name":"version","type":"string"},
+{"name":"hostname","type":"string"},
+{"name":"pid","type":"long"}]}],"default":null},
+{"name":"traceparent","type":["null","string"],"default":null},
+{"name":"tracestate","type":["null","string"],"default":null},
+{"name":"routingKey","type":["null","string"],"default":null},
+{"name":"idempotencyKey","type":["null","string"],"default":null},
+{"name":"priority","type":{"type":"enum","name":"Priority","symbols":["Low","Normal","High","Critical"]}},
+{"name":"expiresAt","type":["null",{"type":"long","logicalType":"timestamp-micros"}],"default":null},
+{"name":"deliverAfterMs","type":["null","long"],"default":null},
+{"name":"deliverAt","type":["null",{"type":"long","logicalType":"timestamp-micros"}],"default":null},
+{"name":"retry","type":["null",{"type":"record","name":"RetryInfo","fields":[
+{"name":"attempt","type":"long"},
+{"name":"maxAttempts","type":"long"},
+{"name":"lastError","type":["null","string"],"default":null},
+{"name":"nextRetryAt","type":["null",{"type":"long","logicalType":"timestamp-micros"}],"default":null}]}],"default":null},
+{"name":"payload","type":"#,
+    telegram_queue_message_schema!(),
+    "}]}"
+);
+
+/// A type with an Avro schema and binary encoding.
+pub trait AvroRecord: Sized {
+    /// The Avro schema, as JSON, to register for the topic.
+    const SCHEMA: &'static str;
+
+    /// Encodes the value with Avro binary encoding, without framing.
+    fn to_avro(&self) -> Vec<u8> {
+        let mut writer = Writer::default();
+        self.write(&mut writer);
+        writer.0
+    }
+
+    /// Decodes a value encoded with [`AvroRecord::to_avro`].
+    fn from_avro(bytes: &[u8]) -> Result<Self, SharedTypesError> {
+        let mut reader = Reader { bytes, position: 0 };
+        let value = Self::read(&mut reader)?;
+        if reader.position != bytes.len() {
+            return Err(decode_error("trailing bytes"));
+        }
+        Ok(value)
+    }
+
+    /// Encodes the value framed for a schema registry: the magic byte, the
+    /// big endian `schema_id`, then the Avro binary encoding.
+    fn to_confluent(&self, schema_id: u32) -> Vec<u8> {
+        let mut bytes = vec![CONFLUENT_MAGIC_BYTE];
+        bytes.extend_from_slice(&schema_id.to_be_bytes());
+        bytes.extend(self.to_avro());
+        bytes
+    }
+
+    /// Decodes a value framed by [`AvroRecord::to_confluent`], returning the
+    /// schema id along with it.
+    fn from_confluent(bytes: &[u8]) -> Result<(u32, Self), SharedTypesError> {
+        match bytes {
+            [CONFLUENT_MAGIC_BYTE, a, b, c, d, rest @ ..] => {
+                Ok((u32::from_be_bytes([*a, *b, *c, *d]), Self::from_avro(rest)?))
+            }
+            _ => Err(decode_error("missing schema registry header")),
+        }
+    }
+
+    #[doc(hidden)]
+    fn write(&self, writer: &mut Writer);
+
+    #[doc(hidden)]
+    fn read(reader: &mut Reader<'_>) -> Result<Self, SharedTypesError>;
+}
+
+impl AvroRecord for TelegramQueueMessage {
+    const SCHEMA: &'static str = telegram_queue_message_schema!();
+
+    fn write(&self, writer: &mut Writer) {
+        writer.long(i64::from(self.schema_version));
+        writer.long(self.chat_id);
+        writer.string(&self.message);
+        writer.boolean(self.force_send);
+        writer.long(i64::from(self.status.severity()));
+        writer.optional(self.dedup_key.as_ref(), |writer, key| writer.string(key));
+        writer.map(&self.metadata);
+    }
+
+    fn read(reader: &mut Reader<'_>) -> Result<Self, SharedTypesError> {
+        let schema_version = reader.u32()?;
+        let chat_id = reader.long()?;
+        let message = reader.string()?;
+        let force_send = reader.boolean()?;
+        let status = match reader.long()? {
+            0 => MessageStatus::None,
+            1 => MessageStatus::Info,
+            2 => MessageStatus::Ok,
+            3 => MessageStatus::Warn,
+            4 => MessageStatus::Error,
+            _ => return Err(decode_error("unknown message status")),
+        };
+        let mut converted = TelegramQueueMessage::new(chat_id, message, force_send);
+        converted.schema_version = schema_version;
+        converted.status = status;
+        converted.dedup_key = reader.optional(Reader::string)?;
+        converted.metadata = reader.map()?;
+        Ok(converted)
+    }
+}
+
+impl AvroRecord for QueueEnvelope<TelegramQueueMessage> {
+    const SCHEMA: &'static str = TELEGRAM_ENVELOPE_SCHEMA;
+
+    fn write(&self, writer: &mut Writer) {
+        writer.long(i64::from(self.schema_version));
+        writer.string(&self.message_id.to_string());
+        writer.timestamp(self.created_at);
+        writer.optional(self.correlation_id.as_ref(), |writer, id| writer.string(id));
+        writer.string(&self.producer);
+        writer.optional(self.producer_info.as_ref(), |writer, info| {
+            writer.string(&info.service);
+            writer.string(&info.version);
+            writer.string(&info.hostname);
+            writer.long(i64::from(info.pid));
+        });
+        writer.optional(self.traceparent.as_ref(), |writer, value| writer.string(value));
+        writer.optional(self.tracestate.as_ref(), |writer, value| writer.string(value));
+        writer.optional(self.routing_key.as_ref(), |writer, key| writer.string(key));
+        writer.optional(self.idempotency_key.as_ref(), |writer, key| writer.string(key));
+        writer.long(match self.priority {
+            Priority::Low => 0,
+            Priority::Normal => 1,
+            Priority::High => 2,
+            Priority::Critical => 3,
+        });
+        writer.optional(self.expires_at.as_ref(), |writer, at| writer.timestamp(*at));
+        writer.optional(self.deliver_after.as_ref(), |writer, delay| {
+            writer.long(i64::try_from(delay.as_millis()).unwrap_or(i64::MAX))
+        });
+        writer.optional(self.deliver_at.as_ref(), |writer, at| writer.timestamp(*at));
+        writer.optional(self.retry.as_ref(), |writer, retry| {
+            writer.long(i64::from(retry.attempt));
+            writer.long(i64::from(retry.max_attempts));
+            writer.optional(retry.last_error.as_ref(), |writer, error| writer.string(error));
+            writer.optional(retry.next_retry_at.as_ref(), |writer, at| writer.timestamp(*at));
+        });
+        self.payload.write(writer);
+    }
+
+    fn read(reader: &mut Reader<'_>) -> Result<Self, SharedTypesError> {
+        let schema_version = reader.u32()?;
+        let message_id = reader
+            .string()?
+            .parse()
+            .map_err(|_| decode_error("invalid message id"))?;
+        let created_at = reader.timestamp()?;
+        let correlation_id = reader.optional(Reader::string)?;
+        let producer = reader.string()?;
+        let producer_info = reader.optional(|reader| {
+            Ok(ProducerInfo {
+                service: reader.string()?,
+                version: reader.string()?,
+                hostname: reader.string()?,
+                pid: reader.u32()?,
+            })
+        })?;
+        let traceparent = reader.optional(Reader::string)?;
+        let tracestate = reader.optional(Reader::string)?;
+        let routing_key = reader.optional(Reader::string)?;
+        let idempotency_key = reader.optional(Reader::string)?;
+        let priority = match reader.long()? {
+            0 => Priority::Low,
+            1 => Priority::Normal,
+            2 => Priority::High,
+            3 => Priority::Critical,
+            _ => return Err(decode_error("unknown priority")),
+        };
+        let expires_at = reader.optional(Reader::timestamp)?;
+        let deliver_after = reader.optional(|reader| {
+            let millis = u64::try_from(reader.long()?).map_err(|_| decode_error("negative delay"))?;
+            Ok(Duration::from_millis(millis))
+        })?;
+        let deliver_at = reader.optional(Reader::timestamp)?;
+        let retry = reader.optional(|reader| {
+            let attempt = reader.u32()?;
+            let mut retry = RetryInfo::new(reader.u32()?);
+            retry.attempt = attempt;
+            retry.last_error = reader.optional(Reader::string)?;
+            retry.next_retry_at = reader.optional(Reader::timestamp)?;
+            Ok(retry)
+        })?;
+
+        let mut envelope = QueueEnvelope::new(producer, TelegramQueueMessage::read(reader)?);
+        envelope.schema_version = schema_version;
+        envelope.message_id = message_id;
+        envelope.created_at = created_at;
+        envelope.correlation_id = correlation_id;
+        envelope.producer_info = producer_info;
+        envelope.traceparent = traceparent;
+        envelope.tracestate = tracestate;
+        envelope.routing_key = routing_key;
+        envelope.idempotency_key = idempotency_key;
+        envelope.priority = priority;
+        envelope.expires_at = expires_at;
+        envelope.deliver_after = deliver_after;
+        envelope.deliver_at = deliver_at;
+        envelope.retry = retry;
+        Ok(envelope)
+    }
+}
+
+/// Avro binary encoder.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct Writer(Vec<u8>);
+
+impl Writer {
+    fn long(&mut self, value: i64) {
+        let mut zigzag = ((value << 1) ^ (value >> 63)) as u64;
+        while zigzag >= 0x80 {
+            self.0.push((zigzag as u8 & 0x7f) | 0x80);
+            zigzag >>= 7;
+        }
+        self.0.push(zigzag as u8);
+    }
+
+    fn boolean(&mut self, value: bool) {
+        self.0.push(u8::from(value));
+    }
+
+    fn string(&mut self, value: &str) {
+        self.long(value.len() as i64);
+        self.0.extend_from_slice(value.as_bytes());
+    }
+
+    fn timestamp(&mut self, at: DateTime<Utc>) {
+        self.long(at.timestamp_micros());
+    }
+
+    /// Writes a `["null", T]` union.
+    fn optional<T>(&mut self, value: Option<&T>, write: impl FnOnce(&mut Self, &T)) {
+        match value {
+            None => self.long(0),
+            Some(value) => {
+                self.long(1);
+                write(self, value);
+            }
+        }
+    }
+
+    fn map(&mut self, map: &HashMap<String, String>) {
+        if !map.is_empty() {
+            self.long(map.len() as i64);
+            for (key, value) in map {
+                self.string(key);
+                self.string(value);
+            }
+        }
+        self.long(0);
+    }
+}
+
+/// Avro binary decoder.
+#[doc(hidden)]
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, SharedTypesError> {
+        let byte = *self
+            .bytes
+            .get(self.position)
+            .ok_or_else(|| decode_error("unexpected end of input"))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn long(&mut self) -> Result<i64, SharedTypesError> {
+        let mut zigzag = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            zigzag |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64));
+            }
+        }
+        Err(decode_error("variable length integer too long"))
+    }
+
+    fn u32(&mut self) -> Result<u32, SharedTypesError> {
+        u32::try_from(self.long()?).map_err(|_| decode_error("value out of range"))
+    }
+
+    fn boolean(&mut self) -> Result<bool, SharedTypesError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(decode_error("invalid boolean")),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SharedTypesError> {
+        let length = usize::try_from(self.long()?).map_err(|_| decode_error("negative length"))?;
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| decode_error("unexpected end of input"))?;
+        let value = std::str::from_utf8(&self.bytes[self.position..end])
+            .map_err(|_| decode_error("invalid UTF-8 string"))?
+            .to_string();
+        self.position = end;
+        Ok(value)
+    }
+
+    fn timestamp(&mut self) -> Result<DateTime<Utc>, SharedTypesError> {
+        DateTime::from_timestamp_micros(self.long()?).ok_or_else(|| decode_error("timestamp out of range"))
+    }
+
+    /// Reads a `["null", T]` union.
+    fn optional<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<T, SharedTypesError>,
+    ) -> Result<Option<T>, SharedTypesError> {
+        match self.long()? {
+            0 => Ok(None),
+            1 => read(self).map(Some),
+            _ => Err(decode_error("invalid union branch")),
+        }
+    }
+
+    fn map(&mut self) -> Result<HashMap<String, String>, SharedTypesError> {
+        let mut map = HashMap::new();
+        loop {
+            let count = match self.long()? {
+                0 => return Ok(map),
+                count if count < 0 => {
+                    // A negative count is followed by the block size in bytes.
+                    self.long()?;
+                    count.unsigned_abs()
+                }
+                count => count.unsigned_abs(),
+            };
+            for _ in 0..count {
+                let key = self.string()?;
+                map.insert(key, self.string()?);
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct DecodeError(&'static str);
+
+fn decode_error(reason: &'static str) -> SharedTypesError {
+    SharedTypesError::Serialization {
+        format: "avro",
+        source: Box::new(DecodeError(reason)),
+    }
+}
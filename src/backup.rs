@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Result of the integrity check run on a snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum VerificationStatus {
+    /// The job does not verify snapshots, or skipped it on this run.
+    #[default]
+    NotVerified,
+    Passed,
+    Failed,
+}
+
+impl VerificationStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            VerificationStatus::NotVerified => "not verified",
+            VerificationStatus::Passed => "verified",
+            VerificationStatus::Failed => "verification failed",
+        }
+    }
+}
+
+/// Snapshots kept and pruned by the retention policy after the backup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct RetentionInfo {
+    pub kept: u32,
+    pub pruned: u32,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "oldest_kept"))]
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub oldest_kept: Option<DateTime<Utc>>,
+}
+
+/// Report of a backup run, emitted by the nightly backup jobs of every server.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use shared_types::MessageStatus;
+/// use shared_types::backup::{BackupReport, RetentionInfo, VerificationStatus};
+///
+/// let report = BackupReport::new("db01:/var/lib/postgres", "a1b2c3d4")
+///     .with_size(3 * 1024 * 1024 * 1024)
+///     .with_duration(Duration::from_secs(245))
+///     .with_verification(VerificationStatus::Failed)
+///     .with_retention(RetentionInfo { kept: 14, pruned: 1, oldest_kept: None });
+/// assert_eq!(report.severity(), MessageStatus::Error);
+/// assert_eq!(
+///     report.to_telegram_message(123).message,
+///     "🚨 - <i>backup</i>\nsnapshot <code>a1b2c3d4</code> of db01:/var/lib/postgres: verification failed\n\
+///      3.0 GiB in 4m 05s, 14 snapshots kept, 1 pruned"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BackupReport {
+    /// What was backed up, e.g. `host:/path` or a database name.
+    pub target: String,
+    #[cfg_attr(feature = "serde", serde(alias = "snapshot_id"))]
+    pub snapshot_id: String,
+    #[cfg_attr(feature = "serde", serde(default, alias = "size_bytes"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub size_bytes: u64,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u64>"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<u64>))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "durationMs",
+            with = "crate::duration_ms::option",
+            skip_serializing_if = "Option::is_none",
+            alias = "duration_ms"
+        )
+    )]
+    pub duration: Option<Duration>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub verification: VerificationStatus,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub retention: Option<RetentionInfo>,
+    /// Error of a backup that did not complete.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub error: Option<String>,
+}
+
+impl BackupReport {
+    pub fn new(target: impl Into<String>, snapshot_id: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            snapshot_id: snapshot_id.into(),
+            size_bytes: 0,
+            duration: None,
+            verification: VerificationStatus::default(),
+            retention: None,
+            error: None,
+        }
+    }
+
+    pub fn with_size(mut self, size_bytes: u64) -> Self {
+        self.size_bytes = size_bytes;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn with_verification(mut self, verification: VerificationStatus) -> Self {
+        self.verification = verification;
+        self
+    }
+
+    pub fn with_retention(mut self, retention: RetentionInfo) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+
+    /// `Error` when the backup failed or its verification did, `Ok` otherwise.
+    pub fn severity(&self) -> MessageStatus {
+        if self.error.is_some() || self.verification == VerificationStatus::Failed {
+            MessageStatus::Error
+        } else {
+            MessageStatus::Ok
+        }
+    }
+
+    /// Formats the report for the `backup` job: snapshot and verification as
+    /// summary, size, duration and retention as content, the error in a `<pre>` block.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let outcome = if self.error.is_some() { "failed" } else { self.verification.label() };
+        let summary = format!(
+            "snapshot <code>{}</code> of {}: {}",
+            render::escape_text(&self.snapshot_id),
+            render::escape_text(&self.target),
+            outcome
+        );
+        let mut content = render::bytes(self.size_bytes);
+        if let Some(duration) = self.duration {
+            content.push_str(&format!(" in {}", render::duration(duration)));
+        }
+        if let Some(retention) = &self.retention {
+            content.push_str(&format!(", {} snapshots kept, {} pruned", retention.kept, retention.pruned));
+        }
+        let mut builder = TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("backup")
+            .summary(summary)
+            .content(content);
+        if let Some(error) = &self.error {
+            builder = builder.code_block(error);
+        }
+        builder.build()
+    }
+}
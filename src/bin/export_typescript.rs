@@ -0,0 +1,13 @@
+//! Writes the TypeScript declarations of the queue types, for producers
+//! written in TypeScript: `export-typescript [path]`, by default
+//! `shared_types.d.ts`.
+
+use std::{env, fs, process};
+
+fn main() {
+    let path = env::args().nth(1).unwrap_or_else(|| "shared_types.d.ts".to_string());
+    if let Err(err) = fs::write(&path, shared_types::schema::typescript_declarations()) {
+        eprintln!("cannot write {}: {}", path, err);
+        process::exit(1);
+    }
+}
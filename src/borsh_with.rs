@@ -0,0 +1,107 @@
+//! Borsh encodings of the foreign types held by the core queue types, for
+//! `#[borsh(serialize_with, deserialize_with)]`: timestamps as seconds and
+//! nanoseconds since the epoch, durations as seconds and nanoseconds, uuids as
+//! their 16 bytes, time zones by IANA name and times of day as seconds and
+//! nanoseconds since midnight.
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::time::Duration;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use chrono::{DateTime, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+pub(crate) mod timestamp {
+    use super::*;
+
+    pub(crate) fn serialize<W: Write>(timestamp: &DateTime<Utc>, writer: &mut W) -> Result<()> {
+        (timestamp.timestamp(), timestamp.timestamp_subsec_nanos()).serialize(writer)
+    }
+
+    pub(crate) fn deserialize<R: Read>(reader: &mut R) -> Result<DateTime<Utc>> {
+        let (secs, nanos) = <(i64, u32)>::deserialize_reader(reader)?;
+        DateTime::from_timestamp(secs, nanos).ok_or_else(|| invalid("timestamp out of range"))
+    }
+}
+
+pub(crate) mod option_timestamp {
+    use super::*;
+
+    pub(crate) fn serialize<W: Write>(
+        timestamp: &Option<DateTime<Utc>>,
+        writer: &mut W,
+    ) -> Result<()> {
+        timestamp
+            .map(|timestamp| (timestamp.timestamp(), timestamp.timestamp_subsec_nanos()))
+            .serialize(writer)
+    }
+
+    pub(crate) fn deserialize<R: Read>(reader: &mut R) -> Result<Option<DateTime<Utc>>> {
+        <Option<(i64, u32)>>::deserialize_reader(reader)?
+            .map(|(secs, nanos)| {
+                DateTime::from_timestamp(secs, nanos)
+                    .ok_or_else(|| invalid("timestamp out of range"))
+            })
+            .transpose()
+    }
+}
+
+pub(crate) mod option_duration {
+    use super::*;
+
+    pub(crate) fn serialize<W: Write>(duration: &Option<Duration>, writer: &mut W) -> Result<()> {
+        duration
+            .map(|duration| (duration.as_secs(), duration.subsec_nanos()))
+            .serialize(writer)
+    }
+
+    pub(crate) fn deserialize<R: Read>(reader: &mut R) -> Result<Option<Duration>> {
+        Ok(<Option<(u64, u32)>>::deserialize_reader(reader)?
+            .map(|(secs, nanos)| Duration::new(secs, nanos)))
+    }
+}
+
+pub(crate) mod uuid_bytes {
+    use super::*;
+
+    pub(crate) fn serialize<W: Write>(uuid: &Uuid, writer: &mut W) -> Result<()> {
+        uuid.as_bytes().serialize(writer)
+    }
+
+    pub(crate) fn deserialize<R: Read>(reader: &mut R) -> Result<Uuid> {
+        Ok(Uuid::from_bytes(<[u8; 16]>::deserialize_reader(reader)?))
+    }
+}
+
+pub(crate) mod tz {
+    use super::*;
+
+    pub(crate) fn serialize<W: Write>(tz: &Tz, writer: &mut W) -> Result<()> {
+        tz.name().serialize(writer)
+    }
+
+    pub(crate) fn deserialize<R: Read>(reader: &mut R) -> Result<Tz> {
+        String::deserialize_reader(reader)?
+            .parse()
+            .map_err(|_| invalid("unknown time zone"))
+    }
+}
+
+pub(crate) mod time {
+    use super::*;
+
+    pub(crate) fn serialize<W: Write>(time: &NaiveTime, writer: &mut W) -> Result<()> {
+        (time.num_seconds_from_midnight(), time.nanosecond()).serialize(writer)
+    }
+
+    pub(crate) fn deserialize<R: Read>(reader: &mut R) -> Result<NaiveTime> {
+        let (secs, nanos) = <(u32, u32)>::deserialize_reader(reader)?;
+        NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos)
+            .ok_or_else(|| invalid("time of day out of range"))
+    }
+}
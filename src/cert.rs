@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Days before expiry from which a certificate is a warning.
+pub const CERT_WARN_DAYS: i64 = 21;
+
+/// Days before expiry from which a certificate is an error.
+pub const CERT_ERROR_DAYS: i64 = 7;
+
+/// Expiry of a TLS certificate, reported by the TLS watch job.
+///
+/// ```rust
+/// use chrono::{Duration, TimeZone, Utc};
+/// use shared_types::MessageStatus;
+/// use shared_types::cert::CertExpiryAlert;
+///
+/// let now = Utc.with_ymd_and_hms(2024, 5, 17, 3, 0, 0).unwrap();
+/// let alert = CertExpiryAlert::new("shop.example.com", "R3", now + Duration::days(5), "shop.example.com:443", now);
+/// assert_eq!(alert.days_remaining, 5);
+/// assert_eq!(alert.severity(), MessageStatus::Error);
+/// assert_eq!(
+///     alert.to_telegram_message(123).message,
+///     "🚨 - <i>tls</i>\nshop.example.com expires in 5 days (2024-05-22)\nissuer R3, seen on shop.example.com:443"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CertExpiryAlert {
+    pub domain: String,
+    pub issuer: String,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "not_after"))]
+    pub not_after: DateTime<Utc>,
+    /// Whole days left when the certificate was checked, negative once expired.
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "days_remaining"))]
+    pub days_remaining: i64,
+    /// Where the certificate was found, e.g. `host:port` or a file path.
+    pub source: String,
+}
+
+impl CertExpiryAlert {
+    /// Creates the alert of a certificate checked at `now`.
+    pub fn new(
+        domain: impl Into<String>,
+        issuer: impl Into<String>,
+        not_after: DateTime<Utc>,
+        source: impl Into<String>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            domain: domain.into(),
+            issuer: issuer.into(),
+            not_after,
+            days_remaining: (not_after - now).num_days(),
+            source: source.into(),
+        }
+    }
+
+    /// Returns true when the certificate had expired when checked.
+    pub fn is_expired(&self) -> bool {
+        self.days_remaining < 0
+    }
+
+    /// `Error` under [`CERT_ERROR_DAYS`], `Warn` under [`CERT_WARN_DAYS`], `Ok` otherwise.
+    pub fn severity(&self) -> MessageStatus {
+        if self.days_remaining < CERT_ERROR_DAYS {
+            MessageStatus::Error
+        } else if self.days_remaining < CERT_WARN_DAYS {
+            MessageStatus::Warn
+        } else {
+            MessageStatus::Ok
+        }
+    }
+
+    /// Formats the alert for the `tls` job, deduplicated per domain and day count.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let domain = render::escape_text(&self.domain);
+        let date = self.not_after.format("%Y-%m-%d");
+        let summary = match self.days_remaining {
+            days if days < 0 => format!("{} expired {} days ago ({})", domain, -days, date),
+            0 => format!("{} expires today ({})", domain, date),
+            1 => format!("{} expires tomorrow ({})", domain, date),
+            days => format!("{} expires in {} days ({})", domain, days, date),
+        };
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("tls")
+            .summary(summary)
+            .content(format!(
+                "issuer {}, seen on {}",
+                render::escape_text(&self.issuer),
+                render::escape_text(&self.source)
+            ))
+            .dedup_key(format!("cert:{}:{}", self.domain, self.days_remaining))
+            .build()
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the environment variables read by [`ChatDirectory::from_env`].
+pub const DEFAULT_ENV_PREFIX: &str = "TELEGRAM_CHAT_";
+
+/// Maps logical channels ("ops", "billing", "oncall") to Telegram chat ids.
+///
+/// Serializes as a plain JSON object, e.g. `{"ops": -100123, "oncall": 42}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(type = "Record<string, number>"))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct ChatDirectory {
+    channels: HashMap<String, i64>,
+}
+
+impl ChatDirectory {
+    /// Creates an empty ChatDirectory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a channel, returning the directory for chaining.
+    pub fn with_channel(mut self, channel: impl Into<String>, chat_id: i64) -> Self {
+        self.insert(channel, chat_id);
+        self
+    }
+
+    /// Adds or replaces a channel, returning the previous chat id if any.
+    pub fn insert(&mut self, channel: impl Into<String>, chat_id: i64) -> Option<i64> {
+        self.channels.insert(channel.into(), chat_id)
+    }
+
+    /// Returns the chat id of a channel.
+    pub fn chat_id(&self, channel: &str) -> Option<i64> {
+        self.channels.get(channel).copied()
+    }
+
+    /// Returns the channel names and chat ids.
+    pub fn channels(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.channels.iter().map(|(channel, chat_id)| (channel.as_str(), *chat_id))
+    }
+
+    /// Loads a directory from a JSON object of channel names to chat ids.
+    ///
+    /// ```rust
+    /// use shared_types::ChatDirectory;
+    ///
+    /// let directory = ChatDirectory::from_json(r#"{"ops": -100123, "oncall": 42}"#).unwrap();
+    /// assert_eq!(directory.chat_id("ops"), Some(-100123));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Loads a directory from `TELEGRAM_CHAT_<CHANNEL>=<chat id>` environment variables.
+    ///
+    /// See [`ChatDirectory::from_env_prefix`].
+    pub fn from_env() -> Result<Self, ChatDirectoryError> {
+        Self::from_env_prefix(DEFAULT_ENV_PREFIX)
+    }
+
+    /// Loads a directory from environment variables starting with `prefix`.
+    ///
+    /// The rest of the variable name, lowercased, is the channel name: with the
+    /// default prefix `TELEGRAM_CHAT_OPS=-100123` maps `ops` to `-100123`.
+    /// Variables whose name is not UTF-8 are skipped; a value that is not
+    /// UTF-8 is an invalid chat id.
+    pub fn from_env_prefix(prefix: &str) -> Result<Self, ChatDirectoryError> {
+        let mut directory = Self::new();
+        for (variable, value) in env::vars_os() {
+            let Some(variable) = variable.to_str() else {
+                continue;
+            };
+            let Some(channel) = variable.strip_prefix(prefix) else {
+                continue;
+            };
+            if channel.is_empty() {
+                continue;
+            }
+
+            let invalid = || ChatDirectoryError::InvalidChatId {
+                variable: variable.to_string(),
+                value: value.to_string_lossy().into_owned(),
+            };
+            let chat_id = value.to_str().ok_or_else(invalid)?.trim().parse().map_err(|_| invalid())?;
+            directory.insert(channel.to_lowercase(), chat_id);
+        }
+        Ok(directory)
+    }
+}
+
+/// Documented as the plain JSON object it serializes to.
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for ChatDirectory {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        HashMap::<String, i64>::schema()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for ChatDirectory {}
+
+/// Error returned when loading a [`ChatDirectory`] from the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChatDirectoryError {
+    /// A variable did not contain a valid chat id.
+    InvalidChatId { variable: String, value: String },
+}
+
+impl fmt::Display for ChatDirectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChatDirectoryError::InvalidChatId { variable, value } => {
+                write!(f, "invalid chat id {:?} in {}", value, variable)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChatDirectoryError {}
@@ -0,0 +1,159 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Algorithm used to compress a [`CompressedPayload`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CompressionAlgorithm {
+    /// Stored as is, only base64 encoded.
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// A serialized payload compressed to fit the broker frame size.
+///
+/// The payload is serialized to JSON, compressed with `algorithm` and base64
+/// encoded into `data`. `original_size` is the length of the JSON in bytes and
+/// bounds decompression. The compress/decompress helpers require the
+/// `compression` feature; the type itself is always available so it can be
+/// embedded and forwarded by services that never unpack it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CompressedPayload {
+    pub algorithm: CompressionAlgorithm,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "original_size"))]
+    pub original_size: u64,
+    pub data: String,
+}
+
+#[cfg(feature = "compression")]
+impl CompressedPayload {
+    /// Serializes `value` to JSON and compresses it with `algorithm`.
+    ///
+    /// ```rust
+    /// use shared_types::compression::{CompressedPayload, CompressionAlgorithm};
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let msg = TelegramQueueMessage::new(123, "log line\n".repeat(1000), false);
+    /// let compressed = CompressedPayload::compress(&msg, CompressionAlgorithm::Zstd).unwrap();
+    /// assert!(compressed.data.len() < msg.message.len());
+    ///
+    /// let restored: TelegramQueueMessage = compressed.decompress().unwrap();
+    /// assert_eq!(restored.message, msg.message);
+    /// ```
+    pub fn compress<T: Serialize>(value: &T, algorithm: CompressionAlgorithm) -> crate::error::Result<Self> {
+        use std::io::Write;
+
+        use base64::Engine;
+
+        let json = serde_json::to_vec(value)?;
+        let compressed = match algorithm {
+            CompressionAlgorithm::None => Ok(json.clone()),
+            CompressionAlgorithm::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&json).and_then(|()| encoder.finish())
+            }
+            CompressionAlgorithm::Zstd => zstd::encode_all(json.as_slice(), 0),
+        }
+        .map_err(|err| algorithm.error(err))?;
+
+        Ok(Self {
+            algorithm,
+            original_size: json.len() as u64,
+            data: base64::engine::general_purpose::STANDARD.encode(compressed),
+        })
+    }
+
+    /// Decompresses and deserializes the payload.
+    ///
+    /// Fails with [`SharedTypesError::Serialization`] when the data is not valid
+    /// base64, cannot be decompressed, or when the decompressed size does not
+    /// match `original_size`.
+    ///
+    /// ```rust
+    /// use shared_types::compression::{CompressedPayload, CompressionAlgorithm};
+    /// use shared_types::error::ErrorCode;
+    ///
+    /// let mut compressed = CompressedPayload::compress(&"hello", CompressionAlgorithm::Gzip).unwrap();
+    /// compressed.original_size += 1;
+    /// let err = compressed.decompress::<String>().unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::Serialization);
+    /// assert_eq!(err.to_string(), "gzip serialization failed: decompressed size 7 does not match original size 8");
+    /// ```
+    ///
+    /// [`SharedTypesError::Serialization`]: crate::error::SharedTypesError::Serialization
+    pub fn decompress<T: serde::de::DeserializeOwned>(&self) -> crate::error::Result<T> {
+        use std::io::{Error, ErrorKind, Read};
+
+        use base64::Engine;
+
+        use crate::error::SharedTypesError;
+
+        let compressed = base64::engine::general_purpose::STANDARD
+            .decode(&self.data)
+            .map_err(|err| SharedTypesError::Serialization {
+                format: "base64",
+                source: Box::new(err),
+            })?;
+
+        // Read at most one byte past the announced size to detect mismatches
+        // without inflating arbitrarily large payloads.
+        let limit = self.original_size.saturating_add(1);
+        let mut json = Vec::new();
+        match self.algorithm {
+            CompressionAlgorithm::None => {
+                json = compressed;
+                Ok(0)
+            }
+            CompressionAlgorithm::Gzip => flate2::read::GzDecoder::new(compressed.as_slice())
+                .take(limit)
+                .read_to_end(&mut json),
+            CompressionAlgorithm::Zstd => zstd::Decoder::new(compressed.as_slice())
+                .and_then(|decoder| decoder.take(limit).read_to_end(&mut json)),
+        }
+        .map_err(|err| self.algorithm.error(err))?;
+
+        if json.len() as u64 != self.original_size {
+            return Err(self.algorithm.error(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "decompressed size {} does not match original size {}",
+                    json.len(),
+                    self.original_size
+                ),
+            )));
+        }
+
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
+
+#[cfg(feature = "compression")]
+impl CompressionAlgorithm {
+    /// Wraps an error of the algorithm in [`crate::error::SharedTypesError::Serialization`].
+    fn error(self, err: std::io::Error) -> crate::error::SharedTypesError {
+        let format = match self {
+            CompressionAlgorithm::None => "base64",
+            CompressionAlgorithm::Gzip => "gzip",
+            CompressionAlgorithm::Zstd => "zstd",
+        };
+        crate::error::SharedTypesError::Serialization {
+            format,
+            source: Box::new(err),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::envelope::QueueEnvelope;
+use crate::retry::RetryInfo;
+
+/// What a consumer decided to do with a message it processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "outcome", rename_all = "camelCase"))]
+pub enum ConsumeOutcome {
+    /// Processed successfully, remove it from the queue.
+    Ack,
+    /// Failed, put it back on the queue after `delay`.
+    NackRequeue {
+        #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+        #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+        #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+        #[cfg_attr(feature = "serde", serde(rename = "delayMs", with = "crate::duration_ms"))]
+        delay: Duration,
+    },
+    /// Failed for good, move it to the dead letter queue.
+    NackDeadLetter { reason: String },
+}
+
+impl ConsumeOutcome {
+    /// Picks the outcome of a failed attempt from the retry state.
+    ///
+    /// Requeues after the backoff delay while attempts remain, otherwise
+    /// dead-letters with `error` as the reason. `retry` should already include
+    /// the failed attempt, see [`RetryInfo::record_failure`].
+    ///
+    /// ```rust
+    /// use chrono::Utc;
+    /// use shared_types::consumer::ConsumeOutcome;
+    /// use shared_types::retry::RetryInfo;
+    ///
+    /// let mut retry = RetryInfo::new(1);
+    /// retry.record_failure("bot blocked", Utc::now());
+    /// assert_eq!(
+    ///     ConsumeOutcome::from_failure(&retry, "bot blocked"),
+    ///     ConsumeOutcome::NackDeadLetter { reason: "bot blocked".into() }
+    /// );
+    /// ```
+    pub fn from_failure(retry: &RetryInfo, error: impl Into<String>) -> Self {
+        if retry.should_retry() {
+            ConsumeOutcome::NackRequeue {
+                delay: retry.backoff_delay(),
+            }
+        } else {
+            ConsumeOutcome::NackDeadLetter {
+                reason: error.into(),
+            }
+        }
+    }
+
+    /// Returns true for [`ConsumeOutcome::Ack`].
+    pub fn is_ack(&self) -> bool {
+        matches!(self, ConsumeOutcome::Ack)
+    }
+}
+
+/// The processing outcome a consumer reports to its runner for one message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ConsumerResult {
+    /// Id of the processed message.
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "message_id"))]
+    pub message_id: Uuid,
+    /// Name of the consumer that processed the message.
+    pub consumer: String,
+    /// The decision taken.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub outcome: ConsumeOutcome,
+    /// When processing finished.
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "processed_at"))]
+    pub processed_at: DateTime<Utc>,
+}
+
+impl ConsumerResult {
+    /// Creates a result for the message with id `message_id`, processed now.
+    pub fn new(message_id: Uuid, consumer: impl Into<String>, outcome: ConsumeOutcome) -> Self {
+        Self {
+            message_id,
+            consumer: consumer.into(),
+            outcome,
+            processed_at: Utc::now(),
+        }
+    }
+
+    /// Creates a result for the message in `envelope`, processed now.
+    pub fn for_envelope<T>(
+        envelope: &QueueEnvelope<T>,
+        consumer: impl Into<String>,
+        outcome: ConsumeOutcome,
+    ) -> Self {
+        Self::new(envelope.message_id, consumer, outcome)
+    }
+}
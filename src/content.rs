@@ -0,0 +1,284 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::slack::{SlackBlock, SlackText, escape_mrkdwn};
+use crate::telegram::MessageStatus;
+
+/// A piece of body text, rendered with each channel's own markup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "type", content = "text", rename_all = "camelCase"))]
+pub enum ContentSegment {
+    Text(String),
+    Bold(String),
+    Italic(String),
+    /// Inline code.
+    Code(String),
+    /// A preformatted block on its own lines.
+    CodeBlock(String),
+}
+
+/// A labelled value, e.g. `Host: ftp01`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ContentField {
+    pub label: String,
+    pub value: String,
+}
+
+/// A link rendered after the fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ContentLink {
+    pub label: String,
+    pub url: String,
+}
+
+/// A channel-agnostic notification: producers describe the message once and
+/// each channel renders it with idiomatic formatting.
+///
+/// Text is plain: every renderer escapes it for its own markup.
+///
+/// ```rust
+/// use shared_types::content::NotificationContent;
+/// use shared_types::MessageStatus;
+///
+/// let content = NotificationContent::new(MessageStatus::Error, "Backup failed")
+///     .text("disk ")
+///     .code("/dev/sda1")
+///     .text(" is <full>")
+///     .field("Host", "ftp01")
+///     .link("Logs", "https://logs.example.com/backup");
+///
+/// assert_eq!(
+///     content.render_telegram(),
+///     "🚨 - <b>Backup failed</b>\ndisk <code>/dev/sda1</code> is &lt;full&gt;\n\n\
+///      <b>Host</b>: ftp01\n\n<a href=\"https://logs.example.com/backup\">Logs</a>"
+/// );
+/// assert_eq!(
+///     content.render_plain(),
+///     "🚨 Backup failed\ndisk /dev/sda1 is <full>\n\nHost: ftp01\n\nLogs: https://logs.example.com/backup"
+/// );
+/// assert_eq!(content.render_slack().len(), 3);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct NotificationContent {
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    pub title: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub body: Vec<ContentSegment>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub fields: Vec<ContentField>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub links: Vec<ContentLink>,
+}
+
+impl NotificationContent {
+    pub fn new(status: MessageStatus, title: impl Into<String>) -> Self {
+        Self {
+            status,
+            title: title.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Appends a body segment.
+    pub fn segment(mut self, segment: ContentSegment) -> Self {
+        self.body.push(segment);
+        self
+    }
+
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.segment(ContentSegment::Text(text.into()))
+    }
+
+    pub fn bold(self, text: impl Into<String>) -> Self {
+        self.segment(ContentSegment::Bold(text.into()))
+    }
+
+    pub fn italic(self, text: impl Into<String>) -> Self {
+        self.segment(ContentSegment::Italic(text.into()))
+    }
+
+    pub fn code(self, text: impl Into<String>) -> Self {
+        self.segment(ContentSegment::Code(text.into()))
+    }
+
+    pub fn code_block(self, text: impl Into<String>) -> Self {
+        self.segment(ContentSegment::CodeBlock(text.into()))
+    }
+
+    pub fn field(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push(ContentField {
+            label: label.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    pub fn link(mut self, label: impl Into<String>, url: impl Into<String>) -> Self {
+        self.links.push(ContentLink {
+            label: label.into(),
+            url: url.into(),
+        });
+        self
+    }
+
+    /// Renders Telegram HTML: `{emoji} - <b>{title}</b>`, the body, then the
+    /// fields and links each in their own paragraph.
+    pub fn render_telegram(&self) -> String {
+        let header = match self.status {
+            MessageStatus::None => format!("<b>{}</b>", escape_html(&self.title)),
+            status => format!("{} - <b>{}</b>", status.emoji(), escape_html(&self.title)),
+        };
+        let body = self.render_body(|segment| match segment {
+            ContentSegment::Text(text) => escape_html(text),
+            ContentSegment::Bold(text) => format!("<b>{}</b>", escape_html(text)),
+            ContentSegment::Italic(text) => format!("<i>{}</i>", escape_html(text)),
+            ContentSegment::Code(text) => format!("<code>{}</code>", escape_html(text)),
+            ContentSegment::CodeBlock(text) => format!("<pre>{}</pre>", escape_html(text)),
+        });
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| format!("<b>{}</b>: {}", escape_html(&field.label), escape_html(&field.value)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let links = self
+            .links
+            .iter()
+            .map(|link| {
+                format!(
+                    "<a href=\"{}\">{}</a>",
+                    render::escape_attribute(&link.url),
+                    escape_html(&link.label)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" · ");
+        join_parts(vec![format!("{}\n{}", header, body), fields, links])
+    }
+
+    /// Renders Slack blocks: a section with the title and body, a section with
+    /// the fields and a context with the links.
+    pub fn render_slack(&self) -> Vec<SlackBlock> {
+        let header = match self.status {
+            MessageStatus::None => format!("*{}*", escape_mrkdwn(&self.title)),
+            status => format!("{} *{}*", status.emoji(), escape_mrkdwn(&self.title)),
+        };
+        let body = self.render_body(|segment| match segment {
+            ContentSegment::Text(text) => escape_mrkdwn(text),
+            ContentSegment::Bold(text) => format!("*{}*", escape_mrkdwn(text)),
+            ContentSegment::Italic(text) => format!("_{}_", escape_mrkdwn(text)),
+            ContentSegment::Code(text) => format!("`{}`", escape_mrkdwn(text)),
+            ContentSegment::CodeBlock(text) => format!("```\n{}\n```", escape_mrkdwn(text)),
+        });
+
+        let text = if body.is_empty() { header } else { format!("{}\n{}", header, body) };
+        let mut blocks = vec![SlackBlock::section(text)];
+        if !self.fields.is_empty() {
+            blocks.push(SlackBlock::Section {
+                text: None,
+                fields: self
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        SlackText::mrkdwn(format!(
+                            "*{}*\n{}",
+                            escape_mrkdwn(&field.label),
+                            escape_mrkdwn(&field.value)
+                        ))
+                    })
+                    .collect(),
+            });
+        }
+        if !self.links.is_empty() {
+            blocks.push(SlackBlock::Context {
+                elements: self
+                    .links
+                    .iter()
+                    .map(|link| SlackText::mrkdwn(format!("<{}|{}>", link.url, escape_mrkdwn(&link.label))))
+                    .collect(),
+            });
+        }
+        blocks
+    }
+
+    /// Renders plain text, for SMS, push notifications and email fallbacks.
+    pub fn render_plain(&self) -> String {
+        let header = match self.status {
+            MessageStatus::None => self.title.clone(),
+            status => format!("{} {}", status.emoji(), self.title),
+        };
+        let body = self.render_body(|segment| match segment {
+            ContentSegment::Text(text)
+            | ContentSegment::Bold(text)
+            | ContentSegment::Italic(text)
+            | ContentSegment::Code(text)
+            | ContentSegment::CodeBlock(text) => text.clone(),
+        });
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| format!("{}: {}", field.label, field.value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let links = self
+            .links
+            .iter()
+            .map(|link| format!("{}: {}", link.label, link.url))
+            .collect::<Vec<_>>()
+            .join("\n");
+        join_parts(vec![format!("{}\n{}", header, body), fields, links])
+    }
+
+    /// Renders the body segments inline, putting code blocks on their own lines.
+    fn render_body(&self, render: impl Fn(&ContentSegment) -> String) -> String {
+        let mut body = String::new();
+        for segment in &self.body {
+            let is_block = matches!(segment, ContentSegment::CodeBlock(_));
+            if is_block && !body.is_empty() && !body.ends_with('\n') {
+                body.push('\n');
+            }
+            body.push_str(&render(segment));
+            if is_block {
+                body.push('\n');
+            }
+        }
+        body.trim_end_matches('\n').to_string()
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    render::escape_text(text).into_owned()
+}
+
+/// Joins the non-empty parts as paragraphs separated by a blank line.
+fn join_parts(parts: Vec<String>) -> String {
+    parts
+        .into_iter()
+        .map(|part| part.trim_end_matches('\n').to_string())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
@@ -0,0 +1,253 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Years searched by [`CronSchedule::next_run_after`] before giving up on
+/// expressions that never match, e.g. `0 0 30 2 *`.
+const MAX_SEARCH_YEARS: i32 = 5;
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A standard five field cron expression: minute, hour, day of month, month
+/// and day of week, evaluated in UTC.
+///
+/// Fields accept `*`, values, `a-b` ranges, `/step` and comma separated
+/// lists; months and weekdays also accept three letter names, and Sunday is
+/// both `0` and `7`. The `@yearly`, `@monthly`, `@weekly`, `@daily` and
+/// `@hourly` shorthands are supported. As in Vixie cron, when both day of
+/// month and day of week are restricted a day matching either one runs.
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use shared_types::cron::CronSchedule;
+///
+/// let schedule: CronSchedule = "30 2 * * mon-fri".parse().unwrap();
+/// let friday = Utc.with_ymd_and_hms(2024, 5, 17, 3, 0, 0).unwrap();
+/// assert_eq!(
+///     schedule.next_run_after(friday),
+///     Some(Utc.with_ymd_and_hms(2024, 5, 20, 2, 30, 0).unwrap())
+/// );
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// assert_eq!(serde_json::to_string(&schedule).unwrap(), "\"30 2 * * mon-fri\"");
+/// # }
+/// assert!("61 * * * *".parse::<CronSchedule>().is_err());
+///
+/// let never = CronSchedule::parse("0 0 30 2 *").unwrap();
+/// assert_eq!(never.next_run_after(friday), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "ts-rs", ts(as = "String"))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parses and validates a cron expression.
+    pub fn parse(expression: &str) -> Result<Self, CronError> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(CronError::FieldCount(fields.len()));
+        };
+        let days_of_week = parse_field(day_of_week, "day of week", 0, 7, WEEKDAY_NAMES)?;
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            minutes: parse_field(minute, "minute", 0, 59, &[])?,
+            hours: parse_field(hour, "hour", 0, 23, &[])? as u32,
+            days_of_month: parse_field(day_of_month, "day of month", 1, 31, &[])? as u32,
+            months: parse_field(month, "month", 1, 12, MONTH_NAMES)? as u16,
+            // Sunday is both 0 and 7.
+            days_of_week: ((days_of_week | days_of_week >> 7) & 0x7f) as u8,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        })
+    }
+
+    /// Returns the expression as written.
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns the first run strictly after `after`, at a whole minute, or
+    /// `None` when the expression matches no date in the next years.
+    pub fn next_run_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.naive_utc().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let last_year = start.year() + MAX_SEARCH_YEARS;
+        let mut time = start;
+        while time.year() <= last_year {
+            if !bit(self.months.into(), time.month()) {
+                time = first_of_next_month(time.date())?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !bit(self.hours.into(), time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !bit(self.minutes, time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time.and_utc());
+            }
+        }
+        None
+    }
+
+    /// Returns true when `time`, truncated to the minute, matches the expression.
+    pub fn matches(&self, time: DateTime<Utc>) -> bool {
+        let time: NaiveDateTime = time.naive_utc();
+        bit(self.months.into(), time.month())
+            && self.matches_day(time.date())
+            && bit(self.hours.into(), time.hour())
+            && bit(self.minutes, time.minute())
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = bit(self.days_of_month.into(), date.day());
+        let day_of_week = bit(self.days_of_week.into(), date.weekday().num_days_from_sunday());
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        CronSchedule::parse(expression)
+    }
+}
+
+impl TryFrom<String> for CronSchedule {
+    type Error = CronError;
+
+    fn try_from(expression: String) -> Result<Self, Self::Error> {
+        CronSchedule::parse(&expression)
+    }
+}
+
+impl From<CronSchedule> for String {
+    fn from(schedule: CronSchedule) -> Self {
+        schedule.expression
+    }
+}
+
+/// Documented as the expression string it serializes to.
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for CronSchedule {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        String::schema()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for CronSchedule {}
+
+/// Error returned when a cron expression is not valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CronError {
+    /// The expression does not have five fields.
+    FieldCount(usize),
+    /// A field contains an unknown name, a value out of range or a bad step.
+    InvalidField { field: &'static str, value: String },
+}
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CronError::FieldCount(count) => write!(f, "expected 5 cron fields, found {}", count),
+            CronError::InvalidField { field, value } => write!(f, "invalid cron {} {:?}", field, value),
+        }
+    }
+}
+
+impl std::error::Error for CronError {}
+
+/// Parses a field into a bit set of the allowed values.
+fn parse_field(value: &str, field: &'static str, min: u32, max: u32, names: &[&str]) -> Result<u64, CronError> {
+    let invalid = || CronError::InvalidField {
+        field,
+        value: value.to_string(),
+    };
+    let number = |part: &str| -> Result<u32, CronError> {
+        let lower = part.to_ascii_lowercase();
+        let number = match names.iter().position(|name| *name == lower) {
+            Some(index) => index as u32 + min,
+            None => part.parse().map_err(|_| invalid())?,
+        };
+        (min..=max).contains(&number).then_some(number).ok_or_else(invalid)
+    };
+
+    let mut set = 0u64;
+    for part in value.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|step| *step > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (number(start)?, number(end)?),
+                // `5/15` means from 5 to the end of the range.
+                None if part.contains('/') => (number(range)?, max),
+                None => {
+                    let value = number(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    match date.month() {
+        12 => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1),
+        month => NaiveDate::from_ymd_opt(date.year(), month + 1, 1),
+    }
+}
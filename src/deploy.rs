@@ -0,0 +1,184 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Emoji shown on deploy announcements instead of the status emoji, except on failures.
+pub const DEPLOY_EMOJI: &str = "🛰️";
+
+/// Environment a service is deployed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Environment {
+    Development,
+    Staging,
+    Production,
+}
+
+impl Environment {
+    /// Returns the environment name shown in messages.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Development => "development",
+            Environment::Staging => "staging",
+            Environment::Production => "production",
+        }
+    }
+}
+
+/// Stage of a deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum DeploymentOutcome {
+    Started,
+    Succeeded,
+    Failed,
+}
+
+/// A deployment announcement, identical across repositories.
+///
+/// ```rust
+/// use shared_types::deploy::{DeploymentEvent, DeploymentOutcome, Environment};
+///
+/// let event = DeploymentEvent::new("ftp-sync", "1.4.2", Environment::Production, DeploymentOutcome::Succeeded)
+///     .with_commit("9fceb02d0ae598e95dc970b74767f19372d61af8")
+///     .with_deployer("alice")
+///     .with_pipeline_url("https://ci.example.com/pipelines/42");
+/// assert_eq!(
+///     event.to_telegram_message(123).message,
+///     "🛰️ - <i>ftp-sync</i>\ndeployed <b>1.4.2</b> to production\n\
+///      commit <code>9fceb02</code> by alice, <a href=\"https://ci.example.com/pipelines/42\">pipeline</a>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DeploymentEvent {
+    pub service: String,
+    pub version: String,
+    pub environment: Environment,
+    pub outcome: DeploymentOutcome,
+    /// Whether the deployment goes back to an earlier version.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rollback: bool,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub commit: Option<String>,
+    /// User or automation that started the deployment.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub deployer: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "pipeline_url"))]
+    pub pipeline_url: Option<String>,
+}
+
+impl DeploymentEvent {
+    pub fn new(
+        service: impl Into<String>,
+        version: impl Into<String>,
+        environment: Environment,
+        outcome: DeploymentOutcome,
+    ) -> Self {
+        Self {
+            service: service.into(),
+            version: version.into(),
+            environment,
+            outcome,
+            rollback: false,
+            commit: None,
+            deployer: None,
+            pipeline_url: None,
+        }
+    }
+
+    /// Marks the deployment as a rollback.
+    pub fn rollback(mut self) -> Self {
+        self.rollback = true;
+        self
+    }
+
+    pub fn with_commit(mut self, commit: impl Into<String>) -> Self {
+        self.commit = Some(commit.into());
+        self
+    }
+
+    pub fn with_deployer(mut self, deployer: impl Into<String>) -> Self {
+        self.deployer = Some(deployer.into());
+        self
+    }
+
+    pub fn with_pipeline_url(mut self, pipeline_url: impl Into<String>) -> Self {
+        self.pipeline_url = Some(pipeline_url.into());
+        self
+    }
+
+    /// `Error` for failures, `Warn` for completed rollbacks, `Ok` for
+    /// completed deployments and `Info` when starting.
+    pub fn severity(&self) -> MessageStatus {
+        match self.outcome {
+            DeploymentOutcome::Started => MessageStatus::Info,
+            DeploymentOutcome::Succeeded if self.rollback => MessageStatus::Warn,
+            DeploymentOutcome::Succeeded => MessageStatus::Ok,
+            DeploymentOutcome::Failed => MessageStatus::Error,
+        }
+    }
+
+    /// Formats the announcement with the service as job name and the commit,
+    /// deployer and pipeline link as content.
+    ///
+    /// Uses [`DEPLOY_EMOJI`] unless the deployment failed.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let version = format!("<b>{}</b>", render::escape_text(&self.version));
+        let environment = self.environment.as_str();
+        let summary = match (self.outcome, self.rollback) {
+            (DeploymentOutcome::Started, false) => format!("deploying {} to {}", version, environment),
+            (DeploymentOutcome::Started, true) => format!("rolling back {} to {}", environment, version),
+            (DeploymentOutcome::Succeeded, false) => format!("deployed {} to {}", version, environment),
+            (DeploymentOutcome::Succeeded, true) => format!("rolled back {} to {}", environment, version),
+            (DeploymentOutcome::Failed, false) => format!("failed to deploy {} to {}", version, environment),
+            (DeploymentOutcome::Failed, true) => {
+                format!("failed to roll back {} to {}", environment, version)
+            }
+        };
+
+        let mut details = Vec::new();
+        if let Some(commit) = &self.commit {
+            let short: String = commit.chars().take(7).collect();
+            details.push(format!("commit <code>{}</code>", render::escape_text(&short)));
+        }
+        if let Some(deployer) = &self.deployer {
+            details.push(format!("by {}", render::escape_text(deployer)));
+        }
+        let mut content = details.join(" ");
+        if let Some(pipeline_url) = &self.pipeline_url {
+            let link = format!(
+                "<a href=\"{}\">pipeline</a>",
+                render::escape_attribute(pipeline_url)
+            );
+            content = if content.is_empty() { link } else { format!("{}, {}", content, link) };
+        }
+
+        let mut builder = TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name(&self.service)
+            .summary(summary)
+            .content(content);
+        if self.outcome != DeploymentOutcome::Failed {
+            builder = builder.emoji(DEPLOY_EMOJI);
+        }
+        builder.build()
+    }
+}
@@ -0,0 +1,406 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::job::JobName;
+use crate::render::truncate;
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// Maximum length of the message content.
+pub const DISCORD_MAX_CONTENT_LENGTH: usize = 2000;
+
+/// Maximum length of an embed title.
+pub const DISCORD_MAX_TITLE_LENGTH: usize = 256;
+
+/// Maximum length of an embed description.
+pub const DISCORD_MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+/// Maximum length of an embed field name.
+pub const DISCORD_MAX_FIELD_NAME_LENGTH: usize = 256;
+
+/// Maximum length of an embed field value.
+pub const DISCORD_MAX_FIELD_VALUE_LENGTH: usize = 1024;
+
+/// Maximum number of fields in an embed.
+pub const DISCORD_MAX_FIELDS: usize = 25;
+
+/// Maximum combined length of the titles, descriptions, field names and field
+/// values of the embeds of a message.
+pub const DISCORD_MAX_EMBED_TOTAL_LENGTH: usize = 6000;
+
+/// Length of the fences and line breaks around a code block.
+const CODE_FENCES_LENGTH: usize = 8;
+
+/// Where the bot worker posts the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum DiscordTarget {
+    /// Channel id (snowflake), posted to with the bot token.
+    ChannelId(String),
+    /// Id of a webhook configured on the worker side; the token is never queued.
+    WebhookId(String),
+}
+
+/// A field of an embed, serialized in Discord's own format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct DiscordEmbedField {
+    pub name: String,
+    pub value: String,
+    /// Whether the field is shown side by side with other inline fields.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inline: bool,
+}
+
+/// A rich embed, serialized in Discord's own format so the worker can pass it
+/// to the API unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct DiscordEmbed {
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub title: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub description: Option<String>,
+    /// Accent color as `0xRRGGBB`, see [`embed_color`].
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub color: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub fields: Vec<DiscordEmbedField>,
+}
+
+/// Represents a message received from the queue to be posted to Discord.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DiscordQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    pub target: DiscordTarget,
+    /// Markdown text shown above the embeds, e.g. role mentions.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub content: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub embeds: Vec<DiscordEmbed>,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+}
+
+impl DiscordQueueMessage {
+    /// Creates a plain text message without embeds.
+    pub fn new(target: DiscordTarget, content: impl Into<String>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            target,
+            content: content.into(),
+            embeds: Vec::new(),
+            status: MessageStatus::None,
+            dedup_key: None,
+        }
+    }
+
+    /// Returns a builder for a message with one embed.
+    pub fn builder(target: DiscordTarget) -> DiscordMessageBuilder {
+        DiscordMessageBuilder::new(target)
+    }
+}
+
+/// Returns the embed accent color of a status: blue, green, yellow or red,
+/// `None` for [`MessageStatus::None`].
+pub fn embed_color(status: MessageStatus) -> Option<u32> {
+    match status {
+        MessageStatus::None => None,
+        MessageStatus::Info => Some(0x3498DB),
+        MessageStatus::Ok => Some(0x2ECC71),
+        MessageStatus::Warn => Some(0xF1C40F),
+        MessageStatus::Error => Some(0xE74C3C),
+    }
+}
+
+/// A builder for creating DiscordQueueMessage objects with a single embed.
+///
+/// Mirrors [`crate::TelegramMessageBuilder`]: the job name becomes the embed
+/// title, the summary, content, code blocks and details its description, and
+/// the status its color.
+///
+/// ```rust
+/// use shared_types::{DiscordQueueMessage, MessageStatus};
+/// use shared_types::discord::DiscordTarget;
+///
+/// let msg = DiscordQueueMessage::builder(DiscordTarget::WebhookId("alerts".into()))
+///     .status(MessageStatus::Warn)
+///     .job_name("ftp")
+///     .summary("slow *upload*")
+///     .field("Host", "ftp01", true)
+///     .build();
+/// let embed = &msg.embeds[0];
+/// assert_eq!(embed.title.as_deref(), Some("⚠️ ftp"));
+/// assert_eq!(embed.description.as_deref(), Some("slow \\*upload\\*"));
+/// assert_eq!(embed.color, Some(0xF1C40F));
+/// ```
+pub struct DiscordMessageBuilder {
+    target: DiscordTarget,
+    message_content: String,
+    status: MessageStatus,
+    emoji: Option<String>,
+    job_name: JobName,
+    summary: String,
+    content: String,
+    code_blocks: Vec<String>,
+    fields: Vec<DiscordEmbedField>,
+    details: String,
+    dedup_key: Option<String>,
+}
+
+impl DiscordMessageBuilder {
+    /// Initializes a new builder with the required target.
+    pub fn new(target: DiscordTarget) -> Self {
+        Self {
+            target,
+            message_content: String::new(),
+            status: MessageStatus::None,
+            emoji: None,
+            job_name: JobName::default(),
+            summary: String::new(),
+            content: String::new(),
+            code_blocks: Vec::new(),
+            fields: Vec::new(),
+            details: String::new(),
+            dedup_key: None,
+        }
+    }
+
+    /// Sets the markdown text shown above the embed, e.g. `<@&role>` mentions.
+    pub fn message_content(mut self, content: impl Into<String>) -> Self {
+        self.message_content = content.into();
+        self
+    }
+
+    /// Sets the status level, which sets the embed color and adds an emoji to the title.
+    pub fn status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Overrides the status-derived emoji of the title. The color still follows the status.
+    pub fn emoji(mut self, emoji: impl Into<String>) -> Self {
+        self.emoji = Some(emoji.into());
+        self
+    }
+
+    /// Sets the job name, used as the embed title.
+    pub fn job_name(mut self, job_name: impl Into<JobName>) -> Self {
+        self.job_name = job_name.into();
+        self
+    }
+
+    /// Sets a summary line, shown first in the description. The summary is
+    /// plain text and escaped.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = summary.into();
+        self
+    }
+
+    /// Sets the embed content. Content is markdown and used verbatim.
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    /// Appends a code block after the content.
+    pub fn code_block(mut self, snippet: impl Into<String>) -> Self {
+        self.code_blocks.push(snippet.into());
+        self
+    }
+
+    /// Adds an embed field; fields beyond [`DISCORD_MAX_FIELDS`] are dropped.
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<String>, inline: bool) -> Self {
+        if self.fields.len() < DISCORD_MAX_FIELDS {
+            self.fields.push(DiscordEmbedField {
+                name: truncate(&name.into(), DISCORD_MAX_FIELD_NAME_LENGTH),
+                value: truncate(&value.into(), DISCORD_MAX_FIELD_VALUE_LENGTH),
+                inline,
+            });
+        }
+        self
+    }
+
+    /// Sets details (e.g. a log tail), rendered last in a code block.
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = details.into();
+        self
+    }
+
+    /// Sets an explicit dedup key for the message.
+    pub fn dedup_key(mut self, dedup_key: impl Into<String>) -> Self {
+        self.dedup_key = Some(dedup_key.into());
+        self
+    }
+
+    /// Builds the DiscordQueueMessage, truncating texts to Discord's limits.
+    ///
+    /// The description is shortened from its end, cutting code blocks inside
+    /// their fences, then fields are dropped from the end until the embed fits
+    /// [`DISCORD_MAX_EMBED_TOTAL_LENGTH`].
+    ///
+    /// ```rust
+    /// use shared_types::DiscordQueueMessage;
+    /// use shared_types::discord::{DiscordTarget, DISCORD_MAX_DESCRIPTION_LENGTH, DISCORD_MAX_EMBED_TOTAL_LENGTH};
+    ///
+    /// let mut builder = DiscordQueueMessage::builder(DiscordTarget::ChannelId("1".into()))
+    ///     .job_name("backup")
+    ///     .summary("snapshot failed")
+    ///     .details("x".repeat(5000));
+    /// for index in 0..25 {
+    ///     builder = builder.field(format!("host {}", index), "y".repeat(1024), true);
+    /// }
+    /// let embed = &builder.build().embeds[0];
+    /// let description = embed.description.as_deref().unwrap();
+    /// assert_eq!(description.chars().count(), DISCORD_MAX_DESCRIPTION_LENGTH);
+    /// assert!(description.starts_with("snapshot failed\n```\nxxx") && description.ends_with("...\n```"));
+    ///
+    /// let fields: usize = embed.fields.iter().map(|f| f.name.chars().count() + f.value.chars().count()).sum();
+    /// assert!(6 + description.chars().count() + fields <= DISCORD_MAX_EMBED_TOTAL_LENGTH);
+    /// assert_eq!(embed.fields.len(), 1);
+    /// ```
+    pub fn build(self) -> DiscordQueueMessage {
+        let emoji = match &self.emoji {
+            Some(emoji) => emoji.as_str(),
+            None => self.status.emoji(),
+        };
+        let title = [emoji, self.job_name.as_str()]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let title = truncate(&title, DISCORD_MAX_TITLE_LENGTH);
+
+        let mut parts = vec![
+            DescriptionPart::Text(escape_markdown(&self.summary)),
+            DescriptionPart::Text(self.content),
+        ];
+        parts.extend(self.code_blocks.into_iter().map(DescriptionPart::Code));
+        if !self.details.is_empty() {
+            parts.push(DescriptionPart::Code(self.details));
+        }
+        let description_length = DISCORD_MAX_DESCRIPTION_LENGTH
+            .min(DISCORD_MAX_EMBED_TOTAL_LENGTH - title.chars().count());
+        let description = fit_description(parts, description_length);
+
+        let mut fields = self.fields;
+        let field_length =
+            |field: &DiscordEmbedField| field.name.chars().count() + field.value.chars().count();
+        let mut total = title.chars().count()
+            + description.chars().count()
+            + fields.iter().map(field_length).sum::<usize>();
+        while total > DISCORD_MAX_EMBED_TOTAL_LENGTH {
+            let Some(field) = fields.pop() else { break };
+            total -= field_length(&field);
+        }
+
+        let embed = DiscordEmbed {
+            title: Some(title).filter(|title| !title.is_empty()),
+            description: Some(description).filter(|description| !description.is_empty()),
+            color: embed_color(self.status),
+            fields,
+        };
+
+        DiscordQueueMessage {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            target: self.target,
+            content: truncate(&self.message_content, DISCORD_MAX_CONTENT_LENGTH),
+            embeds: if embed == DiscordEmbed::default() { Vec::new() } else { vec![embed] },
+            status: self.status,
+            dedup_key: self.dedup_key,
+        }
+    }
+}
+
+/// Escapes Discord markdown control characters so `text` renders literally.
+///
+/// ```rust
+/// use shared_types::discord::escape_markdown;
+///
+/// assert_eq!(escape_markdown("*a* _b_ `c`"), "\\*a\\* \\_b\\_ \\`c\\`");
+/// ```
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Part of an embed description, fitted to the length limit by [`fit_description`].
+enum DescriptionPart {
+    /// Markdown, used verbatim.
+    Text(String),
+    /// Plain text rendered in a code block.
+    Code(String),
+}
+
+/// Joins the non-empty parts with line breaks, truncating the first part that
+/// does not fit `max_length` and dropping the ones after it.
+fn fit_description(parts: Vec<DescriptionPart>, max_length: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut remaining = max_length;
+    for part in parts {
+        // Room left once the line break before the part is counted.
+        let room = if lines.is_empty() { remaining } else { remaining.saturating_sub(1) };
+        let line = match part {
+            DescriptionPart::Text(text) if text.is_empty() => continue,
+            DescriptionPart::Text(text) => fit(&text, room),
+            DescriptionPart::Code(snippet) => code_block(&snippet, room),
+        };
+        let Some(line) = line else { break };
+        remaining = room - line.chars().count();
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Wraps `snippet` in a code block of at most `max_length` characters,
+/// breaking up fences inside it and truncating it inside the fences.
+fn code_block(snippet: &str, max_length: usize) -> Option<String> {
+    let snippet = snippet.replace("```", "`\u{200b}``");
+    let snippet = fit(&snippet, max_length.checked_sub(CODE_FENCES_LENGTH)?)?;
+    Some(format!("```\n{}\n```", snippet))
+}
+
+/// Returns `text` truncated to `max_length`, or `None` when only the ellipsis
+/// would be left of it.
+fn fit(text: &str, max_length: usize) -> Option<String> {
+    if text.chars().count() <= max_length {
+        Some(text.to_string())
+    } else if max_length > 3 {
+        Some(truncate(text, max_length))
+    } else {
+        None
+    }
+}
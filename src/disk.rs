@@ -0,0 +1,209 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Usage percentages at which a mount becomes a warning or an error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DiskThresholds {
+    #[cfg_attr(feature = "serde", serde(alias = "warn_percent"))]
+    pub warn_percent: f64,
+    #[cfg_attr(feature = "serde", serde(alias = "error_percent"))]
+    pub error_percent: f64,
+}
+
+impl Default for DiskThresholds {
+    /// Warn at 80%, error at 90%.
+    fn default() -> Self {
+        Self {
+            warn_percent: 80.0,
+            error_percent: 90.0,
+        }
+    }
+}
+
+impl DiskThresholds {
+    /// Returns the status of a mount used at `percent`.
+    pub fn status(&self, percent: f64) -> MessageStatus {
+        if percent >= self.error_percent {
+            MessageStatus::Error
+        } else if percent >= self.warn_percent {
+            MessageStatus::Warn
+        } else {
+            MessageStatus::Ok
+        }
+    }
+}
+
+/// Usage of a mounted filesystem.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct MountUsage {
+    #[cfg_attr(feature = "serde", serde(alias = "mount_point"))]
+    pub mount_point: String,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "total_bytes"))]
+    pub total_bytes: u64,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "used_bytes"))]
+    pub used_bytes: u64,
+    /// Used space in percent of the total, from 0 to 100.
+    #[cfg_attr(feature = "serde", serde(alias = "used_percent"))]
+    pub used_percent: f64,
+}
+
+impl MountUsage {
+    /// Creates the usage of a mount, computing `used_percent`.
+    pub fn new(mount_point: impl Into<String>, total_bytes: u64, used_bytes: u64) -> Self {
+        let used_percent = if total_bytes == 0 {
+            0.0
+        } else {
+            used_bytes as f64 * 100.0 / total_bytes as f64
+        };
+        Self {
+            mount_point: mount_point.into(),
+            total_bytes,
+            used_bytes,
+            used_percent,
+        }
+    }
+}
+
+/// Disk usage of a host, sent by the node monitoring agents.
+///
+/// ```rust
+/// use shared_types::MessageStatus;
+/// use shared_types::disk::{DiskUsageReport, MountUsage};
+///
+/// const GIB: u64 = 1024 * 1024 * 1024;
+/// let report = DiskUsageReport::new("ftp01")
+///     .with_mount(MountUsage::new("/", 50 * GIB, 20 * GIB))
+///     .with_mount(MountUsage::new("/srv/ftp", 500 * GIB, 460 * GIB));
+/// assert_eq!(report.severity(), MessageStatus::Error);
+/// assert_eq!(report.crossed().count(), 1);
+/// assert_eq!(
+///     report.to_telegram_message(123).message,
+///     "🚨 - <i>disk</i>\nftp01: /srv/ftp at 92%\n\
+///      <pre>✗ /srv/ftp  92%  460.0 GiB / 500.0 GiB\n  /         40%   20.0 GiB /  50.0 GiB</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DiskUsageReport {
+    pub host: String,
+    pub mounts: Vec<MountUsage>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thresholds: DiskThresholds,
+}
+
+impl DiskUsageReport {
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            mounts: Vec::new(),
+            thresholds: DiskThresholds::default(),
+        }
+    }
+
+    pub fn with_mount(mut self, mount: MountUsage) -> Self {
+        self.mounts.push(mount);
+        self
+    }
+
+    pub fn with_thresholds(mut self, thresholds: DiskThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Returns the mounts at or above the warning threshold.
+    pub fn crossed(&self) -> impl Iterator<Item = &MountUsage> {
+        self.mounts
+            .iter()
+            .filter(|mount| mount.used_percent >= self.thresholds.warn_percent)
+    }
+
+    /// Returns the most used mount.
+    pub fn worst(&self) -> Option<&MountUsage> {
+        self.mounts
+            .iter()
+            .max_by(|a, b| a.used_percent.total_cmp(&b.used_percent))
+    }
+
+    /// Returns the status of the worst mount, `Ok` without mounts.
+    pub fn severity(&self) -> MessageStatus {
+        self.worst()
+            .map_or(MessageStatus::Ok, |mount| self.thresholds.status(mount.used_percent))
+    }
+
+    /// Formats the report for the `disk` job: the worst mount as summary and a
+    /// table of the mounts, most used first, marking those over a threshold.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let summary = match self.worst() {
+            Some(mount) => format!(
+                "{}: {} at {:.0}%",
+                render::escape_text(&self.host),
+                render::escape_text(&mount.mount_point),
+                mount.used_percent
+            ),
+            None => format!("{}: no mounts", render::escape_text(&self.host)),
+        };
+
+        let mut mounts: Vec<&MountUsage> = self.mounts.iter().collect();
+        mounts.sort_by(|a, b| b.used_percent.total_cmp(&a.used_percent));
+        let rows: Vec<(&str, String, String, String)> = mounts
+            .iter()
+            .map(|mount| {
+                let marker = match self.thresholds.status(mount.used_percent) {
+                    MessageStatus::Error => "✗",
+                    MessageStatus::Warn => "!",
+                    _ => " ",
+                };
+                (
+                    marker,
+                    mount.mount_point.clone(),
+                    render::bytes(mount.used_bytes),
+                    render::bytes(mount.total_bytes),
+                )
+            })
+            .collect();
+        let mount_width = rows.iter().map(|row| row.1.chars().count()).max().unwrap_or(0);
+        let used_width = rows.iter().map(|row| row.2.len()).max().unwrap_or(0);
+        let total_width = rows.iter().map(|row| row.3.len()).max().unwrap_or(0);
+        let table = rows
+            .iter()
+            .zip(&mounts)
+            .map(|((marker, mount_point, used, total), mount)| {
+                format!(
+                    "{} {:<mount_width$} {:>3.0}%  {:>used_width$} / {:>total_width$}",
+                    marker, mount_point, mount.used_percent, used, total
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("disk")
+            .summary(summary)
+            .code_block(table)
+            .dedup_key(format!("disk:{}", self.host))
+            .build()
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Kind of a back-office document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum DocumentKind {
+    Invoice,
+    Contract,
+    Report,
+}
+
+impl DocumentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentKind::Invoice => "invoice",
+            DocumentKind::Contract => "contract",
+            DocumentKind::Report => "report",
+        }
+    }
+}
+
+/// Pipeline stage a document reached, in processing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum DocumentStage {
+    Received,
+    Parsed,
+    Validated,
+    Archived,
+}
+
+impl DocumentStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentStage::Received => "received",
+            DocumentStage::Parsed => "parsed",
+            DocumentStage::Validated => "validated",
+            DocumentStage::Archived => "archived",
+        }
+    }
+}
+
+/// Progress of a document through the back-office pipeline, announced by
+/// every pipeline job on the shared queue.
+///
+/// `stage` is the last stage reached; errors mean the next one failed.
+///
+/// ```rust
+/// use chrono::Utc;
+/// use shared_types::MessageStatus;
+/// use shared_types::document::{DocumentEvent, DocumentKind, DocumentStage};
+///
+/// let event = DocumentEvent::new("INV-2024-0042", DocumentKind::Invoice, DocumentStage::Parsed, Utc::now())
+///     .with_error("VAT number missing");
+/// assert_eq!(event.severity(), MessageStatus::Error);
+/// assert_eq!(
+///     event.to_telegram_message(123).message,
+///     "🚨 - <i>documents</i>\ninvoice <code>INV-2024-0042</code> parsed, not validated\n\
+///      <pre>VAT number missing</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DocumentEvent {
+    #[cfg_attr(feature = "serde", serde(alias = "document_id"))]
+    pub document_id: String,
+    pub kind: DocumentKind,
+    pub stage: DocumentStage,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub errors: Vec<String>,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl DocumentEvent {
+    pub fn new(
+        document_id: impl Into<String>,
+        kind: DocumentKind,
+        stage: DocumentStage,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            document_id: document_id.into(),
+            kind,
+            stage,
+            errors: Vec::new(),
+            timestamp,
+        }
+    }
+
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.errors.push(error.into());
+        self
+    }
+
+    /// Returns the stage after this one, `None` once archived.
+    pub fn next_stage(&self) -> Option<DocumentStage> {
+        match self.stage {
+            DocumentStage::Received => Some(DocumentStage::Parsed),
+            DocumentStage::Parsed => Some(DocumentStage::Validated),
+            DocumentStage::Validated => Some(DocumentStage::Archived),
+            DocumentStage::Archived => None,
+        }
+    }
+
+    /// `Error` with errors, `Ok` once archived, `Info` in between.
+    pub fn severity(&self) -> MessageStatus {
+        if !self.errors.is_empty() {
+            MessageStatus::Error
+        } else if self.stage == DocumentStage::Archived {
+            MessageStatus::Ok
+        } else {
+            MessageStatus::Info
+        }
+    }
+
+    /// Formats the event for the `documents` job, with the errors in a `<pre>` block.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let mut summary = format!(
+            "{} <code>{}</code> {}",
+            self.kind.as_str(),
+            render::escape_text(&self.document_id),
+            self.stage.as_str()
+        );
+        if let Some(next_stage) = self.next_stage().filter(|_| !self.errors.is_empty()) {
+            summary.push_str(&format!(", not {}", next_stage.as_str()));
+        }
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("documents")
+            .summary(summary)
+            .code_block(self.errors.join("\n"))
+            .build()
+    }
+}
@@ -0,0 +1,556 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::job::JobName;
+use crate::render::{self, headline, html_to_text};
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// Maximum length of the subject line, from the RFC 5322 line length limit.
+pub const EMAIL_MAX_SUBJECT_LENGTH: usize = 998;
+
+/// Tags kept by [`EmailQueueMessage::sanitize`] in the HTML body, when written
+/// in lowercase and without attributes; `br` and `hr` may be self-closing.
+pub const EMAIL_ALLOWED_TAGS: &[&str] = &[
+    "a", "b", "blockquote", "br", "code", "div", "em", "h1", "h2", "h3", "h4", "hr", "i", "li", "ol", "p",
+    "pre", "s", "small", "span", "strong", "table", "tbody", "td", "th", "thead", "tr", "u", "ul",
+];
+
+/// Metadata of a file attached to an email.
+///
+/// The queue only carries a reference: the mailer fetches the content from `url`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct EmailAttachment {
+    pub filename: String,
+    /// MIME type, e.g. `text/csv`.
+    #[cfg_attr(feature = "serde", serde(alias = "content_type"))]
+    pub content_type: String,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "size_bytes"))]
+    pub size_bytes: u64,
+    /// Location the mailer downloads the content from.
+    pub url: String,
+}
+
+impl EmailAttachment {
+    pub fn new(
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        size_bytes: u64,
+        url: impl Into<String>,
+    ) -> Self {
+        Self {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            size_bytes,
+            url: url.into(),
+        }
+    }
+}
+
+/// Represents a message received from the queue to be sent by email.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct EmailQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    pub to: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub cc: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub bcc: Vec<String>,
+    pub subject: String,
+    #[cfg_attr(feature = "serde", serde(alias = "html_body"))]
+    pub html_body: String,
+    /// Plain text alternative for clients that do not render HTML.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "text_body"))]
+    pub text_body: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub attachments: Vec<EmailAttachment>,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+    /// Free-form routing hints and trace ids for the mailer.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub metadata: HashMap<String, String>,
+}
+
+impl EmailQueueMessage {
+    /// Creates a message to a single recipient.
+    pub fn new(to: impl Into<String>, subject: impl Into<String>, html_body: impl Into<String>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            to: vec![to.into()],
+            cc: Vec::new(),
+            bcc: Vec::new(),
+            subject: subject.into(),
+            html_body: html_body.into(),
+            text_body: None,
+            attachments: Vec::new(),
+            status: MessageStatus::None,
+            dedup_key: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Returns a builder for a formatted email.
+    pub fn builder() -> EmailMessageBuilder {
+        EmailMessageBuilder::new()
+    }
+
+    /// Returns all recipients: `to`, then `cc`, then `bcc`.
+    pub fn recipients(&self) -> impl Iterator<Item = &str> {
+        self.to.iter().chain(&self.cc).chain(&self.bcc).map(String::as_str)
+    }
+
+    /// Normalizes the message so it passes header checks.
+    ///
+    /// Line breaks in the subject become spaces (preventing header injection) and
+    /// the subject is truncated to [`EMAIL_MAX_SUBJECT_LENGTH`] characters.
+    /// Addresses are trimmed, empty ones dropped and duplicates removed, keeping
+    /// the first occurrence across `to`, `cc` and `bcc`.
+    ///
+    /// The HTML body is escaped except for [`EMAIL_ALLOWED_TAGS`], links to
+    /// `http`, `https` and `mailto` URLs and character references, so scripts
+    /// and event handlers reach the mailer as text. A missing plain text body
+    /// is derived from the sanitized HTML body.
+    ///
+    /// ```rust
+    /// use shared_types::EmailQueueMessage;
+    ///
+    /// let mut email = EmailQueueMessage::new(" ops@example.com ", "backup\r\nBcc: x@evil.test", "<p>done</p>");
+    /// email.cc.push("ops@example.com".into());
+    /// email.sanitize();
+    /// assert_eq!(email.subject, "backup Bcc: x@evil.test");
+    /// assert_eq!(email.to, ["ops@example.com"]);
+    /// assert!(email.cc.is_empty());
+    /// assert_eq!(email.text_body.as_deref(), Some("done"));
+    ///
+    /// let mut email = EmailQueueMessage::new(
+    ///     "ops@example.com",
+    ///     "report",
+    ///     r#"<p onclick="steal()">see <a href="https://example.com/?a=1&amp;b=2">logs</a></p><script>alert(1)</script>"#,
+    /// );
+    /// email.sanitize();
+    /// assert_eq!(
+    ///     email.html_body,
+    ///     r#"&lt;p onclick="steal()"&gt;see <a href="https://example.com/?a=1&amp;b=2">logs</a></p>&lt;script&gt;alert(1)&lt;/script&gt;"#
+    /// );
+    /// ```
+    pub fn sanitize(&mut self) {
+        let subject = self
+            .subject
+            .split(['\r', '\n'])
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.subject = match subject.char_indices().nth(EMAIL_MAX_SUBJECT_LENGTH) {
+            Some((end, _)) => subject[..end].to_string(),
+            None => subject,
+        };
+
+        let mut seen = Vec::new();
+        for addresses in [&mut self.to, &mut self.cc, &mut self.bcc] {
+            let mut kept = Vec::with_capacity(addresses.len());
+            for address in addresses.drain(..) {
+                let address = address.trim().to_string();
+                let key = address.to_ascii_lowercase();
+                if !address.is_empty() && !seen.contains(&key) {
+                    seen.push(key);
+                    kept.push(address);
+                }
+            }
+            *addresses = kept;
+        }
+
+        self.html_body = sanitize_html(&self.html_body);
+        if self.text_body.is_none() {
+            self.text_body = Some(html_to_text(&self.html_body));
+        }
+    }
+
+    /// Checks that the message can be handed to a mailer.
+    ///
+    /// ```rust
+    /// use shared_types::EmailQueueMessage;
+    /// use shared_types::email::EmailValidationError;
+    ///
+    /// let email = EmailQueueMessage::new("not-an-address", "backup", "done");
+    /// assert!(matches!(email.validate(), Err(EmailValidationError::InvalidAddress(_))));
+    /// ```
+    pub fn validate(&self) -> Result<(), EmailValidationError> {
+        if self.to.is_empty() {
+            return Err(EmailValidationError::NoRecipients);
+        }
+        if let Some(address) = self.recipients().find(|address| !is_valid_address(address)) {
+            return Err(EmailValidationError::InvalidAddress(address.to_string()));
+        }
+        if self.subject.trim().is_empty() {
+            return Err(EmailValidationError::EmptySubject);
+        }
+        if self.subject.contains(['\r', '\n']) {
+            return Err(EmailValidationError::SubjectLineBreak);
+        }
+        let length = self.subject.chars().count();
+        if length > EMAIL_MAX_SUBJECT_LENGTH {
+            return Err(EmailValidationError::SubjectTooLong {
+                length,
+                max: EMAIL_MAX_SUBJECT_LENGTH,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by [`EmailQueueMessage::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailValidationError {
+    /// The message has no `to` recipient.
+    NoRecipients,
+    /// A recipient is not a plain `local@domain` address.
+    InvalidAddress(String),
+    EmptySubject,
+    /// The subject contains a line break, which would inject headers.
+    SubjectLineBreak,
+    SubjectTooLong { length: usize, max: usize },
+}
+
+impl fmt::Display for EmailValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmailValidationError::NoRecipients => f.write_str("email has no recipient"),
+            EmailValidationError::InvalidAddress(address) => {
+                write!(f, "invalid email address {:?}", address)
+            }
+            EmailValidationError::EmptySubject => f.write_str("email subject is empty"),
+            EmailValidationError::SubjectLineBreak => f.write_str("email subject contains a line break"),
+            EmailValidationError::SubjectTooLong { length, max } => {
+                write!(f, "email subject is {} characters, the maximum is {}", length, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EmailValidationError {}
+
+/// A builder for creating formatted EmailQueueMessage objects.
+///
+/// Mirrors [`crate::TelegramMessageBuilder`]: the same alert renders as an HTML
+/// body with a derived subject and plain text alternative.
+///
+/// ```rust
+/// use shared_types::{EmailQueueMessage, MessageStatus};
+///
+/// let email = EmailQueueMessage::builder()
+///     .to("ops@example.com")
+///     .status(MessageStatus::Error)
+///     .job_name("backup")
+///     .summary("snapshot failed")
+///     .code_block("disk <full>")
+///     .build();
+/// assert_eq!(email.subject, "🚨 backup: snapshot failed");
+/// assert_eq!(
+///     email.html_body,
+///     "<p><i>backup</i></p>\n<p>snapshot failed</p>\n<pre>disk &lt;full&gt;</pre>"
+/// );
+/// assert_eq!(email.text_body.as_deref(), Some("backup\nsnapshot failed\ndisk <full>"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EmailMessageBuilder {
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    subject: Option<String>,
+    status: MessageStatus,
+    emoji: Option<String>,
+    job_name: JobName,
+    summary: String,
+    content: String,
+    code_blocks: Vec<String>,
+    details: String,
+    attachments: Vec<EmailAttachment>,
+    dedup_key: Option<String>,
+    metadata: HashMap<String, String>,
+}
+
+impl EmailMessageBuilder {
+    /// Initializes a new builder without recipients; add them with [`to`](Self::to).
+    pub fn new() -> Self {
+        Self {
+            to: Vec::new(),
+            cc: Vec::new(),
+            bcc: Vec::new(),
+            subject: None,
+            status: MessageStatus::None,
+            emoji: None,
+            job_name: JobName::default(),
+            summary: String::new(),
+            content: String::new(),
+            code_blocks: Vec::new(),
+            details: String::new(),
+            attachments: Vec::new(),
+            dedup_key: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Adds a `to` recipient.
+    pub fn to(mut self, address: impl Into<String>) -> Self {
+        self.to.push(address.into());
+        self
+    }
+
+    /// Adds a `cc` recipient.
+    pub fn cc(mut self, address: impl Into<String>) -> Self {
+        self.cc.push(address.into());
+        self
+    }
+
+    /// Adds a `bcc` recipient.
+    pub fn bcc(mut self, address: impl Into<String>) -> Self {
+        self.bcc.push(address.into());
+        self
+    }
+
+    /// Sets the subject, replacing the one derived from status, job name and summary.
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Sets the status level, which adds an emoji prefix to the subject.
+    pub fn status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Overrides the status-derived emoji prefix of the subject.
+    pub fn emoji(mut self, emoji: impl Into<String>) -> Self {
+        self.emoji = Some(emoji.into());
+        self
+    }
+
+    /// Sets the job name, shown in italics above the body.
+    pub fn job_name(mut self, job_name: impl Into<JobName>) -> Self {
+        self.job_name = job_name.into();
+        self
+    }
+
+    /// Sets a summary line, shown first and used in the derived subject.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = summary.into();
+        self
+    }
+
+    /// Sets the message content. Content is HTML, kept as far as
+    /// [`EmailQueueMessage::sanitize`] allows.
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    /// Appends a `<pre>` block after the content, escaping its text.
+    pub fn code_block(mut self, snippet: impl Into<String>) -> Self {
+        self.code_blocks.push(snippet.into());
+        self
+    }
+
+    /// Sets details (e.g. a log tail), rendered last in a `<blockquote>`.
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = details.into();
+        self
+    }
+
+    /// Attaches a file reference.
+    pub fn attachment(mut self, attachment: EmailAttachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    /// Sets an explicit dedup key for the message.
+    pub fn dedup_key(mut self, dedup_key: impl Into<String>) -> Self {
+        self.dedup_key = Some(dedup_key.into());
+        self
+    }
+
+    /// Attaches a metadata entry (routing hint, trace id, ...) to the message.
+    pub fn meta(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Builds the EmailQueueMessage, then [sanitizes](EmailQueueMessage::sanitize) it.
+    pub fn build(self) -> EmailQueueMessage {
+        let subject = self.subject.clone().unwrap_or_else(|| self.derived_subject());
+
+        let mut html = Vec::new();
+        let mut text = Vec::new();
+        if !self.job_name.as_str().is_empty() {
+            html.push(format!("<p><i>{}</i></p>", escape(self.job_name.as_str())));
+            text.push(self.job_name.to_string());
+        }
+        for paragraph in [&self.summary, &self.content] {
+            if !paragraph.is_empty() {
+                html.push(format!("<p>{}</p>", paragraph));
+                text.push(html_to_text(paragraph));
+            }
+        }
+        for snippet in self.code_blocks.iter().filter(|snippet| !snippet.is_empty()) {
+            html.push(format!("<pre>{}</pre>", escape(snippet)));
+            text.push(snippet.clone());
+        }
+        if !self.details.is_empty() {
+            html.push(format!("<blockquote>{}</blockquote>", escape(&self.details)));
+            text.push(self.details.clone());
+        }
+
+        let mut message = EmailQueueMessage {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            to: self.to,
+            cc: self.cc,
+            bcc: self.bcc,
+            subject,
+            html_body: html.join("\n"),
+            text_body: Some(text.join("\n")),
+            attachments: self.attachments,
+            status: self.status,
+            dedup_key: self.dedup_key,
+            metadata: self.metadata,
+        };
+        message.sanitize();
+        message
+    }
+
+    /// Returns `{emoji} {job_name}: {summary}`, leaving out the parts that are not set.
+    fn derived_subject(&self) -> String {
+        let emoji = match &self.emoji {
+            Some(emoji) => emoji.as_str(),
+            None => self.status.emoji(),
+        };
+        headline(emoji, self.job_name.as_str(), &html_to_text(&self.summary))
+    }
+}
+
+impl Default for EmailMessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns true for a plain `local@domain.tld` address without display name.
+pub fn is_valid_address(address: &str) -> bool {
+    let Some((local, domain)) = address.split_once('@') else {
+        return false;
+    };
+    let forbidden = |c: char| c.is_whitespace() || c.is_control() || "<>()[],;:\\\"@".contains(c);
+    !local.is_empty()
+        && !local.contains(forbidden)
+        && domain.contains('.')
+        && !domain.starts_with(['.', '-'])
+        && !domain.ends_with(['.', '-'])
+        && !domain.contains("..")
+        && !domain.contains(forbidden)
+}
+
+fn escape(text: &str) -> String {
+    render::escape_text(text).into_owned()
+}
+
+/// Escapes `html`, then re-enables the allowed tags, links and character references.
+fn sanitize_html(html: &str) -> String {
+    const ESCAPED_AMP: &str = "&amp;";
+    const ESCAPED_OPEN: &str = "&lt;";
+    const ESCAPED_CLOSE: &str = "&gt;";
+
+    let escaped = render::escape_text(html);
+    let mut sanitized = String::with_capacity(escaped.len());
+    let mut rest: &str = &escaped;
+    while let Some(start) = rest.find('&') {
+        sanitized.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(entity) = rest.strip_prefix(ESCAPED_AMP).filter(|entity| starts_with_entity(entity)) {
+            sanitized.push('&');
+            rest = entity;
+            continue;
+        }
+        let tag = rest
+            .strip_prefix(ESCAPED_OPEN)
+            .and_then(|tag| tag.split_once(ESCAPED_CLOSE))
+            .filter(|(tag, _)| is_allowed_tag(tag));
+        match tag {
+            Some((tag, tail)) => {
+                sanitized.push('<');
+                push_restoring_entities(&mut sanitized, tag);
+                sanitized.push('>');
+                rest = tail;
+            }
+            None => {
+                sanitized.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    sanitized.push_str(rest);
+    sanitized
+}
+
+/// Appends escaped attribute text, turning `&amp;` back into `&` where it starts a character reference.
+fn push_restoring_entities(sanitized: &mut String, escaped: &str) {
+    const ESCAPED_AMP: &str = "&amp;";
+
+    let mut rest = escaped;
+    while let Some(start) = rest.find(ESCAPED_AMP) {
+        sanitized.push_str(&rest[..start]);
+        rest = &rest[start + ESCAPED_AMP.len()..];
+        sanitized.push_str(if starts_with_entity(rest) { "&" } else { ESCAPED_AMP });
+    }
+    sanitized.push_str(rest);
+}
+
+/// Returns true for the escaped content of an allowed tag, between `<` and `>`.
+fn is_allowed_tag(tag: &str) -> bool {
+    if let Some(href) = tag.strip_prefix("a href=\"").and_then(|href| href.strip_suffix('"')) {
+        return !href.contains('"') && ["http://", "https://", "mailto:"].iter().any(|scheme| href.starts_with(scheme));
+    }
+    let name = match tag.strip_prefix('/') {
+        Some(name) => name,
+        None if matches!(tag, "br/" | "br /" | "hr/" | "hr /") => return true,
+        None => tag,
+    };
+    EMAIL_ALLOWED_TAGS.contains(&name)
+}
+
+/// Returns true when `text` starts with the name and `;` of a character
+/// reference, e.g. `nbsp;` or `#39;`.
+fn starts_with_entity(text: &str) -> bool {
+    let Some(end) = text.find(';') else {
+        return false;
+    };
+    let name = &text[..end];
+    match name.strip_prefix('#') {
+        Some(code) => match code.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()),
+        },
+        None => !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
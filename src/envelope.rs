@@ -0,0 +1,528 @@
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::hash::stable_hash_hex;
+#[cfg(feature = "serde")]
+use crate::limits::SizeError;
+use crate::priority::Priority;
+use crate::retry::RetryInfo;
+use crate::routes;
+use crate::producer::ProducerInfo;
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::TelegramQueueMessage;
+use crate::trace::TraceParent;
+
+/// Header read by the RabbitMQ delayed message exchange plugin, in milliseconds.
+pub const RABBITMQ_DELAY_HEADER: &str = "x-delay";
+
+/// Largest `DelaySeconds` accepted by SQS.
+pub const SQS_MAX_DELAY_SECONDS: u32 = 900;
+
+/// Wraps a queued payload with uniform tracing metadata.
+///
+/// Every message put on a queue carries a unique id, its creation time, the
+/// producing service and an optional correlation id shared by related messages.
+///
+/// ```rust
+/// use shared_types::{QueueEnvelope, TelegramQueueMessage};
+///
+/// let msg = TelegramQueueMessage::new(123, "done".into(), false);
+/// let envelope = QueueEnvelope::new("ftp-sync", msg).with_correlation_id("run-42");
+/// assert_eq!(envelope.correlation_id.as_deref(), Some("run-42"));
+/// ```
+///
+/// Like every type of this crate the envelope is written in camelCase, and
+/// reading also accepts the snake_case spelling of each key:
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use shared_types::{QueueEnvelope, TelegramQueueMessage};
+///
+/// let envelope: QueueEnvelope<TelegramQueueMessage> = serde_json::from_str(
+///     r#"{"message_id": "67e55044-10b1-426f-9247-bb680e5fe0c8", "created_at": "2024-05-17T03:00:00Z",
+///         "producer": "ftp-sync", "correlation_id": "run-42",
+///         "payload": {"chat_id": 123, "message": "done", "force_send": false, "dedup_key": "ftp"}}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(envelope.payload.dedup_key.as_deref(), Some("ftp"));
+/// let json = serde_json::to_value(&envelope).unwrap();
+/// assert_eq!(json["correlationId"], "run-42");
+/// assert_eq!(json["payload"]["dedupKey"], "ftp");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct QueueEnvelope<T> {
+    /// Schema version of the envelope.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    /// Unique id of this message.
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::uuid_bytes::serialize",
+            deserialize_with = "crate::borsh_with::uuid_bytes::deserialize"
+        )
+    )]
+    #[cfg_attr(feature = "serde", serde(alias = "message_id"))]
+    pub message_id: Uuid,
+    /// When the producer created the message.
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::timestamp::serialize",
+            deserialize_with = "crate::borsh_with::timestamp::deserialize"
+        )
+    )]
+    #[cfg_attr(feature = "serde", serde(alias = "created_at"))]
+    pub created_at: DateTime<Utc>,
+    /// Id shared by all messages belonging to the same operation.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "correlation_id"))]
+    pub correlation_id: Option<String>,
+    /// Name of the producing service.
+    pub producer: String,
+    /// Detailed identity of the producing service instance.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "producer_info"))]
+    pub producer_info: Option<ProducerInfo>,
+    /// W3C `traceparent` of the producing span.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub traceparent: Option<String>,
+    /// W3C `tracestate` accompanying `traceparent`.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub tracestate: Option<String>,
+    /// Route used by workers sharing one physical queue to pick their traffic,
+    /// see [`crate::routes`].
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "routing_key"))]
+    pub routing_key: Option<String>,
+    /// Key identifying redeliveries of the same logical message, for consumer side deduplication.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "idempotency_key"))]
+    pub idempotency_key: Option<String>,
+    /// Delivery priority.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub priority: Priority,
+    /// After this time the message is stale and should be dropped instead of delivered.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "expires_at"))]
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::option_timestamp::serialize",
+            deserialize_with = "crate::borsh_with::option_timestamp::deserialize"
+        )
+    )]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Delay after `created_at` before the message should be delivered.
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u64>"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<u64>))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "deliverAfterMs",
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "crate::duration_ms::option",
+            alias = "deliver_after_ms"
+        )
+    )]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::option_duration::serialize",
+            deserialize_with = "crate::borsh_with::option_duration::deserialize"
+        )
+    )]
+    pub deliver_after: Option<Duration>,
+    /// Earliest time the message should be delivered.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "deliver_at"))]
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::option_timestamp::serialize",
+            deserialize_with = "crate::borsh_with::option_timestamp::deserialize"
+        )
+    )]
+    pub deliver_at: Option<DateTime<Utc>>,
+    /// Retry state, set once the message has been attempted.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub retry: Option<RetryInfo>,
+    /// The wrapped message.
+    pub payload: T,
+}
+
+impl<T> QueueEnvelope<T> {
+    /// Wraps `payload` with a fresh message id and the current time.
+    pub fn new(producer: impl Into<String>, payload: T) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            message_id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            correlation_id: None,
+            producer: producer.into(),
+            producer_info: None,
+            traceparent: None,
+            tracestate: None,
+            routing_key: None,
+            idempotency_key: None,
+            priority: Priority::default(),
+            expires_at: None,
+            deliver_after: None,
+            deliver_at: None,
+            retry: None,
+            payload,
+        }
+    }
+
+    /// Sets the correlation id.
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    /// Wraps `payload` on behalf of `producer`, using its service name as the producer name.
+    ///
+    /// ```rust
+    /// use shared_types::{producer_info, QueueEnvelope};
+    ///
+    /// let envelope = QueueEnvelope::from_producer(producer_info!(), "payload");
+    /// assert_eq!(envelope.producer, "shared_types");
+    /// ```
+    pub fn from_producer(producer: ProducerInfo, payload: T) -> Self {
+        Self::new(producer.service.clone(), payload).with_producer_info(producer)
+    }
+
+    /// Attaches the identity of the producing service instance.
+    pub fn with_producer_info(mut self, producer_info: ProducerInfo) -> Self {
+        self.producer_info = Some(producer_info);
+        self
+    }
+
+    /// Sets the W3C trace context, `tracestate` being optional.
+    pub fn with_trace_context(mut self, traceparent: TraceParent, tracestate: Option<String>) -> Self {
+        self.traceparent = Some(traceparent.to_string());
+        self.tracestate = tracestate.filter(|tracestate| !tracestate.is_empty());
+        self
+    }
+
+    /// Returns the parsed `traceparent`, `None` when absent or invalid.
+    pub fn trace_parent(&self) -> Option<TraceParent> {
+        self.traceparent.as_deref().and_then(TraceParent::parse)
+    }
+
+    /// Sets the trace context from the span of an OpenTelemetry context.
+    ///
+    /// With `tracing`, pass the context of the current span obtained through
+    /// `tracing_opentelemetry::OpenTelemetrySpanExt::context`. Invalid span
+    /// contexts leave the envelope unchanged.
+    #[cfg(feature = "opentelemetry")]
+    pub fn with_otel_context(self, context: &opentelemetry::Context) -> Self {
+        use opentelemetry::trace::TraceContextExt;
+
+        let span = context.span();
+        let span_context = span.span_context();
+        match TraceParent::from_span_context(span_context) {
+            Some(traceparent) => {
+                let tracestate = span_context.trace_state().header();
+                self.with_trace_context(traceparent, Some(tracestate))
+            }
+            None => self,
+        }
+    }
+
+    /// Returns an OpenTelemetry context whose remote parent is the producer span,
+    /// to start the consumer span as its child.
+    #[cfg(feature = "opentelemetry")]
+    pub fn otel_context(&self) -> opentelemetry::Context {
+        use opentelemetry::trace::TraceContextExt;
+
+        match self.trace_parent() {
+            Some(traceparent) => opentelemetry::Context::new().with_remote_span_context(
+                traceparent.to_span_context(self.tracestate.as_deref()),
+            ),
+            None => opentelemetry::Context::new(),
+        }
+    }
+
+    /// Sets the routing key.
+    pub fn with_routing_key(mut self, routing_key: impl Into<String>) -> Self {
+        self.routing_key = Some(routing_key.into());
+        self
+    }
+
+    /// Returns true when the routing key equals `route` or lies below it.
+    ///
+    /// Envelopes without a routing key match no route.
+    pub fn is_routed_to(&self, route: &str) -> bool {
+        self.routing_key
+            .as_deref()
+            .is_some_and(|routing_key| routes::matches(routing_key, route))
+    }
+
+    /// Sets the idempotency key.
+    pub fn with_idempotency_key(mut self, idempotency_key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(idempotency_key.into());
+        self
+    }
+
+    /// Sets the delivery priority.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the time after which the message is dropped.
+    pub fn with_expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Expires the message `ttl` after its creation.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use shared_types::QueueEnvelope;
+    ///
+    /// let envelope = QueueEnvelope::new("ftp-sync", "job still running").expires_in(Duration::from_secs(300));
+    /// assert!(!envelope.is_expired());
+    /// assert!(envelope.is_expired_at(envelope.created_at + chrono::Duration::minutes(10)));
+    /// ```
+    pub fn expires_in(mut self, ttl: Duration) -> Self {
+        self.expires_at = chrono::Duration::from_std(ttl)
+            .ok()
+            .and_then(|ttl| self.created_at.checked_add_signed(ttl));
+        self
+    }
+
+    /// Returns true when the message has expired at `now`.
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Returns true when the message has expired.
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Utc::now())
+    }
+
+    /// Delays delivery by `delay` after the message creation.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use shared_types::QueueEnvelope;
+    ///
+    /// let envelope = QueueEnvelope::new("backup", "reminder").deliver_after(Duration::from_secs(90));
+    /// let now = envelope.created_at;
+    /// assert_eq!(envelope.rabbitmq_x_delay_at(now), Some(90_000));
+    /// assert_eq!(envelope.sqs_delay_seconds_at(now), Some(90));
+    /// assert!(!envelope.is_deliverable_at(now));
+    /// ```
+    pub fn deliver_after(mut self, delay: Duration) -> Self {
+        self.deliver_after = Some(delay);
+        self
+    }
+
+    /// Delays delivery until `deliver_at`.
+    pub fn deliver_at(mut self, deliver_at: DateTime<Utc>) -> Self {
+        self.deliver_at = Some(deliver_at);
+        self
+    }
+
+    /// Returns the earliest delivery time, the later of `deliver_at` and
+    /// `created_at + deliver_after`, or `None` when delivery is not delayed.
+    pub fn delivery_time(&self) -> Option<DateTime<Utc>> {
+        let after = self
+            .deliver_after
+            .and_then(|delay| chrono::Duration::from_std(delay).ok())
+            .and_then(|delay| self.created_at.checked_add_signed(delay));
+        after.max(self.deliver_at)
+    }
+
+    /// Returns the delay still to wait at `now`, `None` when the message is due.
+    pub fn remaining_delay_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.delivery_time()
+            .and_then(|delivery_time| (delivery_time - now).to_std().ok())
+            .filter(|delay| !delay.is_zero())
+    }
+
+    /// Returns true when the message may be delivered at `now`.
+    pub fn is_deliverable_at(&self, now: DateTime<Utc>) -> bool {
+        self.remaining_delay_at(now).is_none()
+    }
+
+    /// Returns the value of the [`RABBITMQ_DELAY_HEADER`] header at `now`, in
+    /// milliseconds, `None` when the message is due.
+    pub fn rabbitmq_x_delay_at(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.remaining_delay_at(now)
+            .map(|delay| i64::try_from(delay.as_millis()).unwrap_or(i64::MAX))
+    }
+
+    /// Returns the value of the [`RABBITMQ_DELAY_HEADER`] header, see
+    /// [`QueueEnvelope::rabbitmq_x_delay_at`].
+    pub fn rabbitmq_x_delay(&self) -> Option<i64> {
+        self.rabbitmq_x_delay_at(Utc::now())
+    }
+
+    /// Returns the SQS `DelaySeconds` at `now`, rounded up and capped at
+    /// [`SQS_MAX_DELAY_SECONDS`], `None` when the message is due.
+    ///
+    /// Longer delays must be re-queued by the consumer until
+    /// [`QueueEnvelope::is_deliverable_at`] holds.
+    pub fn sqs_delay_seconds_at(&self, now: DateTime<Utc>) -> Option<u32> {
+        self.remaining_delay_at(now).map(|delay| {
+            let seconds = delay.as_secs() + u64::from(delay.subsec_nanos() > 0);
+            u32::try_from(seconds).map_or(SQS_MAX_DELAY_SECONDS, |seconds| {
+                seconds.min(SQS_MAX_DELAY_SECONDS)
+            })
+        })
+    }
+
+    /// Returns the SQS `DelaySeconds`, see [`QueueEnvelope::sqs_delay_seconds_at`].
+    pub fn sqs_delay_seconds(&self) -> Option<u32> {
+        self.sqs_delay_seconds_at(Utc::now())
+    }
+
+    /// Sets the retry state, allowing `max_attempts` attempts.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.retry = Some(RetryInfo::new(max_attempts));
+        self
+    }
+
+    /// Wraps a follow-up `payload` in the same correlation as `self`.
+    ///
+    /// The correlation id is inherited, or taken from the message id of `self`
+    /// when it has none.
+    pub fn follow_up<U>(&self, producer: impl Into<String>, payload: U) -> QueueEnvelope<U> {
+        let correlation_id = self
+            .correlation_id
+            .clone()
+            .unwrap_or_else(|| self.message_id.to_string());
+        QueueEnvelope::new(producer, payload).with_correlation_id(correlation_id)
+    }
+
+    /// Transforms the payload, keeping the envelope metadata.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> QueueEnvelope<U> {
+        QueueEnvelope {
+            schema_version: self.schema_version,
+            message_id: self.message_id,
+            created_at: self.created_at,
+            correlation_id: self.correlation_id,
+            producer: self.producer,
+            producer_info: self.producer_info,
+            traceparent: self.traceparent,
+            tracestate: self.tracestate,
+            routing_key: self.routing_key,
+            idempotency_key: self.idempotency_key,
+            priority: self.priority,
+            expires_at: self.expires_at,
+            deliver_after: self.deliver_after,
+            deliver_at: self.deliver_at,
+            retry: self.retry,
+            payload: f(self.payload),
+        }
+    }
+
+    /// Consumes the envelope, returning the payload.
+    pub fn into_payload(self) -> T {
+        self.payload
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize> QueueEnvelope<T> {
+    /// Checks that the JSON encoded envelope fits in `limit_bytes`, returning its size.
+    ///
+    /// Call before publishing so oversized messages fail with a typed error
+    /// instead of an opaque broker rejection; see [`crate::limits`] for broker limits.
+    ///
+    /// ```rust
+    /// use shared_types::QueueEnvelope;
+    /// use shared_types::limits::{SizeError, SQS_MAX_MESSAGE_BYTES};
+    ///
+    /// let small = QueueEnvelope::new("ftp-sync", "done");
+    /// assert!(small.validate_size(SQS_MAX_MESSAGE_BYTES).is_ok());
+    ///
+    /// let huge = QueueEnvelope::new("ftp-sync", "x".repeat(SQS_MAX_MESSAGE_BYTES));
+    /// assert!(matches!(huge.validate_size(SQS_MAX_MESSAGE_BYTES), Err(SizeError::TooLarge { .. })));
+    /// ```
+    pub fn validate_size(&self, limit_bytes: usize) -> Result<usize, SizeError> {
+        let size = serde_json::to_vec(self).map_err(SizeError::Serialize)?.len();
+        if size > limit_bytes {
+            return Err(SizeError::TooLarge { size, limit: limit_bytes });
+        }
+        Ok(size)
+    }
+}
+
+impl QueueEnvelope<TelegramQueueMessage> {
+    /// Wraps a Telegram message, deriving the priority from its status and
+    /// routing it to [`routes::TELEGRAM`].
+    ///
+    /// ```rust
+    /// use shared_types::{MessageStatus, Priority, QueueEnvelope, TelegramQueueMessage};
+    ///
+    /// let msg = TelegramQueueMessage::builder(123).status(MessageStatus::Error).build();
+    /// assert_eq!(QueueEnvelope::telegram("ftp-sync", msg).priority, Priority::Critical);
+    /// ```
+    pub fn telegram(producer: impl Into<String>, message: TelegramQueueMessage) -> Self {
+        let priority = Priority::from(message.status);
+        Self::new(producer, message)
+            .with_priority(priority)
+            .with_routing_key(routes::TELEGRAM)
+    }
+
+    /// Sets the idempotency key derived from the chat, the message text and the
+    /// UTC day of creation, see [`telegram_idempotency_key`].
+    ///
+    /// ```rust
+    /// use shared_types::{QueueEnvelope, TelegramQueueMessage};
+    ///
+    /// let msg = TelegramQueueMessage::new(123, "backup done".into(), false);
+    /// let first = QueueEnvelope::telegram("backup", msg.clone()).with_derived_idempotency_key();
+    /// let redelivered = QueueEnvelope::telegram("backup", msg).with_derived_idempotency_key();
+    /// assert_eq!(first.idempotency_key, redelivered.idempotency_key);
+    /// ```
+    pub fn with_derived_idempotency_key(self) -> Self {
+        let key = telegram_idempotency_key(self.payload.chat_id, &self.payload.message, self.created_at);
+        self.with_idempotency_key(key)
+    }
+}
+
+/// Writes `[{producer} {message_id}] ` followed by the payload, passing the
+/// precision on so a [`TelegramQueueMessage`] preview stays truncated.
+///
+/// ```rust
+/// use shared_types::{QueueEnvelope, TelegramQueueMessage};
+///
+/// let envelope = QueueEnvelope::telegram("ftp-sync", TelegramQueueMessage::new(123, "done".into(), false));
+/// assert_eq!(envelope.to_string(), format!("[ftp-sync {}] [123] done", envelope.message_id));
+/// ```
+impl<T: fmt::Display> fmt::Display for QueueEnvelope<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{} {}] ", self.producer, self.message_id)?;
+        fmt::Display::fmt(&self.payload, f)
+    }
+}
+
+/// Computes a stable idempotency key from a chat id, message text and day bucket.
+///
+/// The day bucket is the UTC date of `at`: the same text sent to the same chat
+/// on the same day is treated as a redelivery, while a repeat on the next day is not.
+pub fn telegram_idempotency_key(chat_id: i64, message: &str, at: DateTime<Utc>) -> String {
+    let chat_id = chat_id.to_string();
+    let day = at.format("%Y-%m-%d").to_string();
+    stable_hash_hex([chat_id.as_str(), message, day.as_str()])
+}
@@ -0,0 +1,298 @@
+//! The crate-wide error type and its stable error codes.
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::chat_directory::ChatDirectoryError;
+use crate::cron::CronError;
+use crate::email::EmailValidationError;
+use crate::job::JobName;
+use crate::limits::SizeError;
+use crate::priority::PriorityError;
+use crate::signing::SignatureError;
+use crate::sms::PhoneNumberError;
+use crate::subject::SubjectError;
+use crate::telegram::{
+    MessageStatus, MessageStatusError, TELEGRAM_MAX_MESSAGE_LENGTH, TelegramMessageBuilder, TelegramQueueMessage,
+};
+
+/// Result of the fallible APIs of this crate.
+pub type Result<T, E = SharedTypesError> = std::result::Result<T, E>;
+
+/// Error returned by the fallible APIs of this crate.
+///
+/// The module specific errors convert into it with `?`, so services can handle
+/// every failure of the shared types through one type.
+///
+/// ```rust
+/// use shared_types::cron::CronSchedule;
+/// use shared_types::error::SharedTypesError;
+///
+/// fn parse(expression: &str) -> Result<CronSchedule, SharedTypesError> {
+///     Ok(expression.parse::<CronSchedule>()?)
+/// }
+///
+/// let err = parse("* * *").unwrap_err();
+/// assert!(matches!(err, SharedTypesError::Cron(_)));
+/// assert_eq!(err.to_string(), "expected 5 cron fields, found 3");
+/// ```
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum SharedTypesError {
+    /// The content could not be made safe for the target channel.
+    #[error("sanitization failed: {0}")]
+    Sanitization(String),
+    /// A value does not satisfy the constraints of its type.
+    #[error("validation failed: {0}")]
+    Validation(String),
+    /// A value could not be serialized or deserialized in `format`.
+    #[error("{format} serialization failed: {source}")]
+    Serialization {
+        format: &'static str,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// A Telegram message is longer than the Bot API accepts.
+    #[error("telegram message is {length} characters, exceeding the limit of {max}")]
+    TelegramMessageTooLong { length: usize, max: usize },
+    /// A serialized message exceeds the limit of its transport.
+    #[error("message is {size} bytes, exceeding the limit of {limit} bytes")]
+    SizeLimitExceeded { size: usize, limit: usize },
+    #[error(transparent)]
+    Cron(#[from] CronError),
+    #[error(transparent)]
+    Email(#[from] EmailValidationError),
+    #[error(transparent)]
+    PhoneNumber(#[from] PhoneNumberError),
+    #[error(transparent)]
+    ChatDirectory(#[from] ChatDirectoryError),
+    #[error(transparent)]
+    Signature(#[from] SignatureError),
+    #[error(transparent)]
+    Subject(#[from] SubjectError),
+    #[cfg(feature = "io")]
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Another error, with a description of what was being done.
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<SharedTypesError>,
+    },
+}
+
+impl SharedTypesError {
+    /// Returns the stable code of the error.
+    ///
+    /// ```rust
+    /// use shared_types::error::{ErrorCode, SharedTypesError};
+    /// use shared_types::{MessageStatus, TelegramQueueMessage};
+    ///
+    /// let err = SharedTypesError::TelegramMessageTooLong { length: 5000, max: 4096 };
+    /// assert_eq!(err.code(), ErrorCode::TelegramOverLength);
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .status(MessageStatus::Error)
+    ///     .job_name("reports")
+    ///     .content("send failed")
+    ///     .code(err.code().as_str())
+    ///     .build();
+    /// assert_eq!(msg.message, "🚨 - <i>reports</i>\nsend failed <code>E_TG_001_OVER_LENGTH</code>");
+    /// ```
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SharedTypesError::Sanitization(_) => ErrorCode::Sanitization,
+            SharedTypesError::Validation(_) => ErrorCode::Validation,
+            SharedTypesError::Serialization { .. } => ErrorCode::Serialization,
+            SharedTypesError::TelegramMessageTooLong { .. } => ErrorCode::TelegramOverLength,
+            SharedTypesError::SizeLimitExceeded { .. } => ErrorCode::QueueSizeLimit,
+            SharedTypesError::Cron(_) => ErrorCode::CronInvalid,
+            SharedTypesError::Email(_) => ErrorCode::EmailInvalid,
+            SharedTypesError::PhoneNumber(_) => ErrorCode::SmsInvalidNumber,
+            SharedTypesError::ChatDirectory(_) => ErrorCode::ChatDirectoryInvalidId,
+            SharedTypesError::Signature(_) => ErrorCode::SignatureInvalid,
+            SharedTypesError::Subject(_) => ErrorCode::SubjectInvalid,
+            #[cfg(feature = "io")]
+            SharedTypesError::Io(_) => ErrorCode::Io,
+            SharedTypesError::Context { source, .. } => source.code(),
+        }
+    }
+}
+
+/// Declares an error code enum from one table of variants and codes, so the
+/// serde names and `as_str` cannot drift apart.
+macro_rules! error_codes {
+    ($(#[$attr:meta])* pub enum $name:ident { $($variant:ident => $code:tt,)+ }) => {
+        $(#[$attr])*
+        pub enum $name {
+            $(
+                #[cfg_attr(feature = "serde", serde(rename = $code))]
+                $variant,
+            )+
+        }
+
+        impl $name {
+            /// Every code, in declaration order.
+            pub const ALL: &'static [$name] = &[$($name::$variant),+];
+
+            /// Returns the code, e.g. `E_TG_001_OVER_LENGTH`.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $code,)+
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// Stable identifier of an error, shown in alerts and dashboards so support
+    /// can look it up across services.
+    ///
+    /// Codes read `E_{AREA}_{NUMBER}_{NAME}` and never change meaning once
+    /// released; new failures get new codes. They serialize as the code:
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use shared_types::error::ErrorCode;
+    ///
+    /// for code in ErrorCode::ALL {
+    ///     let json = serde_json::to_string(code).unwrap();
+    ///     assert_eq!(json, format!("\"{}\"", code.as_str()));
+    ///     assert_eq!(serde_json::from_str::<ErrorCode>(&json).unwrap(), *code);
+    /// }
+    /// # }
+    /// ```
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+    #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+    #[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+    #[non_exhaustive]
+    pub enum ErrorCode {
+        Sanitization => "E_GEN_001_SANITIZATION",
+        Validation => "E_GEN_002_VALIDATION",
+        Serialization => "E_GEN_003_SERIALIZATION",
+        TelegramOverLength => "E_TG_001_OVER_LENGTH",
+        QueueSizeLimit => "E_QUEUE_001_SIZE_LIMIT",
+        CronInvalid => "E_CRON_001_INVALID",
+        EmailInvalid => "E_EMAIL_001_INVALID",
+        SmsInvalidNumber => "E_SMS_001_INVALID_NUMBER",
+        ChatDirectoryInvalidId => "E_CHAT_001_INVALID_ID",
+        SignatureInvalid => "E_SIG_001_INVALID",
+        SubjectInvalid => "E_SUBJ_001_INVALID",
+        Io => "E_IO_001_FAILED",
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<SizeError> for SharedTypesError {
+    fn from(err: SizeError) -> Self {
+        match err {
+            SizeError::TooLarge { size, limit } => SharedTypesError::SizeLimitExceeded { size, limit },
+            #[cfg(feature = "serde")]
+            SizeError::Serialize(err) => err.into(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for SharedTypesError {
+    fn from(err: serde_json::Error) -> Self {
+        SharedTypesError::Serialization {
+            format: "json",
+            source: Box::new(err),
+        }
+    }
+}
+
+impl From<MessageStatusError> for SharedTypesError {
+    fn from(err: MessageStatusError) -> Self {
+        SharedTypesError::Validation(err.to_string())
+    }
+}
+
+impl From<PriorityError> for SharedTypesError {
+    fn from(err: PriorityError) -> Self {
+        SharedTypesError::Validation(err.to_string())
+    }
+}
+
+impl From<chrono::ParseError> for SharedTypesError {
+    fn from(err: chrono::ParseError) -> Self {
+        SharedTypesError::Validation(format!("invalid date or time: {}", err))
+    }
+}
+
+/// Adds context to the errors converting into [`SharedTypesError`], so every
+/// service wraps them the same way.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use shared_types::error::{ErrorCode, ResultExt, SharedTypesError};
+/// use shared_types::TelegramQueueMessage;
+///
+/// fn load(json: &str) -> Result<TelegramQueueMessage, SharedTypesError> {
+///     serde_json::from_str(json).context("loading the queued message")
+/// }
+///
+/// let err = load("{").unwrap_err();
+/// assert_eq!(err.code(), ErrorCode::Serialization);
+/// assert!(err.to_string().starts_with("loading the queued message: json serialization failed"));
+/// # }
+/// ```
+pub trait ResultExt<T> {
+    /// Converts the error and wraps it in [`SharedTypesError::Context`].
+    fn context(self, context: &str) -> Result<T, SharedTypesError>;
+}
+
+impl<T, E: Into<SharedTypesError>> ResultExt<T> for Result<T, E> {
+    fn context(self, context: &str) -> Result<T, SharedTypesError> {
+        self.map_err(|err| SharedTypesError::Context {
+            context: context.to_string(),
+            source: Box::new(err.into()),
+        })
+    }
+}
+
+/// Returns the Error status message reporting `result` when it failed, ready to
+/// publish for `job`, or `None` when it succeeded.
+///
+/// The error is rendered with its alternate `Display` (`{:#}`), which includes
+/// the context chain of `anyhow` errors, in a `<pre>` block truncated to fit a
+/// Telegram message.
+///
+/// ```rust
+/// use shared_types::error::notify_on_error;
+///
+/// let result: Result<(), String> = Err("connection refused <10.0.0.7>".into());
+/// let msg = notify_on_error(&result, 123, "ftp").unwrap();
+/// assert_eq!(msg.message, "🚨 - <i>ftp</i>\n<pre>connection refused &lt;10.0.0.7&gt;</pre>");
+/// assert!(notify_on_error(&Ok::<_, String>(()), 123, "ftp").is_none());
+/// ```
+pub fn notify_on_error<T, E: fmt::Display>(
+    result: &std::result::Result<T, E>,
+    chat_id: i64,
+    job: impl Into<JobName>,
+) -> Option<TelegramQueueMessage> {
+    let err = result.as_ref().err()?;
+    Some(
+        TelegramMessageBuilder::new(chat_id)
+            .status(MessageStatus::Error)
+            .job_name(job)
+            .code_block(format!("{:#}", err))
+            .sanitize(TELEGRAM_MAX_MESSAGE_LENGTH)
+            .build(),
+    )
+}
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::hash::stable_hash_hex;
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// An error seen by a service, grouped with its repetitions by fingerprint so
+/// identical errors raise one alert with a count.
+///
+/// The fingerprint hashes the service, the error class, the message and the
+/// stack excerpt with every run of digits replaced by `#`, so ids, ports and
+/// line numbers do not split a group.
+///
+/// ```rust
+/// use chrono::{Duration, TimeZone, Utc};
+/// use shared_types::error_report::ErrorReport;
+///
+/// let at = Utc.with_ymd_and_hms(2024, 5, 17, 3, 0, 0).unwrap();
+/// let reports = vec![
+///     ErrorReport::new("orders", "TimeoutError", "order 1042 timed out after 30s", at),
+///     ErrorReport::new("orders", "TimeoutError", "order 1043 timed out after 30s", at + Duration::minutes(5)),
+///     ErrorReport::new("orders", "KeyError", "'sku'", at + Duration::minutes(7)),
+/// ];
+/// let grouped = ErrorReport::group(reports);
+/// assert_eq!(grouped.len(), 2);
+/// assert_eq!(grouped[0].count, 2);
+/// assert_eq!(
+///     grouped[0].to_telegram_message(123).message,
+///     "🚨 - <i>orders</i>\nTimeoutError ×2 since 03:00, last 03:05\norder 1042 timed out after 30s"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ErrorReport {
+    pub service: String,
+    /// Type or class of the error, e.g. `TimeoutError` or `std::io::Error`.
+    #[cfg_attr(feature = "serde", serde(alias = "error_class"))]
+    pub error_class: String,
+    pub message: String,
+    /// The innermost frames of the stack trace.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "stack_excerpt"))]
+    pub stack_excerpt: Option<String>,
+    /// Stable hash grouping repetitions of the error, in hex.
+    pub fingerprint: String,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub count: u64,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "first_seen"))]
+    pub first_seen: DateTime<Utc>,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "last_seen"))]
+    pub last_seen: DateTime<Utc>,
+}
+
+impl ErrorReport {
+    /// Creates the report of a single occurrence at `at`.
+    pub fn new(
+        service: impl Into<String>,
+        error_class: impl Into<String>,
+        message: impl Into<String>,
+        at: DateTime<Utc>,
+    ) -> Self {
+        let mut report = Self {
+            service: service.into(),
+            error_class: error_class.into(),
+            message: message.into(),
+            stack_excerpt: None,
+            fingerprint: String::new(),
+            count: 1,
+            first_seen: at,
+            last_seen: at,
+        };
+        report.fingerprint = report.compute_fingerprint();
+        report
+    }
+
+    pub fn with_stack_excerpt(mut self, stack_excerpt: impl Into<String>) -> Self {
+        self.stack_excerpt = Some(stack_excerpt.into());
+        self.fingerprint = self.compute_fingerprint();
+        self
+    }
+
+    /// Computes the fingerprint from the current fields.
+    pub fn compute_fingerprint(&self) -> String {
+        let message = normalize(&self.message);
+        let stack_excerpt = normalize(self.stack_excerpt.as_deref().unwrap_or(""));
+        stable_hash_hex([
+            self.service.as_str(),
+            self.error_class.as_str(),
+            message.as_str(),
+            stack_excerpt.as_str(),
+        ])
+    }
+
+    /// Adds the occurrences of `other`, a report with the same fingerprint,
+    /// keeping the message and stack excerpt of the earliest one.
+    pub fn merge(&mut self, other: ErrorReport) {
+        self.count += other.count;
+        if other.first_seen < self.first_seen {
+            self.first_seen = other.first_seen;
+            self.message = other.message;
+            self.stack_excerpt = other.stack_excerpt;
+        }
+        self.last_seen = self.last_seen.max(other.last_seen);
+    }
+
+    /// Groups reports by fingerprint, in order of first appearance.
+    pub fn group(reports: impl IntoIterator<Item = ErrorReport>) -> Vec<ErrorReport> {
+        let mut grouped: Vec<ErrorReport> = Vec::new();
+        for report in reports {
+            match grouped
+                .iter_mut()
+                .find(|existing| existing.fingerprint == report.fingerprint)
+            {
+                Some(existing) => existing.merge(report),
+                None => grouped.push(report),
+            }
+        }
+        grouped
+    }
+
+    /// Formats the group for the service's job, deduplicated per fingerprint.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let mut summary = render::escape_text(&self.error_class).into_owned();
+        if self.count > 1 {
+            summary.push_str(&format!(
+                " ×{} since {}, last {}",
+                self.count,
+                self.first_seen.format("%H:%M"),
+                self.last_seen.format("%H:%M")
+            ));
+        }
+        TelegramMessageBuilder::new(chat_id)
+            .status(MessageStatus::Error)
+            .job_name(self.service.as_str())
+            .summary(summary)
+            .content(render::escape_text(&self.message).into_owned())
+            .code_block(self.stack_excerpt.clone().unwrap_or_default())
+            .dedup_key(format!("error:{}", self.fingerprint))
+            .build()
+    }
+}
+
+/// Replaces every run of ASCII digits with `#`.
+fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut in_digits = false;
+    for c in text.chars() {
+        if !c.is_ascii_digit() {
+            normalized.push(c);
+        } else if !in_digits {
+            normalized.push('#');
+        }
+        in_digits = c.is_ascii_digit();
+    }
+    normalized
+}
@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+pub use crate::transfer::{TransferDirection, TransferOutcome};
+
+/// Rows of the file table rendered in Telegram messages; the rest is counted.
+pub const FTP_REPORT_MAX_ROWS: usize = 30;
+
+/// Longest file name shown in the file table.
+const MAX_NAME_LENGTH: usize = 40;
+
+/// A file of an FTP transfer report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct FtpFile {
+    pub path: String,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "size_bytes"))]
+    pub size_bytes: u64,
+    pub outcome: TransferOutcome,
+}
+
+impl FtpFile {
+    pub fn new(path: impl Into<String>, size_bytes: u64, outcome: TransferOutcome) -> Self {
+        Self {
+            path: path.into(),
+            size_bytes,
+            outcome,
+        }
+    }
+}
+
+/// Report of an FTP job run: the files moved to or from a host.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use shared_types::MessageStatus;
+/// use shared_types::ftp::{FtpFile, FtpTransferReport, TransferDirection, TransferOutcome};
+///
+/// let report = FtpTransferReport::new("ftp01", TransferDirection::Upload)
+///     .with_file(FtpFile::new("orders.csv", 2048, TransferOutcome::Transferred))
+///     .with_file(FtpFile::new("stock.csv", 512, TransferOutcome::Failed))
+///     .with_error("stock.csv: 550 permission denied")
+///     .with_duration(Duration::from_millis(1500));
+/// assert_eq!(report.total_bytes, 2048);
+/// assert_eq!(report.severity(), MessageStatus::Warn);
+///
+/// let msg = report.to_telegram_message(123);
+/// assert_eq!(
+///     msg.message,
+///     "⚠️ - <i>ftp</i>\nuploaded 1/2 files (2.0 KiB) to ftp01 in 1.5s\n\
+///      <pre>✓ orders.csv 2.0 KiB\n✗ stock.csv    512 B</pre>\n\
+///      <blockquote expandable>stock.csv: 550 permission denied</blockquote>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct FtpTransferReport {
+    pub host: String,
+    pub direction: TransferDirection,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub files: Vec<FtpFile>,
+    /// Bytes of the transferred files.
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "total_bytes"))]
+    pub total_bytes: u64,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u64>"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<u64>))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "durationMs",
+            with = "crate::duration_ms::option",
+            skip_serializing_if = "Option::is_none",
+            alias = "duration_ms"
+        )
+    )]
+    pub duration: Option<Duration>,
+    /// Errors not tied to a single file, e.g. connection failures.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub errors: Vec<String>,
+}
+
+impl FtpTransferReport {
+    pub fn new(host: impl Into<String>, direction: TransferDirection) -> Self {
+        Self {
+            host: host.into(),
+            direction,
+            files: Vec::new(),
+            total_bytes: 0,
+            duration: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Adds a file, counting its size in `total_bytes` when it was transferred.
+    pub fn with_file(mut self, file: FtpFile) -> Self {
+        if file.outcome == TransferOutcome::Transferred {
+            self.total_bytes = self.total_bytes.saturating_add(file.size_bytes);
+        }
+        self.files.push(file);
+        self
+    }
+
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.errors.push(error.into());
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Returns the number of files with the given outcome.
+    pub fn count(&self, outcome: TransferOutcome) -> usize {
+        self.files.iter().filter(|file| file.outcome == outcome).count()
+    }
+
+    /// `Error` when something failed and nothing was transferred, `Warn` when
+    /// something failed, `Ok` otherwise.
+    pub fn severity(&self) -> MessageStatus {
+        let failed = self.count(TransferOutcome::Failed) > 0 || !self.errors.is_empty();
+        match (failed, self.count(TransferOutcome::Transferred)) {
+            (false, _) => MessageStatus::Ok,
+            (true, 0) => MessageStatus::Error,
+            (true, _) => MessageStatus::Warn,
+        }
+    }
+
+    /// Formats the report for the `ftp` job: a summary line, the file table in
+    /// a `<pre>` block and the errors as details.
+    ///
+    /// The table lists at most [`FTP_REPORT_MAX_ROWS`] files.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let (verb, preposition) = match self.direction {
+            TransferDirection::Upload => ("uploaded", "to"),
+            TransferDirection::Download => ("downloaded", "from"),
+        };
+        let mut summary = format!(
+            "{} {}/{} files ({}) {} {}",
+            verb,
+            self.count(TransferOutcome::Transferred),
+            self.files.len(),
+            render::bytes(self.total_bytes),
+            preposition,
+            render::escape_text(&self.host)
+        );
+        if let Some(duration) = self.duration {
+            summary.push_str(&format!(" in {}", render::duration(duration)));
+        }
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("ftp")
+            .summary(summary)
+            .code_block(self.file_table())
+            .details(render::escape_text(&self.errors.join("\n")))
+            .build()
+    }
+
+    /// Lays out the files as aligned `marker name size` rows.
+    fn file_table(&self) -> String {
+        let rows: Vec<(String, String, &str)> = self
+            .files
+            .iter()
+            .take(FTP_REPORT_MAX_ROWS)
+            .map(|file| {
+                let name = render::truncate(&file.path, MAX_NAME_LENGTH);
+                (name, render::bytes(file.size_bytes), file.outcome.marker())
+            })
+            .collect();
+        let name_width = rows.iter().map(|(name, ..)| name.chars().count()).max().unwrap_or(0);
+        let size_width = rows.iter().map(|(_, size, _)| size.len()).max().unwrap_or(0);
+        let mut lines: Vec<String> = rows
+            .iter()
+            .map(|(name, size, marker)| format!("{} {:<name_width$} {:>size_width$}", marker, name, size))
+            .collect();
+        if self.files.len() > FTP_REPORT_MAX_ROWS {
+            lines.push(format!("… and {} more", self.files.len() - FTP_REPORT_MAX_ROWS));
+        }
+        lines.join("\n")
+    }
+}
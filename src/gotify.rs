@@ -0,0 +1,114 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde_json::{Value, json};
+
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// Highest Gotify priority.
+pub const GOTIFY_MAX_PRIORITY: u8 = 10;
+
+/// Returns the Gotify priority of a status: 8 and above wake clients up, so only
+/// `Error` gets there.
+pub fn gotify_priority(status: MessageStatus) -> u8 {
+    match status {
+        MessageStatus::None => 5,
+        MessageStatus::Info => 2,
+        MessageStatus::Ok => 4,
+        MessageStatus::Warn => 7,
+        MessageStatus::Error => GOTIFY_MAX_PRIORITY,
+    }
+}
+
+/// Represents a message received from the queue to be pushed to a Gotify server.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use shared_types::{GotifyQueueMessage, MessageStatus};
+///
+/// let msg = GotifyQueueMessage::new("kiosk", "Backup", "**ftp01** is full")
+///     .with_status(MessageStatus::Error)
+///     .with_markdown(true);
+/// let body = msg.to_gotify_body();
+/// assert_eq!(body["priority"], 10);
+/// assert_eq!(body["extras"]["client::display"]["contentType"], "text/markdown");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct GotifyQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    /// Name of the application token in the worker configuration; the token is never queued.
+    pub app: String,
+    pub title: String,
+    pub message: String,
+    /// Priority from 0 to [`GOTIFY_MAX_PRIORITY`].
+    pub priority: u8,
+    /// Whether clients render `message` as markdown.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub markdown: bool,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+}
+
+impl GotifyQueueMessage {
+    /// Creates a plain text message without status, at the priority of [`MessageStatus::None`].
+    pub fn new(app: impl Into<String>, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            app: app.into(),
+            title: title.into(),
+            message: message.into(),
+            priority: gotify_priority(MessageStatus::None),
+            markdown: false,
+            status: MessageStatus::None,
+            dedup_key: None,
+        }
+    }
+
+    /// Sets the status and the priority derived from it, see [`gotify_priority`].
+    pub fn with_status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self.priority = gotify_priority(status);
+        self
+    }
+
+    /// Sets the priority, capped at [`GOTIFY_MAX_PRIORITY`].
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority.min(GOTIFY_MAX_PRIORITY);
+        self
+    }
+
+    /// Sets whether clients render `message` as markdown.
+    pub fn with_markdown(mut self, markdown: bool) -> Self {
+        self.markdown = markdown;
+        self
+    }
+
+    /// Returns the body to post to the Gotify `/message` endpoint.
+    #[cfg(feature = "serde")]
+    pub fn to_gotify_body(&self) -> Value {
+        let mut body = json!({
+            "title": self.title,
+            "message": self.message,
+            "priority": self.priority.min(GOTIFY_MAX_PRIORITY),
+        });
+        if self.markdown {
+            body["extras"] = json!({ "client::display": { "contentType": "text/markdown" } });
+        }
+        body
+    }
+}
@@ -0,0 +1,31 @@
+//! Stable, dependency-free hashing used to derive keys shared across services.
+//!
+//! `std::hash::DefaultHasher` is not guaranteed to be stable across Rust releases,
+//! so keys that are compared between processes use 64-bit FNV-1a instead.
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hashes the given parts with FNV-1a, separating them with a `0x1f` unit separator
+/// so that `["ab", "c"]` and `["a", "bc"]` produce different values.
+pub(crate) fn stable_hash<'a>(parts: impl IntoIterator<Item = &'a str>) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    for (index, part) in parts.into_iter().enumerate() {
+        if index > 0 {
+            hash = fnv_step(hash, 0x1f);
+        }
+        for byte in part.bytes() {
+            hash = fnv_step(hash, byte);
+        }
+    }
+    hash
+}
+
+/// Same as [`stable_hash`], formatted as a fixed-width lowercase hex string.
+pub(crate) fn stable_hash_hex<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    format!("{:016x}", stable_hash(parts))
+}
+
+fn fnv_step(hash: u64, byte: u8) -> u64 {
+    (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+}
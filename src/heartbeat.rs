@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Health a service reports about itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum HealthStatus {
+    #[default]
+    Healthy,
+    /// Running with reduced functionality, e.g. a dependency is unreachable.
+    Degraded,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    /// Returns the message status used when alerting on the health.
+    pub fn message_status(&self) -> MessageStatus {
+        match self {
+            HealthStatus::Healthy => MessageStatus::Ok,
+            HealthStatus::Degraded => MessageStatus::Warn,
+            HealthStatus::Unhealthy => MessageStatus::Error,
+        }
+    }
+}
+
+/// Liveness signal sent periodically by every service instance to the watchdog.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use chrono::Utc;
+/// use shared_types::heartbeat::Heartbeat;
+///
+/// let now = Utc::now();
+/// let beat = Heartbeat::new("ftp-sync", "ftp01", now - Duration::from_secs(300))
+///     .with_version("1.4.2")
+///     .with_uptime(Duration::from_secs(3600));
+/// assert!(!beat.is_stale_at(now, Duration::from_secs(600)));
+///
+/// let stale = beat.stale_at(now, Duration::from_secs(120)).unwrap();
+/// assert_eq!(stale.silent_for, Duration::from_secs(300));
+/// assert_eq!(
+///     stale.to_telegram_message(123).message,
+///     "🚨 - <i>ftp-sync</i>\nno heartbeat from ftp01 for 5m 00s\nlast version <code>1.4.2</code>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Heartbeat {
+    pub service: String,
+    /// Instance of the service, e.g. a hostname or pod name.
+    pub instance: String,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub timestamp: DateTime<Utc>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status: HealthStatus,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub version: Option<String>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u64>"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<u64>))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "uptimeMs",
+            with = "crate::duration_ms::option",
+            skip_serializing_if = "Option::is_none",
+            alias = "uptime_ms"
+        )
+    )]
+    pub uptime: Option<Duration>,
+}
+
+impl Heartbeat {
+    /// Creates a healthy heartbeat sent at `timestamp`.
+    pub fn new(service: impl Into<String>, instance: impl Into<String>, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            service: service.into(),
+            instance: instance.into(),
+            timestamp,
+            status: HealthStatus::default(),
+            version: None,
+            uptime: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: HealthStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn with_uptime(mut self, uptime: Duration) -> Self {
+        self.uptime = Some(uptime);
+        self
+    }
+
+    /// Returns true when no heartbeat newer than `max_age` was seen at `now`.
+    pub fn is_stale_at(&self, now: DateTime<Utc>, max_age: Duration) -> bool {
+        self.stale_at(now, max_age).is_some()
+    }
+
+    /// Returns the stale heartbeat report when this is older than `max_age` at `now`.
+    pub fn stale_at(&self, now: DateTime<Utc>, max_age: Duration) -> Option<StaleHeartbeat> {
+        let silent_for = (now - self.timestamp).to_std().ok()?;
+        (silent_for > max_age).then(|| StaleHeartbeat {
+            service: self.service.clone(),
+            instance: self.instance.clone(),
+            last_seen: self.timestamp,
+            silent_for,
+            last_version: self.version.clone(),
+        })
+    }
+}
+
+/// An instance whose heartbeats stopped, as detected by the watchdog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct StaleHeartbeat {
+    pub service: String,
+    pub instance: String,
+    /// Timestamp of the last heartbeat received.
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "last_seen"))]
+    pub last_seen: DateTime<Utc>,
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+    #[cfg_attr(feature = "serde", serde(rename = "silentForMs", with = "crate::duration_ms", alias = "silent_for_ms"))]
+    pub silent_for: Duration,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "last_version"))]
+    pub last_version: Option<String>,
+}
+
+impl StaleHeartbeat {
+    /// Returns the stale instances among the latest heartbeat of each instance.
+    pub fn detect<'a>(
+        heartbeats: impl IntoIterator<Item = &'a Heartbeat>,
+        now: DateTime<Utc>,
+        max_age: Duration,
+    ) -> Vec<StaleHeartbeat> {
+        heartbeats
+            .into_iter()
+            .filter_map(|heartbeat| heartbeat.stale_at(now, max_age))
+            .collect()
+    }
+
+    /// Formats an `Error` alert naming the silent instance.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let mut builder = TelegramMessageBuilder::new(chat_id)
+            .status(MessageStatus::Error)
+            .job_name(&self.service)
+            .summary(format!(
+                "no heartbeat from {} for {}",
+                render::escape_text(&self.instance),
+                render::duration(self.silent_for)
+            ))
+            .dedup_key(format!("heartbeat:{}:{}", self.service, self.instance));
+        if let Some(version) = &self.last_version {
+            builder = builder.content("last version").code(version);
+        }
+        builder.build()
+    }
+}
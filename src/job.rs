@@ -0,0 +1,312 @@
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Identifier of a job, as shown in messages and used as a key by dashboards.
+///
+/// Plain strings convert into a `JobName`, so existing `.job_name("ftp")` calls
+/// keep working. For compile-time consistency define the known jobs once with
+/// [`job_registry!`](crate::job_registry) and pass the enum variants instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct JobName(String);
+
+impl JobName {
+    /// Creates a new JobName.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    /// Returns the job name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the JobName, returning the inner string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for JobName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for JobName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for JobName {
+    fn from(name: &str) -> Self {
+        Self(name.to_string())
+    }
+}
+
+impl From<String> for JobName {
+    fn from(name: String) -> Self {
+        Self(name)
+    }
+}
+
+impl From<&String> for JobName {
+    fn from(name: &String) -> Self {
+        Self(name.clone())
+    }
+}
+
+impl From<&JobName> for JobName {
+    fn from(name: &JobName) -> Self {
+        name.clone()
+    }
+}
+
+/// Lifecycle state of a job run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    /// The run had nothing to do, e.g. no new files or a lock held by another run.
+    Skipped,
+    TimedOut,
+}
+
+impl JobStatus {
+    /// Returns true once the run is over.
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobStatus::Pending | JobStatus::Running)
+    }
+
+    /// Returns the message status used when reporting the run.
+    pub fn message_status(&self) -> MessageStatus {
+        match self {
+            JobStatus::Pending | JobStatus::Running | JobStatus::Skipped => MessageStatus::Info,
+            JobStatus::Succeeded => MessageStatus::Ok,
+            JobStatus::Failed | JobStatus::TimedOut => MessageStatus::Error,
+        }
+    }
+
+    /// Returns the status as shown in messages, e.g. `timed out`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+            JobStatus::Skipped => "skipped",
+            JobStatus::TimedOut => "timed out",
+        }
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Report of a single job run, emitted by every scheduler when a run completes.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use chrono::Utc;
+/// use shared_types::MessageStatus;
+/// use shared_types::job::{JobRunReport, JobStatus};
+///
+/// let started = Utc::now();
+/// let report = JobRunReport::new("ftp", "run-42", started)
+///     .with_items_processed(120)
+///     .with_error("550 <a.csv>: permission denied")
+///     .finish(JobStatus::Failed, started + Duration::from_secs(75));
+/// assert_eq!(report.duration, Some(Duration::from_secs(75)));
+///
+/// let msg = report.to_telegram_message(123);
+/// assert_eq!(msg.status, MessageStatus::Error);
+/// assert_eq!(
+///     msg.message,
+///     "🚨 - <i>ftp</i>\nfailed after 1m 15s\nrun <code>run-42</code>, 120 items processed\n\
+///      <pre>550 &lt;a.csv&gt;: permission denied</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct JobRunReport {
+    #[cfg_attr(feature = "serde", serde(alias = "job_name"))]
+    pub job_name: JobName,
+    /// Identifier of the run, unique per job.
+    #[cfg_attr(feature = "serde", serde(alias = "run_id"))]
+    pub run_id: String,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "started_at"))]
+    pub started_at: DateTime<Utc>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "finished_at"))]
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u64>"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<u64>))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "durationMs",
+            with = "crate::duration_ms::option",
+            skip_serializing_if = "Option::is_none",
+            alias = "duration_ms"
+        )
+    )]
+    pub duration: Option<Duration>,
+    pub status: JobStatus,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub error: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "items_processed"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    pub items_processed: Option<u64>,
+}
+
+impl JobRunReport {
+    /// Creates the report of a run started at `started_at`, in the `Running` state.
+    pub fn new(job_name: impl Into<JobName>, run_id: impl Into<String>, started_at: DateTime<Utc>) -> Self {
+        Self {
+            job_name: job_name.into(),
+            run_id: run_id.into(),
+            started_at,
+            finished_at: None,
+            duration: None,
+            status: JobStatus::Running,
+            error: None,
+            items_processed: None,
+        }
+    }
+
+    /// Marks the run as finished at `finished_at`, computing its duration.
+    pub fn finish(mut self, status: JobStatus, finished_at: DateTime<Utc>) -> Self {
+        self.status = status;
+        self.finished_at = Some(finished_at);
+        self.duration = (finished_at - self.started_at).to_std().ok();
+        self
+    }
+
+    /// Sets the error the run failed with, shown in a `<pre>` block.
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+
+    /// Sets the number of items the run processed.
+    pub fn with_items_processed(mut self, items_processed: u64) -> Self {
+        self.items_processed = Some(items_processed);
+        self
+    }
+
+    /// Formats the report as a Telegram message: the status and duration as
+    /// summary, the run id and item count as content and the error in a `<pre>` block.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let summary = match self.duration {
+            Some(duration) => format!("{} after {}", self.status, render::duration(duration)),
+            None => self.status.to_string(),
+        };
+        let mut content = format!("run <code>{}</code>", render::escape_text(&self.run_id));
+        if let Some(items_processed) = self.items_processed {
+            content.push_str(&format!(", {} items processed", items_processed));
+        }
+        let mut builder = TelegramMessageBuilder::new(chat_id)
+            .status(self.status.message_status())
+            .job_name(&self.job_name)
+            .summary(summary)
+            .content(content);
+        if let Some(error) = &self.error {
+            builder = builder.code_block(error);
+        }
+        builder.build()
+    }
+}
+
+/// Declares an enum of known jobs that converts into [`JobName`](crate::job::JobName).
+///
+/// Each variant is mapped to the string used in messages and dashboards. The
+/// generated enum gets `as_str()`, an `ALL` constant, `Display`, and
+/// `From<Enum> for JobName`, so it can be passed to `.job_name()` and to
+/// `telegram_msg!`.
+///
+/// Usage:
+/// ```rust
+/// use shared_types::{job_registry, telegram_msg};
+///
+/// job_registry! {
+///     pub enum Job {
+///         Ftp => "ftp",
+///         Backup => "backup",
+///     }
+/// }
+///
+/// let msg = telegram_msg!(chat_id: 123, status: Ok, job: Job::Backup, content: "done");
+/// assert_eq!(Job::Ftp.as_str(), "ftp");
+/// assert_eq!(Job::ALL.len(), 2);
+/// ```
+#[macro_export]
+macro_rules! job_registry {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant),+
+        }
+
+        impl $name {
+            /// Every job declared in the registry.
+            pub const ALL: &'static [$name] = &[$($name::$variant),+];
+
+            /// Returns the job name used in messages and dashboards.
+            pub const fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $value),+
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::core::convert::From<$name> for $crate::job::JobName {
+            fn from(job: $name) -> Self {
+                $crate::job::JobName::new(job.as_str())
+            }
+        }
+    };
+}
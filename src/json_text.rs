@@ -0,0 +1,49 @@
+//! Serde helpers for fields holding JSON as text, so they exist whatever the
+//! features and only their wire form needs `serde`.
+
+/// `BTreeMap<String, String>` written as a JSON object of strings; other JSON
+/// values are read as their JSON text, e.g. `3` as `"3"`.
+///
+/// Use with `#[serde(default, deserialize_with = "crate::json_text::string_map")]`.
+pub(crate) fn string_map<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<std::collections::BTreeMap<String, String>, D::Error> {
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    let map = std::collections::BTreeMap::<String, Value>::deserialize(deserializer)?;
+    Ok(map
+        .into_iter()
+        .map(|(key, value)| match value {
+            Value::String(value) => (key, value),
+            value => (key, value.to_string()),
+        })
+        .collect())
+}
+
+/// JSON text in an `Option<String>`, embedded in the output as JSON rather
+/// than as a string.
+///
+/// Use with `#[serde(default, with = "crate::json_text::option")]`.
+pub(crate) mod option {
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_json::Value;
+
+    pub(crate) fn serialize<S: Serializer>(json: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+        match json {
+            Some(json) => serde_json::from_str::<Value>(json)
+                .map_err(S::Error::custom)?
+                .serialize(serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+        let value = Option::<Value>::deserialize(deserializer)?;
+        value
+            .map(|value| serde_json::to_string(&value).map_err(D::Error::custom))
+            .transpose()
+    }
+}
@@ -0,0 +1,122 @@
+//! `tracing` layer sending chat alerts for severe events.
+//!
+//! [`TelegramAlertLayer`] turns every event at or above its level into a
+//! [`LogEvent`] and then a [`TelegramQueueMessage`], with the target as job
+//! name and the event fields in a `<pre>` block.
+//! The messages are handed to a channel or closure supplied by the caller,
+//! which publishes them; it must not block nor log at the alerting level.
+
+use std::fmt;
+use std::sync::mpsc::Sender;
+
+use chrono::Utc;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+use crate::logging::LogEvent;
+use crate::telegram::TelegramQueueMessage;
+
+type Sink = Box<dyn Fn(TelegramQueueMessage) + Send + Sync>;
+
+/// Layer converting events at or above a level, `ERROR` by default, into
+/// Telegram messages.
+///
+/// ```rust
+/// use std::sync::mpsc;
+/// use shared_types::MessageStatus;
+/// use shared_types::layer::TelegramAlertLayer;
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let subscriber = tracing_subscriber::registry().with(TelegramAlertLayer::new(-100123, sender));
+///
+/// tracing::subscriber::with_default(subscriber, || {
+///     tracing::warn!("disk at 85%");
+///     tracing::error!(target: "ftp::upload", attempt = 3, "upload failed");
+/// });
+/// let alert = receiver.try_recv().unwrap();
+/// assert_eq!(alert.chat_id, -100123);
+/// assert_eq!(alert.status, MessageStatus::Error);
+/// assert!(alert.message.starts_with("🚨 - <i>ftp::upload</i>\nupload failed"));
+/// assert!(receiver.try_recv().is_err());
+/// ```
+pub struct TelegramAlertLayer {
+    chat_id: i64,
+    min_level: Level,
+    sink: Sink,
+}
+
+impl TelegramAlertLayer {
+    /// Sends the alerts for `chat_id` to `sender`, dropping them once the
+    /// receiver is gone.
+    pub fn new(chat_id: i64, sender: Sender<TelegramQueueMessage>) -> Self {
+        Self::with_sink(chat_id, move |message| {
+            let _ = sender.send(message);
+        })
+    }
+
+    /// Passes the alerts for `chat_id` to `sink`, e.g. a closure doing a
+    /// `try_send` on an async channel.
+    pub fn with_sink(chat_id: i64, sink: impl Fn(TelegramQueueMessage) + Send + Sync + 'static) -> Self {
+        Self {
+            chat_id,
+            min_level: Level::ERROR,
+            sink: Box::new(sink),
+        }
+    }
+
+    /// Alerts on events at `min_level` or more severe.
+    pub fn with_min_level(mut self, min_level: Level) -> Self {
+        self.min_level = min_level;
+        self
+    }
+}
+
+impl fmt::Debug for TelegramAlertLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TelegramAlertLayer")
+            .field("chat_id", &self.chat_id)
+            .field("min_level", &self.min_level)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for TelegramAlertLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        // Levels compare by verbosity, `ERROR` being the smallest.
+        if *metadata.level() > self.min_level {
+            return;
+        }
+        let mut visitor = EventVisitor(LogEvent::new(
+            (*metadata.level()).into(),
+            metadata.target(),
+            "",
+            Utc::now(),
+        ));
+        event.record(&mut visitor);
+        (self.sink)(visitor.0.to_telegram_message(self.chat_id));
+    }
+}
+
+/// Collects the message and fields of an event.
+struct EventVisitor(LogEvent);
+
+impl Visit for EventVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0.message = format!("{:?}", value);
+        } else {
+            self.0.fields.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.message = value.to_string();
+        } else {
+            self.0.fields.insert(field.name().to_string(), value.to_string());
+        }
+    }
+}
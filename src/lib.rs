@@ -1,3 +1,109 @@
+pub mod alert;
+#[cfg(feature = "amqp")]
+pub mod amqp;
+pub mod audit;
+#[cfg(feature = "avro")]
+pub mod avro;
+pub mod backup;
+pub mod cert;
+pub mod chat_directory;
+pub mod compression;
+pub mod consumer;
+pub mod content;
+pub mod cron;
+pub mod deploy;
+pub mod discord;
+pub mod disk;
+pub mod document;
+pub mod email;
+pub mod envelope;
+pub mod error;
+pub mod error_report;
+pub mod ftp;
+pub mod gotify;
+pub mod heartbeat;
+pub mod job;
+#[cfg(feature = "tracing-subscriber")]
+pub mod layer;
+pub mod limits;
+pub mod logging;
+pub mod maintenance;
+pub mod matrix;
+pub mod metric;
+pub mod notification;
+pub mod order;
+pub mod pagerduty;
+pub mod payload;
+pub mod policy;
+pub mod preferences;
+pub mod priority;
+pub mod producer;
+#[cfg(feature = "protobuf")]
+pub mod proto;
+pub mod push;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod quota;
+pub mod rate_limit;
+pub mod retry;
+pub mod routes;
+pub mod schema;
+pub mod signing;
+pub mod sla;
+pub mod slack;
+pub mod sms;
+#[cfg(feature = "serde")]
+pub mod sqs;
+#[cfg(feature = "sqlx")]
+pub mod sql;
+pub mod status;
+#[cfg(feature = "serde")]
+pub mod stream;
+pub mod subject;
+pub mod sync;
+pub mod teams;
 pub mod telegram;
+pub mod trace;
+pub mod transfer;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod webhook;
 
-pub use telegram::{TelegramQueueMessage, MessageStatus, TelegramMessageBuilder};
+#[cfg(feature = "borsh")]
+mod borsh_with;
+#[cfg(feature = "serde")]
+mod duration_ms;
+mod hash;
+#[cfg(feature = "serde")]
+mod json_text;
+mod render;
+
+pub use chat_directory::ChatDirectory;
+pub use consumer::{ConsumeOutcome, ConsumerResult};
+pub use content::NotificationContent;
+pub use discord::{DiscordMessageBuilder, DiscordQueueMessage};
+pub use email::{EmailAttachment, EmailMessageBuilder, EmailQueueMessage};
+pub use envelope::QueueEnvelope;
+pub use error::{ErrorCode, Result, SharedTypesError, notify_on_error};
+pub use gotify::GotifyQueueMessage;
+pub use job::JobName;
+pub use matrix::MatrixQueueMessage;
+pub use notification::Notification;
+pub use pagerduty::PagerDutyEvent;
+pub use payload::{QueueMessageKind, QueuePayload};
+pub use policy::{QuietHours, SendPolicy};
+pub use preferences::NotificationPreferences;
+pub use priority::Priority;
+pub use producer::ProducerInfo;
+pub use push::PushQueueMessage;
+pub use retry::{RetryInfo, RetryPolicy};
+pub use slack::{SlackMessageBuilder, SlackQueueMessage};
+pub use sms::SmsQueueMessage;
+pub use teams::{TeamsMessageBuilder, TeamsQueueMessage};
+pub use telegram::{
+    TelegramQueueMessage, TelegramBroadcastMessage, MessageStatus, TelegramMessageBuilder,
+    SanitizeOptions, StaticTelegramMessage,
+};
+#[cfg(feature = "derive")]
+pub use telegram::telegram_html;
+pub use webhook::WebhookQueueMessage;
@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// Largest message body accepted by SQS (256 KiB).
+pub const SQS_MAX_MESSAGE_BYTES: usize = 256 * 1024;
+
+/// Default RabbitMQ `frame_max`; larger bodies are split over several frames.
+pub const RABBITMQ_DEFAULT_FRAME_MAX: usize = 128 * 1024;
+
+/// Default RabbitMQ `max_message_size` (16 MiB since RabbitMQ 4.0).
+pub const RABBITMQ_DEFAULT_MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Error returned by [`crate::QueueEnvelope::validate_size`].
+///
+/// Non-exhaustive, as `Serialize` only exists with the `serde` feature.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SizeError {
+    /// The serialized message exceeds the limit.
+    TooLarge { size: usize, limit: usize },
+    /// The message could not be serialized.
+    #[cfg(feature = "serde")]
+    Serialize(serde_json::Error),
+}
+
+impl fmt::Display for SizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SizeError::TooLarge { size, limit } => {
+                write!(f, "message is {} bytes, exceeding the limit of {} bytes", size, limit)
+            }
+            #[cfg(feature = "serde")]
+            SizeError::Serialize(err) => write!(f, "failed to serialize message: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SizeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SizeError::TooLarge { .. } => None,
+            #[cfg(feature = "serde")]
+            SizeError::Serialize(err) => Some(err),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Level of a log record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<LogLevel> for MessageStatus {
+    /// `Trace` and `Debug` have no status, the other levels map to their namesake.
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace | LogLevel::Debug => MessageStatus::None,
+            LogLevel::Info => MessageStatus::Info,
+            LogLevel::Warn => MessageStatus::Warn,
+            LogLevel::Error => MessageStatus::Error,
+        }
+    }
+}
+
+impl From<MessageStatus> for LogLevel {
+    /// Lossy reverse of the status mapping: `None` is `Debug` and `Ok` is `Info`.
+    fn from(status: MessageStatus) -> Self {
+        match status {
+            MessageStatus::None => LogLevel::Debug,
+            MessageStatus::Info | MessageStatus::Ok => LogLevel::Info,
+            MessageStatus::Warn => LogLevel::Warn,
+            MessageStatus::Error => LogLevel::Error,
+        }
+    }
+}
+
+/// ```rust
+/// use shared_types::MessageStatus;
+///
+/// assert_eq!(MessageStatus::from(log::Level::Warn), MessageStatus::Warn);
+/// assert_eq!(MessageStatus::from(log::Level::Debug), MessageStatus::None);
+/// assert_eq!(log::Level::from(MessageStatus::Ok), log::Level::Info);
+/// ```
+#[cfg(feature = "log")]
+impl From<log::Level> for LogLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Trace => LogLevel::Trace,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Warn => LogLevel::Warn,
+            log::Level::Error => LogLevel::Error,
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<LogLevel> for log::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => log::Level::Trace,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Error => log::Level::Error,
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<log::Level> for MessageStatus {
+    fn from(level: log::Level) -> Self {
+        LogLevel::from(level).into()
+    }
+}
+
+#[cfg(feature = "log")]
+impl From<MessageStatus> for log::Level {
+    fn from(status: MessageStatus) -> Self {
+        LogLevel::from(status).into()
+    }
+}
+
+/// ```rust
+/// use shared_types::MessageStatus;
+///
+/// assert_eq!(MessageStatus::from(tracing::Level::ERROR), MessageStatus::Error);
+/// assert_eq!(MessageStatus::from(tracing::Level::TRACE), MessageStatus::None);
+/// assert_eq!(tracing::Level::from(MessageStatus::None), tracing::Level::DEBUG);
+/// ```
+#[cfg(feature = "tracing")]
+impl From<tracing::Level> for LogLevel {
+    fn from(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::TRACE => LogLevel::Trace,
+            tracing::Level::DEBUG => LogLevel::Debug,
+            tracing::Level::INFO => LogLevel::Info,
+            tracing::Level::WARN => LogLevel::Warn,
+            _ => LogLevel::Error,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<LogLevel> for tracing::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => tracing::Level::TRACE,
+            LogLevel::Debug => tracing::Level::DEBUG,
+            LogLevel::Info => tracing::Level::INFO,
+            LogLevel::Warn => tracing::Level::WARN,
+            LogLevel::Error => tracing::Level::ERROR,
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<tracing::Level> for MessageStatus {
+    fn from(level: tracing::Level) -> Self {
+        LogLevel::from(level).into()
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl From<MessageStatus> for tracing::Level {
+    fn from(status: MessageStatus) -> Self {
+        LogLevel::from(status).into()
+    }
+}
+
+/// A log record shipped by the log forwarder.
+///
+/// ```rust
+/// use chrono::Utc;
+/// use shared_types::MessageStatus;
+/// use shared_types::logging::{LogEvent, LogLevel};
+///
+/// let event = LogEvent::new(LogLevel::Error, "ftp::upload", "upload failed: <a.csv>", Utc::now())
+///     .with_field("host", "ftp01")
+///     .with_field("attempt", 3);
+/// let msg = event.to_telegram_message(123);
+/// assert_eq!(msg.status, MessageStatus::Error);
+/// assert_eq!(
+///     msg.message,
+///     "🚨 - <i>ftp::upload</i>\nupload failed: &lt;a.csv&gt;\n<pre>attempt=3\nhost=ftp01</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct LogEvent {
+    pub level: LogLevel,
+    /// Module or component that logged the record, e.g. `ftp::upload`.
+    pub target: String,
+    pub message: String,
+    /// Structured fields of the record, as text; JSON numbers and booleans
+    /// are read as their JSON text.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty", deserialize_with = "crate::json_text::string_map")
+    )]
+    pub fields: BTreeMap<String, String>,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl LogEvent {
+    pub fn new(
+        level: LogLevel,
+        target: impl Into<String>,
+        message: impl Into<String>,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            level,
+            target: target.into(),
+            message: message.into(),
+            fields: BTreeMap::new(),
+            timestamp,
+        }
+    }
+
+    /// Adds a structured field, e.g. `with_field("attempt", 3)`.
+    pub fn with_field(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.fields.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// Returns the message status of the level.
+    pub fn status(&self) -> MessageStatus {
+        MessageStatus::from(self.level)
+    }
+
+    /// Formats the record with the target as job name, the message as summary
+    /// and the fields as `key=value` lines in a `<pre>` block.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.status())
+            .job_name(&self.target)
+            .summary(render::escape_text(&self.message))
+            .code_block(
+                self.fields
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+            .build()
+    }
+}
+
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Duration, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::telegram::TelegramQueueMessage;
+
+/// How often a maintenance window repeats.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Recurrence {
+    /// The window happens once.
+    #[default]
+    Once,
+    /// The window repeats every 24 hours from its start.
+    Daily,
+    /// The window repeats every 7 days from its start.
+    Weekly,
+}
+
+impl Recurrence {
+    /// Returns the time between two occurrences, `None` for a single window.
+    pub fn period(&self) -> Option<Duration> {
+        match self {
+            Recurrence::Once => None,
+            Recurrence::Daily => Some(Duration::days(1)),
+            Recurrence::Weekly => Some(Duration::weeks(1)),
+        }
+    }
+}
+
+/// Planned maintenance of some services, during which their alerts are muted.
+///
+/// A message belongs to a service when its job name or its `service` metadata
+/// entry is one of `services`; an empty list covers every service. Recurring
+/// windows repeat in UTC, so they shift by an hour in local time across DST
+/// changes. Force sent messages are never suppressed.
+///
+/// ```rust
+/// use chrono::{Duration, TimeZone, Utc};
+/// use shared_types::TelegramQueueMessage;
+/// use shared_types::maintenance::{MaintenanceWindow, Recurrence};
+///
+/// let start = Utc.with_ymd_and_hms(2024, 5, 17, 2, 0, 0).unwrap();
+/// let window = MaintenanceWindow::new(start, start + Duration::hours(1), "nightly vacuum")
+///     .with_service("ftp")
+///     .with_recurrence(Recurrence::Daily);
+/// let next_night = start + Duration::days(1) + Duration::minutes(30);
+/// assert!(window.is_active(next_night));
+/// assert!(!window.is_active(next_night + Duration::hours(1)));
+///
+/// let ftp = TelegramQueueMessage::builder(123).job_name("ftp").content("timeout").build();
+/// let backup = TelegramQueueMessage::builder(123).job_name("backup").content("failed").build();
+/// assert!(window.suppresses_at(&ftp, next_night));
+/// assert!(!window.suppresses_at(&backup, next_night));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct MaintenanceWindow {
+    /// Affected services, every service when empty.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub services: Vec<String>,
+    /// Start of the first occurrence (inclusive).
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub start: DateTime<Utc>,
+    /// End of the first occurrence (exclusive).
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub end: DateTime<Utc>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub recurrence: Recurrence,
+    pub reason: String,
+}
+
+impl MaintenanceWindow {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>, reason: impl Into<String>) -> Self {
+        Self {
+            services: Vec::new(),
+            start,
+            end,
+            recurrence: Recurrence::Once,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn with_service(mut self, service: impl Into<String>) -> Self {
+        self.services.push(service.into());
+        self
+    }
+
+    pub fn with_recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = recurrence;
+        self
+    }
+
+    /// Returns true when `service` is affected by the window.
+    pub fn covers(&self, service: &str) -> bool {
+        self.services.is_empty() || self.services.iter().any(|covered| covered == service)
+    }
+
+    /// Returns true when `now` falls within an occurrence of the window.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        if now < self.start {
+            return false;
+        }
+        let elapsed = now - self.start;
+        let elapsed = match self.recurrence.period() {
+            Some(period) => Duration::milliseconds(elapsed.num_milliseconds() % period.num_milliseconds()),
+            None => elapsed,
+        };
+        elapsed < self.end - self.start
+    }
+
+    /// Returns true when the window mutes `message` at `now`.
+    pub fn suppresses_at(&self, message: &TelegramQueueMessage, now: DateTime<Utc>) -> bool {
+        let covered = self.services.is_empty()
+            || message.job_name().is_some_and(|job_name| self.covers(job_name))
+            || message.metadata.get("service").is_some_and(|service| self.covers(service));
+        covered && self.is_active(now) && !message.should_force_send(now)
+    }
+
+    /// Returns true when the window mutes `message` now; see
+    /// [`MaintenanceWindow::suppresses_at`].
+    pub fn suppresses(&self, message: &TelegramQueueMessage) -> bool {
+        self.suppresses_at(message, Utc::now())
+    }
+}
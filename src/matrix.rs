@@ -0,0 +1,205 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde_json::{Value, json};
+
+use crate::render::{self, html_to_text};
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// `format` of HTML formatted bodies.
+pub const MATRIX_HTML_FORMAT: &str = "org.matrix.custom.html";
+
+/// Tags kept by [`sanitize_matrix_html`], the set recommended by the Matrix spec.
+const ALLOWED_TAGS: &[&str] = &[
+    "a", "b", "blockquote", "br", "caption", "code", "del", "details", "div", "em", "h1", "h2",
+    "h3", "h4", "h5", "h6", "hr", "i", "li", "ol", "p", "pre", "s", "strike", "strong", "sub",
+    "summary", "sup", "table", "tbody", "td", "th", "thead", "tr", "u", "ul",
+];
+
+/// URL schemes allowed in links.
+const ALLOWED_SCHEMES: &[&str] = &["https://", "http://", "mailto:"];
+
+/// `msgtype` of a room message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum MatrixMsgType {
+    #[cfg_attr(feature = "serde", serde(rename = "m.text"))]
+    Text,
+    /// Automated message; clients do not notify for it by default. Used by bots.
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "m.notice"))]
+    Notice,
+    #[cfg_attr(feature = "serde", serde(rename = "m.emote"))]
+    Emote,
+}
+
+/// Represents a message received from the queue to be sent to a Matrix room.
+///
+/// ```rust
+/// use shared_types::MatrixQueueMessage;
+///
+/// let msg = MatrixQueueMessage::new("#ops:matrix.example.com", "")
+///     .with_html("<b>backup</b> failed <script>alert(1)</script><a href=\"javascript:x\">x</a>");
+/// assert_eq!(
+///     msg.formatted_body.as_deref(),
+///     Some("<b>backup</b> failed &lt;script&gt;alert(1)&lt;/script&gt;<a>x</a>")
+/// );
+/// assert_eq!(msg.body, "backup failed <script>alert(1)</script>x");
+/// assert!(msg.is_room_alias());
+/// # #[cfg(feature = "serde")]
+/// # {
+/// assert_eq!(msg.event_content()["format"], "org.matrix.custom.html");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct MatrixQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    /// Room id (`!abc:server`) or alias (`#ops:server`).
+    pub room: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub msgtype: MatrixMsgType,
+    /// Plain text body, required by Matrix for clients without HTML support.
+    pub body: String,
+    /// HTML body, restricted to the tags of [`sanitize_matrix_html`].
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "formatted_body"))]
+    pub formatted_body: Option<String>,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+}
+
+impl MatrixQueueMessage {
+    /// Creates a plain text notice.
+    pub fn new(room: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            room: room.into(),
+            msgtype: MatrixMsgType::default(),
+            body: body.into(),
+            formatted_body: None,
+            status: MessageStatus::None,
+            dedup_key: None,
+        }
+    }
+
+    /// Sets the HTML body, sanitized with [`sanitize_matrix_html`].
+    ///
+    /// An empty plain body is derived from the HTML.
+    pub fn with_html(mut self, html: &str) -> Self {
+        let formatted_body = sanitize_matrix_html(html);
+        if self.body.is_empty() {
+            self.body = html_to_text(&formatted_body);
+        }
+        self.formatted_body = Some(formatted_body);
+        self
+    }
+
+    pub fn with_msgtype(mut self, msgtype: MatrixMsgType) -> Self {
+        self.msgtype = msgtype;
+        self
+    }
+
+    pub fn with_status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Returns true when `room` is a room id, e.g. `!abc:server`.
+    pub fn is_room_id(&self) -> bool {
+        self.room.starts_with('!') && self.room.contains(':')
+    }
+
+    /// Returns true when `room` is an alias, e.g. `#ops:server`, to resolve before sending.
+    pub fn is_room_alias(&self) -> bool {
+        self.room.starts_with('#') && self.room.contains(':')
+    }
+
+    /// Returns the `m.room.message` event content to send.
+    #[cfg(feature = "serde")]
+    pub fn event_content(&self) -> Value {
+        let mut content = json!({ "msgtype": self.msgtype, "body": self.body });
+        if let Some(formatted_body) = &self.formatted_body {
+            content["format"] = json!(MATRIX_HTML_FORMAT);
+            content["formatted_body"] = json!(formatted_body);
+        }
+        content
+    }
+}
+
+/// Keeps the HTML tags Matrix clients render and escapes everything else.
+///
+/// Attributes are dropped except `href` on links with an `http`, `https` or
+/// `mailto` URL, so the output cannot carry scripts or styles.
+pub fn sanitize_matrix_html(html: &str) -> String {
+    let mut sanitized = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        sanitized.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>').map(|end| start + end) else {
+            sanitized.push_str(&render::escape_text(&rest[start..]));
+            rest = "";
+            break;
+        };
+        let tag = &rest[start..=end];
+        match allowed_tag(tag) {
+            Some(tag) => sanitized.push_str(&tag),
+            None => sanitized.push_str(&render::escape_text(tag)),
+        }
+        rest = &rest[end + 1..];
+    }
+    sanitized.push_str(rest);
+    sanitized
+}
+
+/// Returns the normalized form of `tag` (`<...>`) when it is allowed.
+fn allowed_tag(tag: &str) -> Option<String> {
+    let inner = tag[1..tag.len() - 1].trim();
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner.trim_start()),
+        None => (false, inner),
+    };
+    let inner = inner.trim_end_matches('/').trim_end();
+    let name_end = inner.find(|c: char| c.is_whitespace()).unwrap_or(inner.len());
+    let name = inner[..name_end].to_ascii_lowercase();
+    if !ALLOWED_TAGS.contains(&name.as_str()) {
+        return None;
+    }
+    if closing {
+        return Some(format!("</{}>", name));
+    }
+    let href = if name == "a" { link_href(&inner[name_end..]) } else { None };
+    Some(match href {
+        Some(href) => format!("<a href=\"{}\">", render::escape_attribute(&href)),
+        None => format!("<{}>", name),
+    })
+}
+
+/// Extracts a quoted `href` attribute with an allowed scheme.
+fn link_href(attributes: &str) -> Option<String> {
+    let start = attributes.find("href=")? + "href=".len();
+    let value = &attributes[start..];
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    let href = render::decode_entities(&value[..value.find(quote)?]).into_owned();
+    let lower = href.to_ascii_lowercase();
+    ALLOWED_SCHEMES
+        .iter()
+        .any(|scheme| lower.starts_with(scheme))
+        .then_some(href)
+}
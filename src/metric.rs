@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Unit of a metric value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum MetricUnit {
+    /// A plain number, e.g. a ratio.
+    #[default]
+    None,
+    Count,
+    Bytes,
+    Seconds,
+    Milliseconds,
+    /// From 0 to 100.
+    Percent,
+    BytesPerSecond,
+    CountPerSecond,
+}
+
+/// A single measurement, e.g. a job runtime or throughput.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct MetricSample {
+    pub name: String,
+    pub value: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unit: MetricUnit,
+    /// Dimensions of the sample, e.g. `job` or `host`.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "BTreeMap::is_empty"))]
+    pub labels: BTreeMap<String, String>,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl MetricSample {
+    pub fn new(name: impl Into<String>, value: f64, unit: MetricUnit, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            unit,
+            labels: BTreeMap::new(),
+            timestamp,
+        }
+    }
+
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Returns the `(timestamp, value)` point, as taken by
+    /// [`AlertRule::evaluate`](crate::alert::AlertRule::evaluate).
+    pub fn point(&self) -> (DateTime<Utc>, f64) {
+        (self.timestamp, self.value)
+    }
+}
+
+/// Samples sent together on the queue, so metrics flow next to notifications.
+///
+/// ```rust
+/// use chrono::Utc;
+/// use shared_types::alert::{AlertRule, Comparison};
+/// use shared_types::metric::{MetricBatch, MetricSample, MetricUnit};
+/// use shared_types::payload::QueuePayload;
+///
+/// let now = Utc::now();
+/// let batch = MetricBatch::new("ftp-sync")
+///     .with_sample(MetricSample::new("job_runtime", 12.5, MetricUnit::Seconds, now).with_label("job", "ftp"))
+///     .with_sample(MetricSample::new("files_transferred", 42.0, MetricUnit::Count, now));
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let bytes = batch.to_bytes().unwrap();
+/// assert_eq!(MetricBatch::KIND, "metric_batch");
+/// assert_eq!(MetricBatch::from_bytes(&bytes).unwrap(), batch);
+/// # }
+///
+/// let rule = AlertRule::new("slow-ftp", "job_runtime", Comparison::Gt, 10.0, -100123);
+/// assert!(rule.evaluate(batch.series("job_runtime")).is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct MetricBatch {
+    /// Service that measured the samples.
+    pub source: String,
+    pub samples: Vec<MetricSample>,
+}
+
+impl MetricBatch {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn with_sample(mut self, sample: MetricSample) -> Self {
+        self.samples.push(sample);
+        self
+    }
+
+    pub fn push(&mut self, sample: MetricSample) {
+        self.samples.push(sample);
+    }
+
+    /// Returns the `(timestamp, value)` points of the metric `name`, in batch order.
+    pub fn series<'a>(&'a self, name: &'a str) -> impl Iterator<Item = (DateTime<Utc>, f64)> + 'a {
+        self.samples
+            .iter()
+            .filter(move |sample| sample.name == name)
+            .map(MetricSample::point)
+    }
+}
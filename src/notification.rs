@@ -0,0 +1,175 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::discord::{DiscordQueueMessage, DiscordTarget};
+use crate::email::EmailQueueMessage;
+use crate::gotify::GotifyQueueMessage;
+use crate::matrix::MatrixQueueMessage;
+use crate::pagerduty::PagerDutyEvent;
+use crate::payload::{QueuePayload, impl_from_payloads};
+use crate::push::{PushQueueMessage, PushTarget};
+use crate::slack::SlackQueueMessage;
+use crate::sms::SmsQueueMessage;
+use crate::teams::TeamsQueueMessage;
+use crate::telegram::{MessageStatus, TelegramQueueMessage};
+use crate::webhook::WebhookQueueMessage;
+
+/// A notification for any channel, tagged by `channel`.
+///
+/// Routing services handle every channel through this one type: the common
+/// accessors expose the severity, dedup key and a readable target without
+/// matching on the channel.
+///
+/// ```rust
+/// use shared_types::notification::Notification;
+/// use shared_types::{EmailQueueMessage, MessageStatus, TelegramQueueMessage};
+///
+/// let notifications = vec![
+///     Notification::from(TelegramQueueMessage::builder(-100).status(MessageStatus::Error).build()),
+///     Notification::from(EmailQueueMessage::new("ops@example.com", "report", "<p>ok</p>")),
+/// ];
+/// let urgent: Vec<String> = notifications
+///     .iter()
+///     .filter(|n| n.severity().at_least(MessageStatus::Warn))
+///     .map(Notification::target)
+///     .collect();
+/// assert_eq!(urgent, ["telegram:-100"]);
+/// # #[cfg(feature = "serde")]
+/// # {
+/// assert_eq!(serde_json::to_value(&notifications[1]).unwrap()["channel"], "email");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "channel", rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum Notification {
+    Telegram(TelegramQueueMessage),
+    Email(EmailQueueMessage),
+    Slack(SlackQueueMessage),
+    Discord(DiscordQueueMessage),
+    Sms(SmsQueueMessage),
+    Webhook(WebhookQueueMessage),
+    Teams(TeamsQueueMessage),
+    Push(PushQueueMessage),
+    #[cfg_attr(feature = "serde", serde(rename = "pagerduty"))]
+    PagerDuty(PagerDutyEvent),
+    Matrix(MatrixQueueMessage),
+    Gotify(GotifyQueueMessage),
+}
+
+impl Notification {
+    /// Returns the channel name, the `channel` tag of the notification.
+    pub fn channel(&self) -> &'static str {
+        match self {
+            Notification::Telegram(_) => TelegramQueueMessage::KIND,
+            Notification::Email(_) => EmailQueueMessage::KIND,
+            Notification::Slack(_) => SlackQueueMessage::KIND,
+            Notification::Discord(_) => DiscordQueueMessage::KIND,
+            Notification::Sms(_) => SmsQueueMessage::KIND,
+            Notification::Webhook(_) => WebhookQueueMessage::KIND,
+            Notification::Teams(_) => TeamsQueueMessage::KIND,
+            Notification::Push(_) => PushQueueMessage::KIND,
+            Notification::PagerDuty(_) => PagerDutyEvent::KIND,
+            Notification::Matrix(_) => MatrixQueueMessage::KIND,
+            Notification::Gotify(_) => GotifyQueueMessage::KIND,
+        }
+    }
+
+    /// Returns the status the notification was built with.
+    ///
+    /// Every payload keeps the status of its builder in its `status` field so
+    /// dispatchers can filter by severity, e.g. with [`MessageStatus::at_least`],
+    /// whatever the channel. PagerDuty events map it from their own severity.
+    pub fn severity(&self) -> MessageStatus {
+        match self {
+            Notification::Telegram(message) => message.status,
+            Notification::Email(message) => message.status,
+            Notification::Slack(message) => message.status,
+            Notification::Discord(message) => message.status,
+            Notification::Sms(message) => message.status,
+            Notification::Webhook(message) => message.status,
+            Notification::Teams(message) => message.status,
+            Notification::Push(message) => message.status,
+            Notification::PagerDuty(event) => event.status(),
+            Notification::Matrix(message) => message.status,
+            Notification::Gotify(message) => message.status,
+        }
+    }
+
+    /// Returns the key dispatchers use to suppress duplicates.
+    ///
+    /// Every payload has an optional `dedup_key`: a dispatcher that has already
+    /// delivered a notification with the same key within its dedup window drops
+    /// it, so an alert fired in a tight loop is sent once. Builders take it
+    /// explicitly, and [`TelegramMessageBuilder::derived_dedup_key`] derives it
+    /// from the job, status and content. Without a key nothing is suppressed.
+    ///
+    /// [`TelegramMessageBuilder::derived_dedup_key`]: crate::TelegramMessageBuilder::derived_dedup_key
+    pub fn dedup_key(&self) -> Option<&str> {
+        match self {
+            Notification::Telegram(message) => message.dedup_key.as_deref(),
+            Notification::Email(message) => message.dedup_key.as_deref(),
+            Notification::Slack(message) => message.dedup_key.as_deref(),
+            Notification::Discord(message) => message.dedup_key.as_deref(),
+            Notification::Sms(message) => message.dedup_key.as_deref(),
+            Notification::Webhook(message) => message.dedup_key.as_deref(),
+            Notification::Teams(message) => message.dedup_key.as_deref(),
+            Notification::Push(message) => message.dedup_key.as_deref(),
+            Notification::PagerDuty(event) => event.dedup_key.as_deref(),
+            Notification::Matrix(message) => message.dedup_key.as_deref(),
+            Notification::Gotify(message) => message.dedup_key.as_deref(),
+        }
+    }
+
+    /// Describes the recipient as `{channel}:{target}` for logs and dashboards.
+    ///
+    /// Secrets such as the PagerDuty routing key are never included.
+    pub fn target(&self) -> String {
+        let target = match self {
+            Notification::Telegram(message) => message.chat_id.to_string(),
+            Notification::Email(message) => message.recipients().collect::<Vec<_>>().join(","),
+            Notification::Slack(message) => message.channel.clone(),
+            Notification::Discord(message) => match &message.target {
+                DiscordTarget::ChannelId(id) => format!("channel/{}", id),
+                DiscordTarget::WebhookId(id) => format!("webhook/{}", id),
+            },
+            Notification::Sms(message) => message.to.to_string(),
+            Notification::Webhook(message) => format!("{} {}", message.method.as_str(), message.url),
+            Notification::Teams(message) => message.webhook.clone(),
+            Notification::Push(message) => match &message.target {
+                PushTarget::DeviceToken(token) => format!("device/{}", token),
+                PushTarget::Topic(topic) => format!("topic/{}", topic),
+            },
+            Notification::PagerDuty(event) => event
+                .payload
+                .as_ref()
+                .map_or_else(String::new, |payload| payload.source.clone()),
+            Notification::Matrix(message) => message.room.clone(),
+            Notification::Gotify(message) => message.app.clone(),
+        };
+        format!("{}:{}", self.channel(), target)
+    }
+}
+
+impl_from_payloads!(Notification {
+    Telegram(TelegramQueueMessage),
+    Email(EmailQueueMessage),
+    Slack(SlackQueueMessage),
+    Discord(DiscordQueueMessage),
+    Sms(SmsQueueMessage),
+    Webhook(WebhookQueueMessage),
+    Teams(TeamsQueueMessage),
+    Push(PushQueueMessage),
+    PagerDuty(PagerDutyEvent),
+    Matrix(MatrixQueueMessage),
+    Gotify(GotifyQueueMessage),
+});
+
+impl QueuePayload for Notification {
+    const KIND: &'static str = "notification";
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::content::NotificationContent;
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// An amount in the minor units of its currency, e.g. cents, so totals are
+/// never rounded by floating point.
+///
+/// ```rust
+/// use shared_types::order::Money;
+///
+/// assert_eq!(Money::new(1250, "EUR").to_string(), "12.50 EUR");
+/// assert_eq!(Money::new(1250, "JPY").to_string(), "1250 JPY");
+/// assert_eq!(Money::new(-5, "EUR").to_string(), "-0.05 EUR");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Money {
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "minor_units"))]
+    pub minor_units: i64,
+    /// ISO 4217 code, e.g. `EUR`.
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(minor_units: i64, currency: impl Into<String>) -> Self {
+        Self {
+            minor_units,
+            currency: currency.into(),
+        }
+    }
+
+    /// Returns the number of decimals of the currency, 2 unless known otherwise.
+    pub fn exponent(&self) -> u32 {
+        match self.currency.as_str() {
+            "JPY" | "KRW" | "CLP" | "ISK" | "VND" | "XAF" | "XOF" => 0,
+            "BHD" | "JOD" | "KWD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let exponent = self.exponent();
+        if exponent == 0 {
+            return write!(f, "{} {}", self.minor_units, self.currency);
+        }
+        let scale = 10u64.pow(exponent);
+        let sign = if self.minor_units < 0 { "-" } else { "" };
+        let units = self.minor_units.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:0width$} {}",
+            sign,
+            units / scale,
+            units % scale,
+            self.currency,
+            width = exponent as usize
+        )
+    }
+}
+
+/// Status of a shop order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum OrderStatus {
+    Placed,
+    Paid,
+    PaymentFailed,
+    Shipped,
+    Delivered,
+    Cancelled,
+    Refunded,
+}
+
+impl OrderStatus {
+    /// Returns the status as shown to the ops team, e.g. `payment failed`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Placed => "placed",
+            OrderStatus::Paid => "paid",
+            OrderStatus::PaymentFailed => "payment failed",
+            OrderStatus::Shipped => "shipped",
+            OrderStatus::Delivered => "delivered",
+            OrderStatus::Cancelled => "cancelled",
+            OrderStatus::Refunded => "refunded",
+        }
+    }
+}
+
+/// A change of an order, emitted by the shop integration services.
+///
+/// ```rust
+/// use chrono::Utc;
+/// use shared_types::order::{Money, OrderEvent, OrderStatus};
+///
+/// let event = OrderEvent::new("1042", "cus_81f3", OrderStatus::Shipped, Money::new(4990, "EUR"), Utc::now());
+/// assert_eq!(
+///     event.to_telegram_message(123).message,
+///     "✅ - <i>shop</i>\norder <code>1042</code> shipped, 49.90 EUR\ncustomer <code>cus_81f3</code>"
+/// );
+/// assert_eq!(
+///     event.to_customer_content().render_plain(),
+///     "Your order 1042 has shipped\nIt is on its way to you.\n\nTotal: 49.90 EUR"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct OrderEvent {
+    #[cfg_attr(feature = "serde", serde(alias = "order_id"))]
+    pub order_id: String,
+    /// Reference of the customer in the shop, never personal data.
+    #[cfg_attr(feature = "serde", serde(alias = "customer_ref"))]
+    pub customer_ref: String,
+    pub status: OrderStatus,
+    pub total: Money,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl OrderEvent {
+    pub fn new(
+        order_id: impl Into<String>,
+        customer_ref: impl Into<String>,
+        status: OrderStatus,
+        total: Money,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            order_id: order_id.into(),
+            customer_ref: customer_ref.into(),
+            status,
+            total,
+            timestamp,
+        }
+    }
+
+    /// `Error` for failed payments, `Warn` for cancellations and refunds, `Ok` otherwise.
+    pub fn severity(&self) -> MessageStatus {
+        match self.status {
+            OrderStatus::PaymentFailed => MessageStatus::Error,
+            OrderStatus::Cancelled | OrderStatus::Refunded => MessageStatus::Warn,
+            _ => MessageStatus::Ok,
+        }
+    }
+
+    /// Formats the internal ops alert for the `shop` job.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("shop")
+            .summary(format!(
+                "order <code>{}</code> {}, {}",
+                render::escape_text(&self.order_id),
+                self.status.as_str(),
+                render::escape_text(&self.total.to_string())
+            ))
+            .content(format!(
+                "customer <code>{}</code>",
+                render::escape_text(&self.customer_ref)
+            ))
+            .build()
+    }
+
+    /// Returns the customer-facing notification, rendered per channel (email,
+    /// SMS, push) by the dispatcher. It carries no internal references.
+    pub fn to_customer_content(&self) -> NotificationContent {
+        let (title, text) = match self.status {
+            OrderStatus::Placed => ("has been received", "We will let you know when it ships."),
+            OrderStatus::Paid => ("has been paid", "Thank you, we are preparing it."),
+            OrderStatus::PaymentFailed => ("could not be paid", "Please check your payment method."),
+            OrderStatus::Shipped => ("has shipped", "It is on its way to you."),
+            OrderStatus::Delivered => ("has been delivered", "We hope you enjoy it."),
+            OrderStatus::Cancelled => ("has been cancelled", "Contact us if you did not expect this."),
+            OrderStatus::Refunded => ("has been refunded", "The refund may take a few days to appear."),
+        };
+        NotificationContent::new(MessageStatus::None, format!("Your order {} {}", self.order_id, title))
+            .text(text)
+            .field("Total", self.total.to_string())
+    }
+}
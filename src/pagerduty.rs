@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render::{html_to_text, truncate};
+use crate::telegram::{MessageStatus, TelegramQueueMessage};
+
+/// Events API v2 endpoint the events are posted to.
+pub const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// Maximum length of an event summary.
+pub const PAGERDUTY_MAX_SUMMARY_LENGTH: usize = 1024;
+
+/// What the event does to the incident identified by its dedup key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum EventAction {
+    Trigger,
+    Acknowledge,
+    Resolve,
+}
+
+/// Severity of a triggered incident.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum PagerDutySeverity {
+    Critical,
+    Error,
+    Warning,
+    Info,
+}
+
+impl From<MessageStatus> for PagerDutySeverity {
+    fn from(status: MessageStatus) -> Self {
+        match status {
+            MessageStatus::Error => PagerDutySeverity::Error,
+            MessageStatus::Warn => PagerDutySeverity::Warning,
+            MessageStatus::None | MessageStatus::Info | MessageStatus::Ok => PagerDutySeverity::Info,
+        }
+    }
+}
+
+impl From<PagerDutySeverity> for MessageStatus {
+    fn from(severity: PagerDutySeverity) -> Self {
+        match severity {
+            PagerDutySeverity::Critical | PagerDutySeverity::Error => MessageStatus::Error,
+            PagerDutySeverity::Warning => MessageStatus::Warn,
+            PagerDutySeverity::Info => MessageStatus::Info,
+        }
+    }
+}
+
+/// Details of a triggered incident.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PagerDutyPayload {
+    pub summary: String,
+    /// Affected system, e.g. a hostname.
+    pub source: String,
+    pub severity: PagerDutySeverity,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub component: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub group: Option<String>,
+    /// Free-form details of the incident, as text; JSON numbers and booleans
+    /// are read as their JSON text.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty", deserialize_with = "crate::json_text::string_map")
+    )]
+    pub custom_details: BTreeMap<String, String>,
+}
+
+/// An Events API v2 event, serialized in PagerDuty's own format so the worker
+/// can post it to [`PAGERDUTY_EVENTS_URL`] unchanged.
+///
+/// ```rust
+/// use shared_types::PagerDutyEvent;
+/// use shared_types::pagerduty::EventAction;
+///
+/// let trigger = PagerDutyEvent::trigger("R0UT1NG", "backup failed", "ftp01", shared_types::MessageStatus::Error)
+///     .with_dedup_key("backup-ftp01")
+///     .with_custom_detail("job", "backup");
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let json = serde_json::to_value(&trigger).unwrap();
+/// assert_eq!(json["event_action"], "trigger");
+/// assert_eq!(json["payload"]["severity"], "error");
+/// # }
+///
+/// let resolve = PagerDutyEvent::resolve("R0UT1NG", "backup-ftp01");
+/// assert_eq!(resolve.event_action, EventAction::Resolve);
+/// assert!(resolve.payload.is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PagerDutyEvent {
+    /// Integration key of the PagerDuty service.
+    pub routing_key: String,
+    pub event_action: EventAction,
+    /// Identifies the incident across trigger, acknowledge and resolve events.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub dedup_key: Option<String>,
+    /// Required for triggers, absent otherwise.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub payload: Option<PagerDutyPayload>,
+}
+
+impl PagerDutyEvent {
+    /// Triggers an incident with the severity mapped from `status`.
+    ///
+    /// The summary is truncated to [`PAGERDUTY_MAX_SUMMARY_LENGTH`].
+    pub fn trigger(
+        routing_key: impl Into<String>,
+        summary: impl Into<String>,
+        source: impl Into<String>,
+        status: MessageStatus,
+    ) -> Self {
+        Self {
+            routing_key: routing_key.into(),
+            event_action: EventAction::Trigger,
+            dedup_key: None,
+            payload: Some(PagerDutyPayload {
+                summary: truncate(&summary.into(), PAGERDUTY_MAX_SUMMARY_LENGTH),
+                source: source.into(),
+                severity: PagerDutySeverity::from(status),
+                timestamp: Some(Utc::now()),
+                component: None,
+                group: None,
+                custom_details: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Acknowledges the incident identified by `dedup_key`.
+    pub fn acknowledge(routing_key: impl Into<String>, dedup_key: impl Into<String>) -> Self {
+        Self::update(routing_key.into(), EventAction::Acknowledge, dedup_key.into())
+    }
+
+    /// Resolves the incident identified by `dedup_key`.
+    pub fn resolve(routing_key: impl Into<String>, dedup_key: impl Into<String>) -> Self {
+        Self::update(routing_key.into(), EventAction::Resolve, dedup_key.into())
+    }
+
+    fn update(routing_key: String, event_action: EventAction, dedup_key: String) -> Self {
+        Self {
+            routing_key,
+            event_action,
+            dedup_key: Some(dedup_key),
+            payload: None,
+        }
+    }
+
+    /// Escalates an `Error` Telegram message, `None` for lower statuses.
+    ///
+    /// The summary is the message text without HTML, the dedup key and metadata
+    /// are carried over (metadata as custom details).
+    ///
+    /// ```rust
+    /// use shared_types::{MessageStatus, PagerDutyEvent, TelegramQueueMessage};
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .status(MessageStatus::Error)
+    ///     .job_name("backup")
+    ///     .content("disk <b>full</b>")
+    ///     .build();
+    /// let event = PagerDutyEvent::escalate("R0UT1NG", "ftp01", &msg).unwrap();
+    /// assert_eq!(event.payload.unwrap().summary, "🚨 - backup\ndisk full");
+    ///
+    /// let ok = TelegramQueueMessage::builder(123).status(MessageStatus::Ok).build();
+    /// assert!(PagerDutyEvent::escalate("R0UT1NG", "ftp01", &ok).is_none());
+    /// ```
+    pub fn escalate(
+        routing_key: impl Into<String>,
+        source: impl Into<String>,
+        message: &TelegramQueueMessage,
+    ) -> Option<Self> {
+        if !message.is_at_least(MessageStatus::Error) {
+            return None;
+        }
+        let mut event = Self::trigger(routing_key, html_to_text(&message.message), source, message.status);
+        event.dedup_key = message.dedup_key.clone();
+        for (key, value) in &message.metadata {
+            event = event.with_custom_detail(key.clone(), value.clone());
+        }
+        Some(event)
+    }
+
+    pub fn with_dedup_key(mut self, dedup_key: impl Into<String>) -> Self {
+        self.dedup_key = Some(dedup_key.into());
+        self
+    }
+
+    /// Adds a custom detail to a trigger; ignored for other actions.
+    pub fn with_custom_detail(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        if let Some(payload) = &mut self.payload {
+            payload.custom_details.insert(key.into(), value.to_string());
+        }
+        self
+    }
+
+    /// Returns the status matching the event severity, [`MessageStatus::None`] for
+    /// acknowledge and resolve.
+    pub fn status(&self) -> MessageStatus {
+        self.payload
+            .as_ref()
+            .map_or(MessageStatus::None, |payload| MessageStatus::from(payload.severity))
+    }
+}
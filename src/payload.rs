@@ -0,0 +1,484 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+
+use crate::consumer::ConsumerResult;
+use crate::discord::DiscordQueueMessage;
+use crate::document::DocumentEvent;
+use crate::email::EmailQueueMessage;
+use crate::envelope::QueueEnvelope;
+#[cfg(feature = "serde")]
+use crate::error::SharedTypesError;
+use crate::gotify::GotifyQueueMessage;
+use crate::heartbeat::Heartbeat;
+use crate::matrix::MatrixQueueMessage;
+use crate::metric::MetricBatch;
+use crate::pagerduty::PagerDutyEvent;
+use crate::push::PushQueueMessage;
+use crate::slack::SlackQueueMessage;
+use crate::sms::SmsQueueMessage;
+use crate::teams::TeamsQueueMessage;
+use crate::telegram::{TelegramBroadcastMessage, TelegramQueueMessage};
+use crate::webhook::WebhookQueueMessage;
+
+/// Content type of JSON encoded payloads.
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// Content type of bincode encoded payloads.
+#[cfg(feature = "bincode")]
+pub const BINCODE_CONTENT_TYPE: &str = "application/x-bincode";
+
+/// Configuration of [`QueuePayload::to_bincode`], pinned so every service
+/// encodes alike whatever bincode's defaults become: little endian integers,
+/// variable length (varint) encoding of integers and lengths, and no size
+/// limit, the queue limits being enforced separately.
+#[cfg(feature = "bincode")]
+pub const BINCODE_CONFIG: bincode::config::Configuration<
+    bincode::config::LittleEndian,
+    bincode::config::Varint,
+    bincode::config::NoLimit,
+> = bincode::config::standard()
+    .with_little_endian()
+    .with_variable_int_encoding()
+    .with_no_limit();
+
+/// Content type of CBOR encoded payloads.
+#[cfg(feature = "cbor")]
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
+/// Content type of MessagePack encoded payloads.
+#[cfg(feature = "msgpack")]
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// A type that can be put on a queue.
+///
+/// Abstracts the payload kind, content type and byte encoding so producer and
+/// consumer glue code can handle every payload type the same way. The default
+/// methods encode as JSON.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use shared_types::payload::QueuePayload;
+/// use shared_types::TelegramQueueMessage;
+///
+/// fn publish<P: QueuePayload>(payload: &P) -> (String, Vec<u8>) {
+///     (format!("{}; kind={}", P::content_type(), P::KIND), payload.to_bytes().unwrap())
+/// }
+///
+/// let (content_type, bytes) = publish(&TelegramQueueMessage::new(123, "hi".into(), false));
+/// assert_eq!(content_type, "application/json; kind=telegram");
+/// assert_eq!(TelegramQueueMessage::from_bytes(&bytes).unwrap().message, "hi");
+/// # }
+/// ```
+pub trait QueuePayload: Serializable {
+    /// Stable name of the payload kind, e.g. `"telegram"`.
+    const KIND: &'static str;
+
+    /// MIME type of the bytes produced by [`QueuePayload::to_bytes`].
+    fn content_type() -> &'static str {
+        JSON_CONTENT_TYPE
+    }
+
+    /// Encodes the payload.
+    #[cfg(feature = "serde")]
+    fn to_bytes(&self) -> Result<Vec<u8>, SharedTypesError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Decodes a payload.
+    ///
+    /// ```rust
+    /// use shared_types::error::ErrorCode;
+    /// use shared_types::payload::QueuePayload;
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let err = TelegramQueueMessage::from_bytes(b"{").unwrap_err();
+    /// assert_eq!(err.code(), ErrorCode::Serialization);
+    /// assert!(err.to_string().starts_with("json serialization failed"));
+    /// ```
+    #[cfg(feature = "serde")]
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SharedTypesError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Encodes the payload as MessagePack, with field names so optional and
+    /// tagged fields decode like their JSON counterpart.
+    ///
+    /// ```rust
+    /// use shared_types::payload::{QueueMessageKind, QueuePayload};
+    /// use shared_types::{EmailQueueMessage, MessageStatus, Notification, QueueEnvelope, TelegramQueueMessage};
+    ///
+    /// let msg = TelegramQueueMessage::builder(-100)
+    ///     .status(MessageStatus::Warn)
+    ///     .job_name("ftp")
+    ///     .content("slow upload")
+    ///     .build();
+    /// let bytes = msg.to_msgpack().unwrap();
+    /// assert!(bytes.len() < msg.to_bytes().unwrap().len());
+    /// let restored = TelegramQueueMessage::from_msgpack(&bytes).unwrap();
+    /// assert_eq!(restored.message, msg.message);
+    /// assert_eq!(restored.status, MessageStatus::Warn);
+    ///
+    /// let envelope = QueueEnvelope::new("ftp-sync", QueueMessageKind::from(msg));
+    /// let restored = QueueEnvelope::<QueueMessageKind>::from_msgpack(&envelope.to_msgpack().unwrap()).unwrap();
+    /// assert_eq!(restored.message_id, envelope.message_id);
+    /// assert!(matches!(restored.payload, QueueMessageKind::Telegram(_)));
+    ///
+    /// let email = Notification::from(EmailQueueMessage::new("ops@example.com", "report", "<p>ok</p>"));
+    /// let restored = Notification::from_msgpack(&email.to_msgpack().unwrap()).unwrap();
+    /// assert_eq!(restored.target(), "email:ops@example.com");
+    /// ```
+    #[cfg(feature = "msgpack")]
+    fn to_msgpack(&self) -> Result<Vec<u8>, SharedTypesError> {
+        rmp_serde::to_vec_named(self).map_err(|err| SharedTypesError::Serialization {
+            format: "msgpack",
+            source: Box::new(err),
+        })
+    }
+
+    /// Decodes a payload encoded with [`QueuePayload::to_msgpack`].
+    #[cfg(feature = "msgpack")]
+    fn from_msgpack(bytes: &[u8]) -> Result<Self, SharedTypesError> {
+        rmp_serde::from_slice(bytes).map_err(|err| SharedTypesError::Serialization {
+            format: "msgpack",
+            source: Box::new(err),
+        })
+    }
+
+    /// Encodes the payload as bincode with [`BINCODE_CONFIG`], for internal
+    /// pipes where the JSON overhead matters.
+    ///
+    /// Fields are written in declaration order without names and enum variants
+    /// by index, so both ends must use the same version of this crate; JSON
+    /// values such as a webhook body are kept as JSON text.
+    ///
+    /// ```rust
+    /// use shared_types::payload::{QueueMessageKind, QueuePayload};
+    /// use shared_types::{MessageStatus, QueueEnvelope, TelegramQueueMessage, WebhookQueueMessage};
+    ///
+    /// let msg = TelegramQueueMessage::builder(-100)
+    ///     .status(MessageStatus::Warn)
+    ///     .job_name("ftp")
+    ///     .content("slow upload")
+    ///     .build();
+    /// let bytes = msg.to_bincode().unwrap();
+    /// assert!(bytes.len() < msg.to_bytes().unwrap().len() / 2);
+    /// let restored = TelegramQueueMessage::from_bincode(&bytes).unwrap();
+    /// assert_eq!(restored.message, msg.message);
+    /// assert_eq!(restored.status, MessageStatus::Warn);
+    ///
+    /// let hook = WebhookQueueMessage::new("https://erp.example.com/hooks").with_body(serde_json::json!({"order": 42}));
+    /// let envelope = QueueEnvelope::new("erp-sync", QueueMessageKind::from(hook)).with_correlation_id("run-42");
+    /// let restored = QueueEnvelope::<QueueMessageKind>::from_bincode(&envelope.to_bincode().unwrap()).unwrap();
+    /// assert_eq!(restored.message_id, envelope.message_id);
+    /// assert_eq!(restored.created_at, envelope.created_at);
+    /// let QueueMessageKind::Webhook(hook) = restored.payload else { panic!() };
+    /// assert_eq!(hook.body_bytes(), br#"{"order":42}"#);
+    /// ```
+    #[cfg(feature = "bincode")]
+    fn to_bincode(&self) -> Result<Vec<u8>, SharedTypesError>
+    where
+        Self: bincode::Encode,
+    {
+        bincode::encode_to_vec(self, BINCODE_CONFIG).map_err(|err| SharedTypesError::Serialization {
+            format: "bincode",
+            source: Box::new(err),
+        })
+    }
+
+    /// Decodes a payload encoded with [`QueuePayload::to_bincode`].
+    #[cfg(feature = "bincode")]
+    fn from_bincode(bytes: &[u8]) -> Result<Self, SharedTypesError>
+    where
+        Self: bincode::Decode<()>,
+    {
+        bincode::decode_from_slice(bytes, BINCODE_CONFIG)
+            .map(|(payload, _)| payload)
+            .map_err(|err| SharedTypesError::Serialization {
+                format: "bincode",
+                source: Box::new(err),
+            })
+    }
+
+    /// Encodes the payload as CBOR, for devices with limited JSON support.
+    ///
+    /// ```rust
+    /// use shared_types::payload::QueuePayload;
+    /// use shared_types::{MessageStatus, Notification, QueueEnvelope, TelegramQueueMessage};
+    ///
+    /// let msg = TelegramQueueMessage::builder(-100)
+    ///     .status(MessageStatus::Error)
+    ///     .job_name("pump-3")
+    ///     .content("pressure low")
+    ///     .build();
+    /// let envelope = QueueEnvelope::new("pump-3", Notification::from(msg));
+    /// let bytes = envelope.to_cbor().unwrap();
+    /// let restored = QueueEnvelope::<Notification>::from_cbor(&bytes).unwrap();
+    /// assert_eq!(restored.message_id, envelope.message_id);
+    /// assert_eq!(restored.payload.severity(), MessageStatus::Error);
+    /// assert_eq!(restored.payload.target(), "telegram:-100");
+    /// ```
+    #[cfg(feature = "cbor")]
+    fn to_cbor(&self) -> Result<Vec<u8>, SharedTypesError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes).map_err(|err| SharedTypesError::Serialization {
+            format: "cbor",
+            source: Box::new(err),
+        })?;
+        Ok(bytes)
+    }
+
+    /// Decodes a payload encoded with [`QueuePayload::to_cbor`].
+    #[cfg(feature = "cbor")]
+    fn from_cbor(bytes: &[u8]) -> Result<Self, SharedTypesError> {
+        ciborium::from_reader(bytes).map_err(|err| SharedTypesError::Serialization {
+            format: "cbor",
+            source: Box::new(err),
+        })
+    }
+}
+
+/// Derives [`QueuePayload`](trait@QueuePayload) for new payload types.
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use shared_types::QueuePayload;
+///
+/// #[derive(Serialize, Deserialize, QueuePayload)]
+/// struct FaxQueueMessage {
+///     number: String,
+/// }
+///
+/// #[derive(Serialize, Deserialize, QueuePayload)]
+/// #[queue_payload(kind = "pager")]
+/// struct PagerAlert {
+///     text: String,
+/// }
+///
+/// #[derive(Serialize, Deserialize, QueuePayload)]
+/// struct HTTPWebhookMessage {
+///     url: String,
+/// }
+///
+/// assert_eq!(FaxQueueMessage::KIND, "fax");
+/// assert_eq!(PagerAlert::KIND, "pager");
+/// assert_eq!(HTTPWebhookMessage::KIND, "http_webhook");
+/// assert_eq!(PagerAlert::content_type(), "application/json");
+/// let bytes = FaxQueueMessage { number: "+390612345".into() }.to_bytes().unwrap();
+/// assert_eq!(FaxQueueMessage::from_bytes(&bytes).unwrap().number, "+390612345");
+/// ```
+///
+/// The kind cannot be empty:
+///
+/// ```compile_fail
+/// use serde::{Deserialize, Serialize};
+/// use shared_types::QueuePayload;
+///
+/// #[derive(Serialize, Deserialize, QueuePayload)]
+/// #[queue_payload(kind = "")]
+/// struct PagerAlert {
+///     text: String,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use shared_types_derive::QueuePayload;
+
+/// Bounds of every [`QueuePayload`]: `Serialize + DeserializeOwned` with the
+/// `serde` feature, implemented for every type that meets them.
+#[cfg(feature = "serde")]
+pub trait Serializable: Serialize + DeserializeOwned {}
+
+#[cfg(feature = "serde")]
+impl<T: Serialize + DeserializeOwned> Serializable for T {}
+
+/// Without the `serde` feature a payload needs no serialization bounds.
+#[cfg(not(feature = "serde"))]
+pub trait Serializable {}
+
+#[cfg(not(feature = "serde"))]
+impl<T> Serializable for T {}
+
+impl QueuePayload for TelegramQueueMessage {
+    const KIND: &'static str = "telegram";
+}
+
+impl QueuePayload for TelegramBroadcastMessage {
+    const KIND: &'static str = "telegram_broadcast";
+}
+
+impl QueuePayload for EmailQueueMessage {
+    const KIND: &'static str = "email";
+}
+
+impl QueuePayload for SlackQueueMessage {
+    const KIND: &'static str = "slack";
+}
+
+impl QueuePayload for DiscordQueueMessage {
+    const KIND: &'static str = "discord";
+}
+
+impl QueuePayload for SmsQueueMessage {
+    const KIND: &'static str = "sms";
+}
+
+impl QueuePayload for WebhookQueueMessage {
+    const KIND: &'static str = "webhook";
+}
+
+impl QueuePayload for TeamsQueueMessage {
+    const KIND: &'static str = "teams";
+}
+
+impl QueuePayload for PushQueueMessage {
+    const KIND: &'static str = "push";
+}
+
+impl QueuePayload for PagerDutyEvent {
+    const KIND: &'static str = "pagerduty";
+}
+
+impl QueuePayload for MatrixQueueMessage {
+    const KIND: &'static str = "matrix";
+}
+
+impl QueuePayload for GotifyQueueMessage {
+    const KIND: &'static str = "gotify";
+}
+
+impl QueuePayload for MetricBatch {
+    const KIND: &'static str = "metric_batch";
+}
+
+impl QueuePayload for ConsumerResult {
+    const KIND: &'static str = "consumer_result";
+}
+
+impl QueuePayload for Heartbeat {
+    const KIND: &'static str = "heartbeat";
+}
+
+impl QueuePayload for DocumentEvent {
+    const KIND: &'static str = "document_event";
+}
+
+/// An envelope has the kind of the payload it wraps.
+impl<T: QueuePayload> QueuePayload for QueueEnvelope<T> {
+    const KIND: &'static str = T::KIND;
+}
+
+/// Implements `From` for each payload type of a tagged payload enum.
+macro_rules! impl_from_payloads {
+    ($kind:ident { $($variant:ident($payload:ty)),* $(,)? }) => {
+        $(
+            impl From<$payload> for $kind {
+                fn from(payload: $payload) -> Self {
+                    $kind::$variant(payload)
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_from_payloads;
+
+/// Any payload that can travel on a mixed queue, tagged by `type`.
+///
+/// Lets a single queue carry heterogeneous messages: consumers match on the
+/// enum instead of sniffing JSON. The tag of each variant is its
+/// [`QueuePayload::KIND`]. Unlike [`Notification`](crate::notification::Notification),
+/// which holds the messages delivered on a channel, it also carries metric
+/// batches, consumer results, heartbeats and document events.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use shared_types::payload::QueueMessageKind;
+/// use shared_types::TelegramQueueMessage;
+///
+/// let queued = QueueMessageKind::from(TelegramQueueMessage::new(123, "hi".into(), false));
+/// let json = serde_json::to_value(&queued).unwrap();
+/// assert_eq!(json["type"], "telegram");
+///
+/// match serde_json::from_value(json).unwrap() {
+///     QueueMessageKind::Telegram(message) => assert_eq!(message.message, "hi"),
+///     other => panic!("unexpected {}", other.kind()),
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum QueueMessageKind {
+    Telegram(TelegramQueueMessage),
+    TelegramBroadcast(TelegramBroadcastMessage),
+    Email(EmailQueueMessage),
+    Slack(SlackQueueMessage),
+    Discord(DiscordQueueMessage),
+    Sms(SmsQueueMessage),
+    Webhook(WebhookQueueMessage),
+    Teams(TeamsQueueMessage),
+    Push(PushQueueMessage),
+    #[cfg_attr(feature = "serde", serde(rename = "pagerduty"))]
+    PagerDuty(PagerDutyEvent),
+    Matrix(MatrixQueueMessage),
+    Gotify(GotifyQueueMessage),
+    MetricBatch(MetricBatch),
+    ConsumerResult(ConsumerResult),
+    Heartbeat(Heartbeat),
+    DocumentEvent(DocumentEvent),
+}
+
+impl QueueMessageKind {
+    /// Returns the `type` tag of the wrapped payload.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            QueueMessageKind::Telegram(_) => TelegramQueueMessage::KIND,
+            QueueMessageKind::TelegramBroadcast(_) => TelegramBroadcastMessage::KIND,
+            QueueMessageKind::Email(_) => EmailQueueMessage::KIND,
+            QueueMessageKind::Slack(_) => SlackQueueMessage::KIND,
+            QueueMessageKind::Discord(_) => DiscordQueueMessage::KIND,
+            QueueMessageKind::Sms(_) => SmsQueueMessage::KIND,
+            QueueMessageKind::Webhook(_) => WebhookQueueMessage::KIND,
+            QueueMessageKind::Teams(_) => TeamsQueueMessage::KIND,
+            QueueMessageKind::Push(_) => PushQueueMessage::KIND,
+            QueueMessageKind::PagerDuty(_) => PagerDutyEvent::KIND,
+            QueueMessageKind::Matrix(_) => MatrixQueueMessage::KIND,
+            QueueMessageKind::Gotify(_) => GotifyQueueMessage::KIND,
+            QueueMessageKind::MetricBatch(_) => MetricBatch::KIND,
+            QueueMessageKind::ConsumerResult(_) => ConsumerResult::KIND,
+            QueueMessageKind::Heartbeat(_) => Heartbeat::KIND,
+            QueueMessageKind::DocumentEvent(_) => DocumentEvent::KIND,
+        }
+    }
+}
+
+impl_from_payloads!(QueueMessageKind {
+    Telegram(TelegramQueueMessage),
+    TelegramBroadcast(TelegramBroadcastMessage),
+    Email(EmailQueueMessage),
+    Slack(SlackQueueMessage),
+    Discord(DiscordQueueMessage),
+    Sms(SmsQueueMessage),
+    Webhook(WebhookQueueMessage),
+    Teams(TeamsQueueMessage),
+    Push(PushQueueMessage),
+    PagerDuty(PagerDutyEvent),
+    Matrix(MatrixQueueMessage),
+    Gotify(GotifyQueueMessage),
+    MetricBatch(MetricBatch),
+    ConsumerResult(ConsumerResult),
+    Heartbeat(Heartbeat),
+    DocumentEvent(DocumentEvent),
+});
+
+impl QueuePayload for QueueMessageKind {
+    const KIND: &'static str = "mixed";
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::telegram::MessageStatus;
+
+/// A daily time range, in a given time zone, during which chats should not be disturbed.
+///
+/// The range may wrap around midnight (e.g. `22:00` to `07:00`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct QuietHours {
+    /// IANA time zone the start and end times are expressed in, e.g. `Europe/Rome`.
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "string"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = String))]
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::tz::serialize",
+            deserialize_with = "crate::borsh_with::tz::deserialize"
+        )
+    )]
+    pub tz: Tz,
+    /// Start of the quiet period (inclusive).
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::time::serialize",
+            deserialize_with = "crate::borsh_with::time::deserialize"
+        )
+    )]
+    pub start: NaiveTime,
+    /// End of the quiet period (exclusive).
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::time::serialize",
+            deserialize_with = "crate::borsh_with::time::deserialize"
+        )
+    )]
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Creates quiet hours from `start` to `end` in the time zone `tz`.
+    pub fn new(tz: Tz, start: NaiveTime, end: NaiveTime) -> Self {
+        Self { tz, start, end }
+    }
+
+    /// Returns true when `at` falls within the quiet hours.
+    ///
+    /// ```rust
+    /// use chrono::{NaiveTime, TimeZone, Utc};
+    /// use shared_types::policy::QuietHours;
+    ///
+    /// let quiet = QuietHours::new(
+    ///     chrono_tz::Europe::Rome,
+    ///     NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+    ///     NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+    /// );
+    /// // 23:30 in Rome (UTC+2 in summer)
+    /// assert!(quiet.contains(Utc.with_ymd_and_hms(2026, 7, 1, 21, 30, 0).unwrap()));
+    /// assert!(!quiet.contains(Utc.with_ymd_and_hms(2026, 7, 1, 12, 0, 0).unwrap()));
+    /// ```
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.tz).time();
+        if self.start <= self.end {
+            local >= self.start && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+}
+
+/// Decides whether a message should be force sent, attached by producers and
+/// evaluated by dispatchers.
+///
+/// Force sending delivers the message even when the dispatcher would otherwise
+/// hold or mute it. Messages keep the plain `forceSend` boolean as a serialized
+/// fallback for dispatchers that do not evaluate policies; see
+/// [`SendPolicy::fallback_force_send`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum SendPolicy {
+    /// Always force send.
+    Always,
+    /// Never force send, the default like a `false` `forceSend`.
+    #[default]
+    Never,
+    /// Force send except during the quiet hours.
+    RespectQuietHours(QuietHours),
+    /// Force send only when the message status is at least the given severity.
+    OnlyIfSeverityAtLeast(MessageStatus),
+}
+
+impl SendPolicy {
+    /// Evaluates the policy for a message with the given status at time `now`.
+    pub fn should_force_send(&self, status: MessageStatus, now: DateTime<Utc>) -> bool {
+        match self {
+            SendPolicy::Always => true,
+            SendPolicy::Never => false,
+            SendPolicy::RespectQuietHours(quiet_hours) => !quiet_hours.contains(now),
+            SendPolicy::OnlyIfSeverityAtLeast(min) => status.at_least(*min),
+        }
+    }
+
+    /// Returns the `forceSend` value stored alongside the policy for dispatchers
+    /// that do not evaluate it.
+    ///
+    /// Only [`SendPolicy::Always`] maps to `true`; conditional policies fall back
+    /// to not forcing.
+    pub fn fallback_force_send(&self) -> bool {
+        matches!(self, SendPolicy::Always)
+    }
+}
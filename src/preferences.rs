@@ -0,0 +1,113 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::notification::Notification;
+use crate::policy::QuietHours;
+use crate::telegram::MessageStatus;
+
+/// Delivery preferences of a recipient, evaluated by the dispatchers before
+/// sending a [`Notification`].
+///
+/// Channels are opt-in: a notification is only delivered on the channels
+/// listed in `channels`, by their `channel` tag (`telegram`, `email`, …).
+/// During the quiet hours only `Error` notifications go through.
+///
+/// ```rust
+/// use chrono::{NaiveTime, TimeZone, Utc};
+/// use shared_types::notification::Notification;
+/// use shared_types::preferences::NotificationPreferences;
+/// use shared_types::{EmailQueueMessage, MessageStatus, QuietHours, TelegramQueueMessage};
+///
+/// let preferences = NotificationPreferences::new()
+///     .with_channel("telegram")
+///     .with_min_severity(MessageStatus::Warn)
+///     .with_quiet_hours(QuietHours::new(
+///         chrono_tz::Europe::Rome,
+///         NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+///         NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+///     ))
+///     .with_locale("it-IT");
+/// let noon = Utc.with_ymd_and_hms(2026, 7, 1, 10, 0, 0).unwrap();
+/// let night = Utc.with_ymd_and_hms(2026, 7, 1, 21, 30, 0).unwrap();
+///
+/// let warn = Notification::from(TelegramQueueMessage::builder(-100).status(MessageStatus::Warn).build());
+/// let error = Notification::from(TelegramQueueMessage::builder(-100).status(MessageStatus::Error).build());
+/// let info = Notification::from(TelegramQueueMessage::builder(-100).status(MessageStatus::Info).build());
+/// let email = Notification::from(EmailQueueMessage::new("ops@example.com", "report", "<p>ok</p>"));
+///
+/// assert!(preferences.allows(&warn, noon));
+/// assert!(!preferences.allows(&warn, night));
+/// assert!(preferences.allows(&error, night));
+/// assert!(!preferences.allows(&info, noon));
+/// assert!(!preferences.allows(&email, noon));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct NotificationPreferences {
+    /// Channels the recipient opted in to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub channels: BTreeSet<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "quiet_hours"))]
+    pub quiet_hours: Option<QuietHours>,
+    /// Notifications below this status are dropped.
+    #[cfg_attr(feature = "serde", serde(default, alias = "min_severity"))]
+    pub min_severity: MessageStatus,
+    /// BCP 47 language tag used to render the content, e.g. `it-IT`.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub locale: Option<String>,
+}
+
+impl NotificationPreferences {
+    /// Creates preferences opted in to no channel.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channels.insert(channel.into());
+        self
+    }
+
+    pub fn with_quiet_hours(mut self, quiet_hours: QuietHours) -> Self {
+        self.quiet_hours = Some(quiet_hours);
+        self
+    }
+
+    pub fn with_min_severity(mut self, min_severity: MessageStatus) -> Self {
+        self.min_severity = min_severity;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Returns true when the recipient opted in to `channel`.
+    pub fn is_opted_in(&self, channel: &str) -> bool {
+        self.channels.contains(channel)
+    }
+
+    /// Returns true when `notification` should be delivered at `now`.
+    pub fn allows(&self, notification: &Notification, now: DateTime<Utc>) -> bool {
+        let severity = notification.severity();
+        self.is_opted_in(notification.channel())
+            && severity.at_least(self.min_severity)
+            && (severity.at_least(MessageStatus::Error)
+                || !self.quiet_hours.is_some_and(|quiet_hours| quiet_hours.contains(now)))
+    }
+}
+
+/// Returns true when `notification` should be delivered now according to
+/// `preferences`; see [`NotificationPreferences::allows`].
+pub fn should_deliver(notification: &Notification, preferences: &NotificationPreferences) -> bool {
+    preferences.allows(notification, Utc::now())
+}
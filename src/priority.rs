@@ -0,0 +1,111 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::telegram::MessageStatus;
+
+/// Delivery priority of a queued message, ordered `Low < Normal < High < Critical`.
+///
+/// Dispatchers drain higher priorities first. Transports that carry the
+/// priority outside the body use [`as_str`](Self::as_str), which is also its
+/// serde form, or [`rank`](Self::rank):
+///
+/// ```rust
+/// use shared_types::Priority;
+///
+/// assert_eq!(Priority::High.as_str(), "high");
+/// assert_eq!("Critical".parse::<Priority>(), Ok(Priority::Critical));
+/// assert!("urgent".parse::<Priority>().is_err());
+/// assert_eq!(Priority::from_rank(Priority::Low.rank()), Some(Priority::Low));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+impl Priority {
+    /// Returns the lowercase name of the priority, e.g. `"high"`.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+            Priority::Critical => "critical",
+        }
+    }
+
+    /// Returns the rank of the priority, `0` for `Low` up to `3` for `Critical`.
+    pub const fn rank(&self) -> u8 {
+        match self {
+            Priority::Low => 0,
+            Priority::Normal => 1,
+            Priority::High => 2,
+            Priority::Critical => 3,
+        }
+    }
+
+    /// Returns the priority of a [`rank`](Self::rank), `None` above `3`.
+    pub const fn from_rank(rank: u8) -> Option<Self> {
+        match rank {
+            0 => Some(Priority::Low),
+            1 => Some(Priority::Normal),
+            2 => Some(Priority::High),
+            3 => Some(Priority::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = PriorityError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            "critical" => Ok(Priority::Critical),
+            _ => Err(PriorityError(value.to_string())),
+        }
+    }
+}
+
+/// Error returned when parsing an unknown [`Priority`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriorityError(pub String);
+
+impl fmt::Display for PriorityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown priority {:?}", self.0)
+    }
+}
+
+impl std::error::Error for PriorityError {}
+
+impl From<MessageStatus> for Priority {
+    /// Derives the default priority of a message from its status.
+    ///
+    /// `Error` is critical, `Warn` high, `Info` and `Ok` low; messages without a
+    /// status keep the normal priority.
+    fn from(status: MessageStatus) -> Self {
+        match status {
+            MessageStatus::None => Priority::Normal,
+            MessageStatus::Info | MessageStatus::Ok => Priority::Low,
+            MessageStatus::Warn => Priority::High,
+            MessageStatus::Error => Priority::Critical,
+        }
+    }
+}
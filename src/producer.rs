@@ -0,0 +1,92 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Placeholder for identity fields that cannot be determined.
+pub const UNKNOWN: &str = "unknown";
+
+/// Identity of the service instance that produced a message.
+///
+/// Attached to the envelope so consumers can attribute and rate-limit by
+/// producing service.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ProducerInfo {
+    pub service: String,
+    pub version: String,
+    pub hostname: String,
+    pub pid: u32,
+}
+
+impl ProducerInfo {
+    /// Describes the current process as `service` at `version`.
+    ///
+    /// Prefer the [`producer_info!`](crate::producer_info) macro, which fills in
+    /// the package name and version of the calling crate.
+    pub fn new(service: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+            version: version.into(),
+            hostname: current_hostname(),
+            pid: std::process::id(),
+        }
+    }
+
+    /// Describes the current process from its environment.
+    ///
+    /// The service is read from `SERVICE_NAME`, falling back to the executable
+    /// name; the version from `SERVICE_VERSION`. Missing values are `"unknown"`.
+    pub fn current() -> Self {
+        let service = env::var("SERVICE_NAME")
+            .ok()
+            .filter(|service| !service.is_empty())
+            .or_else(|| {
+                env::current_exe().ok().and_then(|exe| {
+                    exe.file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                })
+            })
+            .unwrap_or_else(|| UNKNOWN.to_string());
+        let version = env::var("SERVICE_VERSION")
+            .ok()
+            .filter(|version| !version.is_empty())
+            .unwrap_or_else(|| UNKNOWN.to_string());
+
+        Self::new(service, version)
+    }
+}
+
+/// Returns the host name from `HOSTNAME` or `/etc/hostname`, or `"unknown"`.
+fn current_hostname() -> String {
+    env::var("HOSTNAME")
+        .ok()
+        .or_else(|| fs::read_to_string(Path::new("/etc/hostname")).ok())
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| UNKNOWN.to_string())
+}
+
+/// Creates a [`ProducerInfo`](crate::producer::ProducerInfo) for the calling
+/// crate, using its Cargo package name and version.
+///
+/// ```rust
+/// use shared_types::producer_info;
+///
+/// let producer = producer_info!();
+/// assert_eq!(producer.pid, std::process::id());
+/// ```
+#[macro_export]
+macro_rules! producer_info {
+    () => {
+        $crate::producer::ProducerInfo::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+    };
+}
@@ -0,0 +1,338 @@
+//! Protobuf messages mirroring `proto/shared_types.proto`, with conversions
+//! from and to the serde types.
+//!
+//! The structs are maintained by hand next to the `.proto` file instead of
+//! being generated, so building the crate does not need `protoc`.
+//!
+//! ```rust
+//! use prost::Message;
+//! use shared_types::proto;
+//! use shared_types::{MessageStatus, QueueEnvelope, TelegramQueueMessage};
+//!
+//! let msg = TelegramQueueMessage::builder(-100)
+//!     .status(MessageStatus::Error)
+//!     .job_name("ftp")
+//!     .content("upload failed")
+//!     .build();
+//! let envelope = QueueEnvelope::new("ftp-sync", msg).with_correlation_id("run-42");
+//!
+//! let bytes = proto::TelegramEnvelope::from(envelope.clone()).encode_to_vec();
+//! let decoded = proto::TelegramEnvelope::decode(bytes.as_slice()).unwrap();
+//! let restored = QueueEnvelope::<TelegramQueueMessage>::try_from(decoded).unwrap();
+//! assert_eq!(restored.message_id, envelope.message_id);
+//! assert_eq!(restored.created_at, envelope.created_at);
+//! assert_eq!(restored.correlation_id.as_deref(), Some("run-42"));
+//! assert_eq!(restored.payload.message, envelope.payload.message);
+//! assert_eq!(restored.payload.status, MessageStatus::Error);
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::envelope::QueueEnvelope;
+use crate::error::SharedTypesError;
+use crate::priority;
+use crate::producer;
+use crate::retry;
+use crate::telegram;
+
+/// `shared_types.v1.MessageStatus`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum MessageStatus {
+    None = 0,
+    Info = 1,
+    Ok = 2,
+    Warn = 3,
+    Error = 4,
+}
+
+/// `shared_types.v1.Priority`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Priority {
+    Normal = 0,
+    Low = 1,
+    High = 2,
+    Critical = 3,
+}
+
+/// `shared_types.v1.TelegramQueueMessage`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TelegramQueueMessage {
+    #[prost(uint32, tag = "1")]
+    pub schema_version: u32,
+    #[prost(int64, tag = "2")]
+    pub chat_id: i64,
+    #[prost(string, tag = "3")]
+    pub message: String,
+    #[prost(bool, tag = "4")]
+    pub force_send: bool,
+    #[prost(enumeration = "MessageStatus", tag = "5")]
+    pub status: i32,
+    #[prost(string, optional, tag = "6")]
+    pub dedup_key: Option<String>,
+    #[prost(map = "string, string", tag = "7")]
+    pub metadata: HashMap<String, String>,
+}
+
+/// `shared_types.v1.ProducerInfo`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProducerInfo {
+    #[prost(string, tag = "1")]
+    pub service: String,
+    #[prost(string, tag = "2")]
+    pub version: String,
+    #[prost(string, tag = "3")]
+    pub hostname: String,
+    #[prost(uint32, tag = "4")]
+    pub pid: u32,
+}
+
+/// `shared_types.v1.RetryInfo`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RetryInfo {
+    #[prost(uint32, tag = "1")]
+    pub attempt: u32,
+    #[prost(uint32, tag = "2")]
+    pub max_attempts: u32,
+    #[prost(string, optional, tag = "3")]
+    pub last_error: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    pub next_retry_at: Option<prost_types::Timestamp>,
+}
+
+/// `shared_types.v1.TelegramEnvelope`, a [`QueueEnvelope`] of a Telegram message.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TelegramEnvelope {
+    #[prost(uint32, tag = "1")]
+    pub schema_version: u32,
+    #[prost(string, tag = "2")]
+    pub message_id: String,
+    #[prost(message, optional, tag = "3")]
+    pub created_at: Option<prost_types::Timestamp>,
+    #[prost(string, optional, tag = "4")]
+    pub correlation_id: Option<String>,
+    #[prost(string, tag = "5")]
+    pub producer: String,
+    #[prost(message, optional, tag = "6")]
+    pub producer_info: Option<ProducerInfo>,
+    #[prost(string, optional, tag = "7")]
+    pub traceparent: Option<String>,
+    #[prost(string, optional, tag = "8")]
+    pub tracestate: Option<String>,
+    #[prost(string, optional, tag = "9")]
+    pub routing_key: Option<String>,
+    #[prost(string, optional, tag = "10")]
+    pub idempotency_key: Option<String>,
+    #[prost(enumeration = "Priority", tag = "11")]
+    pub priority: i32,
+    #[prost(message, optional, tag = "12")]
+    pub expires_at: Option<prost_types::Timestamp>,
+    #[prost(uint64, optional, tag = "13")]
+    pub deliver_after_ms: Option<u64>,
+    #[prost(message, optional, tag = "14")]
+    pub deliver_at: Option<prost_types::Timestamp>,
+    #[prost(message, optional, tag = "15")]
+    pub retry: Option<RetryInfo>,
+    #[prost(message, optional, tag = "16")]
+    pub payload: Option<TelegramQueueMessage>,
+}
+
+impl From<telegram::MessageStatus> for MessageStatus {
+    fn from(status: telegram::MessageStatus) -> Self {
+        match status {
+            telegram::MessageStatus::None => MessageStatus::None,
+            telegram::MessageStatus::Info => MessageStatus::Info,
+            telegram::MessageStatus::Ok => MessageStatus::Ok,
+            telegram::MessageStatus::Warn => MessageStatus::Warn,
+            telegram::MessageStatus::Error => MessageStatus::Error,
+        }
+    }
+}
+
+impl From<MessageStatus> for telegram::MessageStatus {
+    fn from(status: MessageStatus) -> Self {
+        match status {
+            MessageStatus::None => telegram::MessageStatus::None,
+            MessageStatus::Info => telegram::MessageStatus::Info,
+            MessageStatus::Ok => telegram::MessageStatus::Ok,
+            MessageStatus::Warn => telegram::MessageStatus::Warn,
+            MessageStatus::Error => telegram::MessageStatus::Error,
+        }
+    }
+}
+
+impl From<priority::Priority> for Priority {
+    fn from(priority: priority::Priority) -> Self {
+        match priority {
+            priority::Priority::Low => Priority::Low,
+            priority::Priority::Normal => Priority::Normal,
+            priority::Priority::High => Priority::High,
+            priority::Priority::Critical => Priority::Critical,
+        }
+    }
+}
+
+impl From<Priority> for priority::Priority {
+    fn from(priority: Priority) -> Self {
+        match priority {
+            Priority::Low => priority::Priority::Low,
+            Priority::Normal => priority::Priority::Normal,
+            Priority::High => priority::Priority::High,
+            Priority::Critical => priority::Priority::Critical,
+        }
+    }
+}
+
+impl From<telegram::TelegramQueueMessage> for TelegramQueueMessage {
+    /// Converts the message; the send policy is dropped and `force_send` keeps
+    /// its fallback value.
+    fn from(message: telegram::TelegramQueueMessage) -> Self {
+        Self {
+            schema_version: message.schema_version,
+            chat_id: message.chat_id,
+            message: message.message,
+            force_send: message.force_send,
+            status: MessageStatus::from(message.status) as i32,
+            dedup_key: message.dedup_key,
+            metadata: message.metadata,
+        }
+    }
+}
+
+impl TryFrom<TelegramQueueMessage> for telegram::TelegramQueueMessage {
+    type Error = SharedTypesError;
+
+    fn try_from(message: TelegramQueueMessage) -> Result<Self, Self::Error> {
+        let status = MessageStatus::try_from(message.status)
+            .map_err(|_| SharedTypesError::Validation(format!("unknown message status {}", message.status)))?;
+        let mut converted = telegram::TelegramQueueMessage::new(message.chat_id, message.message, message.force_send);
+        converted.schema_version = message.schema_version;
+        converted.status = status.into();
+        converted.dedup_key = message.dedup_key;
+        converted.metadata = message.metadata;
+        Ok(converted)
+    }
+}
+
+impl From<producer::ProducerInfo> for ProducerInfo {
+    fn from(info: producer::ProducerInfo) -> Self {
+        Self {
+            service: info.service,
+            version: info.version,
+            hostname: info.hostname,
+            pid: info.pid,
+        }
+    }
+}
+
+impl From<ProducerInfo> for producer::ProducerInfo {
+    fn from(info: ProducerInfo) -> Self {
+        Self {
+            service: info.service,
+            version: info.version,
+            hostname: info.hostname,
+            pid: info.pid,
+        }
+    }
+}
+
+impl From<retry::RetryInfo> for RetryInfo {
+    fn from(retry: retry::RetryInfo) -> Self {
+        Self {
+            attempt: retry.attempt,
+            max_attempts: retry.max_attempts,
+            last_error: retry.last_error,
+            next_retry_at: retry.next_retry_at.map(to_timestamp),
+        }
+    }
+}
+
+impl TryFrom<RetryInfo> for retry::RetryInfo {
+    type Error = SharedTypesError;
+
+    fn try_from(retry: RetryInfo) -> Result<Self, Self::Error> {
+        let mut converted = retry::RetryInfo::new(retry.max_attempts);
+        converted.attempt = retry.attempt;
+        converted.last_error = retry.last_error;
+        converted.next_retry_at = retry.next_retry_at.map(from_timestamp).transpose()?;
+        Ok(converted)
+    }
+}
+
+impl From<QueueEnvelope<telegram::TelegramQueueMessage>> for TelegramEnvelope {
+    fn from(envelope: QueueEnvelope<telegram::TelegramQueueMessage>) -> Self {
+        Self {
+            schema_version: envelope.schema_version,
+            message_id: envelope.message_id.to_string(),
+            created_at: Some(to_timestamp(envelope.created_at)),
+            correlation_id: envelope.correlation_id,
+            producer: envelope.producer,
+            producer_info: envelope.producer_info.map(Into::into),
+            traceparent: envelope.traceparent,
+            tracestate: envelope.tracestate,
+            routing_key: envelope.routing_key,
+            idempotency_key: envelope.idempotency_key,
+            priority: Priority::from(envelope.priority) as i32,
+            expires_at: envelope.expires_at.map(to_timestamp),
+            deliver_after_ms: envelope
+                .deliver_after
+                .map(|delay| u64::try_from(delay.as_millis()).unwrap_or(u64::MAX)),
+            deliver_at: envelope.deliver_at.map(to_timestamp),
+            retry: envelope.retry.map(Into::into),
+            payload: Some(envelope.payload.into()),
+        }
+    }
+}
+
+impl TryFrom<TelegramEnvelope> for QueueEnvelope<telegram::TelegramQueueMessage> {
+    type Error = SharedTypesError;
+
+    fn try_from(envelope: TelegramEnvelope) -> Result<Self, Self::Error> {
+        let payload = envelope
+            .payload
+            .ok_or_else(|| SharedTypesError::Validation("envelope has no payload".to_string()))?;
+        let created_at = envelope
+            .created_at
+            .ok_or_else(|| SharedTypesError::Validation("envelope has no creation time".to_string()))?;
+        let priority = Priority::try_from(envelope.priority)
+            .map_err(|_| SharedTypesError::Validation(format!("unknown priority {}", envelope.priority)))?;
+
+        let mut converted = QueueEnvelope::new(envelope.producer, payload.try_into()?);
+        converted.schema_version = envelope.schema_version;
+        converted.message_id = envelope
+            .message_id
+            .parse()
+            .map_err(|_| SharedTypesError::Validation(format!("invalid message id {:?}", envelope.message_id)))?;
+        converted.created_at = from_timestamp(created_at)?;
+        converted.correlation_id = envelope.correlation_id;
+        converted.producer_info = envelope.producer_info.map(Into::into);
+        converted.traceparent = envelope.traceparent;
+        converted.tracestate = envelope.tracestate;
+        converted.routing_key = envelope.routing_key;
+        converted.idempotency_key = envelope.idempotency_key;
+        converted.priority = priority.into();
+        converted.expires_at = envelope.expires_at.map(from_timestamp).transpose()?;
+        converted.deliver_after = envelope.deliver_after_ms.map(Duration::from_millis);
+        converted.deliver_at = envelope.deliver_at.map(from_timestamp).transpose()?;
+        converted.retry = envelope.retry.map(TryInto::try_into).transpose()?;
+        Ok(converted)
+    }
+}
+
+fn to_timestamp(at: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: at.timestamp(),
+        nanos: at.timestamp_subsec_nanos() as i32,
+    }
+}
+
+fn from_timestamp(timestamp: prost_types::Timestamp) -> Result<DateTime<Utc>, SharedTypesError> {
+    u32::try_from(timestamp.nanos)
+        .ok()
+        .and_then(|nanos| DateTime::from_timestamp(timestamp.seconds, nanos))
+        .ok_or_else(|| SharedTypesError::Validation(format!("invalid timestamp {}", timestamp)))
+}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde_json::{Value, json};
+
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// Who receives a push notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum PushTarget {
+    /// A single device registration token.
+    DeviceToken(String),
+    /// Every device subscribed to an FCM topic.
+    Topic(String),
+}
+
+/// Delivery priority of a push notification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum PushPriority {
+    /// Delivered when convenient for the device battery.
+    #[default]
+    Normal,
+    /// Delivered immediately, waking the device.
+    High,
+}
+
+impl From<MessageStatus> for PushPriority {
+    /// `Warn` and `Error` are delivered with high priority.
+    fn from(status: MessageStatus) -> Self {
+        if status.at_least(MessageStatus::Warn) {
+            PushPriority::High
+        } else {
+            PushPriority::Normal
+        }
+    }
+}
+
+/// An APNs request: the device token, HTTP/2 headers and JSON payload.
+///
+/// The `apns-topic` header (the app bundle id) is added by the sender.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApnsRequest {
+    pub device_token: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub payload: Value,
+}
+
+/// Represents a message received from the queue to be sent as a mobile push notification.
+///
+/// ```rust
+/// use shared_types::{MessageStatus, PushQueueMessage};
+/// use shared_types::push::PushTarget;
+///
+/// let push = PushQueueMessage::new(PushTarget::DeviceToken("abc123".into()), "Backup failed", "ftp01 is full")
+///     .with_status(MessageStatus::Error)
+///     .with_data("job", "backup")
+///     .with_collapse_key("backup");
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let fcm = push.to_fcm();
+/// assert_eq!(fcm["message"]["token"], "abc123");
+/// assert_eq!(fcm["message"]["android"]["priority"], "HIGH");
+///
+/// let apns = push.to_apns().unwrap();
+/// assert!(apns.headers.contains(&("apns-priority", "10".to_string())));
+/// assert_eq!(apns.payload["aps"]["alert"]["title"], "Backup failed");
+/// assert_eq!(apns.payload["job"], "backup");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct PushQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    pub target: PushTarget,
+    pub title: String,
+    pub body: String,
+    /// Custom key/value data delivered to the app.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub data: HashMap<String, String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub priority: PushPriority,
+    /// Notifications with the same key replace each other on the device.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "collapse_key"))]
+    pub collapse_key: Option<String>,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+}
+
+impl PushQueueMessage {
+    pub fn new(target: PushTarget, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            target,
+            title: title.into(),
+            body: body.into(),
+            data: HashMap::new(),
+            priority: PushPriority::default(),
+            collapse_key: None,
+            status: MessageStatus::None,
+            dedup_key: None,
+        }
+    }
+
+    /// Sets the status and the priority derived from it.
+    pub fn with_status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self.priority = PushPriority::from(status);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: PushPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_data(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.data.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn with_collapse_key(mut self, collapse_key: impl Into<String>) -> Self {
+        self.collapse_key = Some(collapse_key.into());
+        self
+    }
+
+    /// Returns the FCM HTTP v1 `messages:send` request body.
+    #[cfg(feature = "serde")]
+    pub fn to_fcm(&self) -> Value {
+        let mut message = json!({
+            "notification": { "title": self.title, "body": self.body },
+            "android": {
+                "priority": match self.priority {
+                    PushPriority::Normal => "NORMAL",
+                    PushPriority::High => "HIGH",
+                },
+            },
+            "apns": { "headers": apns_headers(self).into_iter().collect::<HashMap<_, _>>() },
+        });
+        match &self.target {
+            PushTarget::DeviceToken(token) => message["token"] = json!(token),
+            PushTarget::Topic(topic) => message["topic"] = json!(topic),
+        }
+        if !self.data.is_empty() {
+            message["data"] = json!(self.data);
+        }
+        if let Some(collapse_key) = &self.collapse_key {
+            message["android"]["collapse_key"] = json!(collapse_key);
+        }
+        json!({ "message": message })
+    }
+
+    /// Returns the direct APNs request, `None` for topic targets which APNs does not support.
+    ///
+    /// Custom data is sent as top-level keys next to `aps`.
+    #[cfg(feature = "serde")]
+    pub fn to_apns(&self) -> Option<ApnsRequest> {
+        let PushTarget::DeviceToken(device_token) = &self.target else {
+            return None;
+        };
+        let mut payload = json!({
+            "aps": { "alert": { "title": self.title, "body": self.body } },
+        });
+        if self.priority == PushPriority::High {
+            payload["aps"]["sound"] = json!("default");
+        }
+        for (key, value) in &self.data {
+            if key != "aps" {
+                payload[key] = json!(value);
+            }
+        }
+        Some(ApnsRequest {
+            device_token: device_token.clone(),
+            headers: apns_headers(self),
+            payload,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+fn apns_headers(message: &PushQueueMessage) -> Vec<(&'static str, String)> {
+    let priority = match message.priority {
+        PushPriority::Normal => "5",
+        PushPriority::High => "10",
+    };
+    let mut headers = vec![
+        ("apns-push-type", "alert".to_string()),
+        ("apns-priority", priority.to_string()),
+    ];
+    if let Some(collapse_key) = &message.collapse_key {
+        headers.push(("apns-collapse-id", collapse_key.clone()));
+    }
+    headers
+}
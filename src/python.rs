@@ -0,0 +1,250 @@
+//! Python bindings of [`TelegramQueueMessage`], its builder and the sanitizer,
+//! so Python jobs build messages with the same logic as the Rust services.
+//!
+//! Build the extension module with `maturin build --release --features python`;
+//! maturin builds the crate as a `cdylib`, which it is not by default.
+//! Statuses are passed as strings parsed like [`MessageStatus::from_str`],
+//! errors are raised as `ValueError`, and builder methods return the builder so
+//! calls are chained.
+//!
+//! ```python
+//! from shared_types import TelegramMessageBuilder, sanitize
+//!
+//! message = (
+//!     TelegramMessageBuilder(-100123)
+//!     .status("warn")
+//!     .job_name("forecast")
+//!     .content("model drift above 5%")
+//!     .build()
+//! )
+//! queue.publish(message.to_json())
+//! sanitize("<b>ok</b> <script>", 4096)  # '<b>ok</b> &lt;script&gt;'
+//! ```
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::telegram::{MessageStatus, TELEGRAM_MAX_MESSAGE_LENGTH, TelegramMessageBuilder, TelegramQueueMessage};
+
+fn value_error(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// A [`TelegramQueueMessage`] exported to Python.
+///
+/// ```rust
+/// use shared_types::python::PyTelegramQueueMessage;
+///
+/// let mut message = PyTelegramQueueMessage::new(123, "<b>hi</b> <script>".into(), false);
+/// message.sanitize_message(4096);
+/// assert_eq!(message.message(), "<b>hi</b> &lt;script&gt;");
+/// assert_eq!(message.to_json().unwrap(), r#"{"schemaVersion":1,"chatId":123,"message":"<b>hi</b> &lt;script&gt;","forceSend":false}"#);
+/// ```
+#[pyclass(name = "TelegramQueueMessage", module = "shared_types", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyTelegramQueueMessage(TelegramQueueMessage);
+
+#[pymethods]
+impl PyTelegramQueueMessage {
+    #[new]
+    #[pyo3(signature = (chat_id, message, force_send = false))]
+    pub fn new(chat_id: i64, message: String, force_send: bool) -> Self {
+        Self(TelegramQueueMessage::new(chat_id, message, force_send))
+    }
+
+    /// Parses a queued message, accepting the same payloads as the consumers.
+    #[staticmethod]
+    pub fn from_json(json: &str) -> PyResult<Self> {
+        serde_json::from_str(json).map(Self).map_err(value_error)
+    }
+
+    /// Returns the JSON payload to publish on the queue.
+    pub fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.0).map_err(value_error)
+    }
+
+    #[getter]
+    pub fn chat_id(&self) -> i64 {
+        self.0.chat_id
+    }
+
+    #[getter]
+    pub fn message(&self) -> String {
+        self.0.message.clone()
+    }
+
+    #[getter]
+    pub fn force_send(&self) -> bool {
+        self.0.force_send
+    }
+
+    /// Returns the status as its lowercase name, e.g. `"warn"`.
+    #[getter]
+    pub fn status(&self) -> &'static str {
+        self.0.status.as_str()
+    }
+
+    #[getter]
+    pub fn dedup_key(&self) -> Option<String> {
+        self.0.dedup_key.clone()
+    }
+
+    #[getter]
+    pub fn metadata(&self) -> HashMap<String, String> {
+        self.0.metadata.clone()
+    }
+
+    /// See [`TelegramQueueMessage::sanitize_message`].
+    #[pyo3(signature = (max_message_length = TELEGRAM_MAX_MESSAGE_LENGTH))]
+    pub fn sanitize_message(&mut self, max_message_length: usize) {
+        self.0.sanitize_message(max_message_length);
+    }
+
+    /// Raises `ValueError` when the message exceeds the Telegram length limit.
+    pub fn check_length(&self) -> PyResult<()> {
+        self.0.check_length().map_err(value_error)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "TelegramQueueMessage(chat_id={}, status='{}', length={})",
+            self.0.chat_id,
+            self.0.status.as_str(),
+            self.0.message.chars().count()
+        )
+    }
+}
+
+impl From<TelegramQueueMessage> for PyTelegramQueueMessage {
+    fn from(message: TelegramQueueMessage) -> Self {
+        Self(message)
+    }
+}
+
+impl From<PyTelegramQueueMessage> for TelegramQueueMessage {
+    fn from(message: PyTelegramQueueMessage) -> Self {
+        message.0
+    }
+}
+
+/// A [`TelegramMessageBuilder`] exported to Python.
+#[pyclass(name = "TelegramMessageBuilder", module = "shared_types")]
+pub struct PyTelegramMessageBuilder {
+    chat_id: i64,
+    builder: TelegramMessageBuilder,
+}
+
+impl PyTelegramMessageBuilder {
+    /// Applies a consuming builder method in place.
+    fn apply(mut slf: PyRefMut<'_, Self>, f: impl FnOnce(TelegramMessageBuilder) -> TelegramMessageBuilder) -> PyRefMut<'_, Self> {
+        let placeholder = TelegramMessageBuilder::new(slf.chat_id);
+        let builder = std::mem::replace(&mut slf.builder, placeholder);
+        slf.builder = f(builder);
+        slf
+    }
+}
+
+#[pymethods]
+impl PyTelegramMessageBuilder {
+    #[new]
+    pub fn new(chat_id: i64) -> Self {
+        Self {
+            chat_id,
+            builder: TelegramMessageBuilder::new(chat_id),
+        }
+    }
+
+    /// Sets the status from its name, raising `ValueError` on unknown statuses.
+    pub fn status<'py>(slf: PyRefMut<'py, Self>, status: &str) -> PyResult<PyRefMut<'py, Self>> {
+        let status = MessageStatus::from_str(status).map_err(value_error)?;
+        Ok(Self::apply(slf, |builder| builder.status(status)))
+    }
+
+    pub fn emoji(slf: PyRefMut<'_, Self>, emoji: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.emoji(emoji))
+    }
+
+    pub fn job_name(slf: PyRefMut<'_, Self>, job_name: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.job_name(job_name))
+    }
+
+    pub fn summary(slf: PyRefMut<'_, Self>, summary: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.summary(summary))
+    }
+
+    pub fn content(slf: PyRefMut<'_, Self>, content: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.content(content))
+    }
+
+    pub fn code(slf: PyRefMut<'_, Self>, snippet: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.code(snippet))
+    }
+
+    pub fn code_block(slf: PyRefMut<'_, Self>, snippet: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.code_block(snippet))
+    }
+
+    pub fn details(slf: PyRefMut<'_, Self>, details: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.details(details))
+    }
+
+    pub fn mention_user(slf: PyRefMut<'_, Self>, user_id: i64, display_name: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.mention_user(user_id, display_name))
+    }
+
+    pub fn mention_username(slf: PyRefMut<'_, Self>, username: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.mention_username(username))
+    }
+
+    pub fn force_send(slf: PyRefMut<'_, Self>, force_send: bool) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.force_send(force_send))
+    }
+
+    pub fn tag(slf: PyRefMut<'_, Self>, tag: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.tag(tag))
+    }
+
+    pub fn dedup_key(slf: PyRefMut<'_, Self>, dedup_key: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.dedup_key(dedup_key))
+    }
+
+    pub fn meta(slf: PyRefMut<'_, Self>, key: String, value: String) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.meta(key, value))
+    }
+
+    /// See [`TelegramMessageBuilder::sanitize`].
+    #[pyo3(signature = (max_length = TELEGRAM_MAX_MESSAGE_LENGTH))]
+    pub fn sanitize(slf: PyRefMut<'_, Self>, max_length: usize) -> PyRefMut<'_, Self> {
+        Self::apply(slf, |builder| builder.sanitize(max_length))
+    }
+
+    /// Builds the message, leaving the builder empty.
+    pub fn build(&mut self) -> PyTelegramQueueMessage {
+        let placeholder = TelegramMessageBuilder::new(self.chat_id);
+        PyTelegramQueueMessage(std::mem::replace(&mut self.builder, placeholder).build())
+    }
+}
+
+/// Escapes `text` for Telegram HTML, keeping the allowed formatting tags and
+/// truncating it to `max_length` characters; see
+/// [`TelegramQueueMessage::sanitize_message`].
+#[pyfunction]
+#[pyo3(signature = (text, max_length = TELEGRAM_MAX_MESSAGE_LENGTH))]
+pub fn sanitize(text: String, max_length: usize) -> String {
+    let mut message = TelegramQueueMessage::new(0, text, false);
+    message.sanitize_message(max_length);
+    message.message
+}
+
+/// The `shared_types` Python module.
+#[pymodule]
+pub fn shared_types(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyTelegramQueueMessage>()?;
+    module.add_class::<PyTelegramMessageBuilder>()?;
+    module.add_function(wrap_pyfunction!(sanitize, module)?)?;
+    module.add("TELEGRAM_MAX_MESSAGE_LENGTH", TELEGRAM_MAX_MESSAGE_LENGTH)?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Used percentage from which a quota is a warning.
+pub const QUOTA_WARN_PERCENT: f64 = 80.0;
+
+/// Used percentage from which a quota is an error.
+pub const QUOTA_ERROR_PERCENT: f64 = 95.0;
+
+/// Period after which a quota resets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum QuotaPeriod {
+    Hourly,
+    Daily,
+    Monthly,
+}
+
+impl QuotaPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaPeriod::Hourly => "hourly",
+            QuotaPeriod::Daily => "daily",
+            QuotaPeriod::Monthly => "monthly",
+        }
+    }
+}
+
+/// Consumption of a billed or rate limited resource, reported by the billing
+/// and API quota monitoring jobs.
+///
+/// ```rust
+/// use shared_types::MessageStatus;
+/// use shared_types::quota::{QuotaPeriod, QuotaUsage};
+///
+/// let usage = QuotaUsage::new("maps-api", 8_600, 10_000, QuotaPeriod::Daily);
+/// assert_eq!(usage.percent, 86.0);
+/// assert_eq!(usage.severity(), MessageStatus::Warn);
+/// assert_eq!(
+///     usage.to_telegram_message(123).message,
+///     "⚠️ - <i>quota</i>\nmaps-api: 86% of the daily quota used\n8600 of 10000, 1400 left"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct QuotaUsage {
+    pub resource: String,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub used: u64,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub limit: u64,
+    pub period: QuotaPeriod,
+    /// Used share of the limit in percent, over 100 once exceeded.
+    pub percent: f64,
+}
+
+impl QuotaUsage {
+    /// Creates the usage of a resource, computing `percent`.
+    pub fn new(resource: impl Into<String>, used: u64, limit: u64, period: QuotaPeriod) -> Self {
+        let percent = if limit == 0 {
+            100.0
+        } else {
+            used as f64 * 100.0 / limit as f64
+        };
+        Self {
+            resource: resource.into(),
+            used,
+            limit,
+            period,
+            percent,
+        }
+    }
+
+    /// Returns what is left before the limit, 0 once exceeded.
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.used)
+    }
+
+    /// `Error` from [`QUOTA_ERROR_PERCENT`], `Warn` from [`QUOTA_WARN_PERCENT`], `Ok` below.
+    pub fn severity(&self) -> MessageStatus {
+        if self.percent >= QUOTA_ERROR_PERCENT {
+            MessageStatus::Error
+        } else if self.percent >= QUOTA_WARN_PERCENT {
+            MessageStatus::Warn
+        } else {
+            MessageStatus::Ok
+        }
+    }
+
+    /// Formats the usage for the `quota` job, deduplicated per resource and severity.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("quota")
+            .summary(format!(
+                "{}: {:.0}% of the {} quota used",
+                render::escape_text(&self.resource),
+                self.percent,
+                self.period.as_str()
+            ))
+            .content(format!("{} of {}, {} left", self.used, self.limit, self.remaining()))
+            .dedup_key(format!("quota:{}:{:?}", self.resource, self.severity()))
+            .build()
+    }
+}
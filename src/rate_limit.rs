@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::consumer::ConsumeOutcome;
+
+/// Messages per second a Telegram bot may send overall.
+pub const TELEGRAM_GLOBAL_MESSAGES_PER_SECOND: u32 = 30;
+
+/// Messages per minute a Telegram bot may send to the same group.
+pub const TELEGRAM_GROUP_MESSAGES_PER_MINUTE: u32 = 20;
+
+/// Token bucket allowing `capacity` messages per `window`, refilled continuously.
+///
+/// Serializable so a bot worker can share its state with the producers, which
+/// then apply the same math before publishing.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use chrono::{TimeZone, Utc};
+/// use shared_types::rate_limit::RateLimitState;
+///
+/// let now = Utc.with_ymd_and_hms(2024, 5, 17, 3, 0, 0).unwrap();
+/// let mut group = RateLimitState::telegram_group(now);
+/// for _ in 0..20 {
+///     assert!(group.try_acquire(now).is_ok());
+/// }
+/// let hint = group.try_acquire(now).unwrap_err();
+/// assert_eq!(hint.retry_after, Duration::from_secs(3));
+/// assert!(group.try_acquire(now + chrono::Duration::seconds(3)).is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct RateLimitState {
+    /// Messages allowed per window, also the largest burst.
+    pub capacity: u32,
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+    #[cfg_attr(feature = "serde", serde(rename = "windowMs", with = "crate::duration_ms", alias = "window_ms"))]
+    pub window: Duration,
+    /// Messages that can be sent right now, fractional while refilling.
+    pub tokens: f64,
+    /// When `tokens` was last computed.
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "updated_at"))]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RateLimitState {
+    /// Creates a full bucket of `capacity` messages per `window`.
+    pub fn new(capacity: u32, window: Duration, now: DateTime<Utc>) -> Self {
+        Self {
+            capacity,
+            window,
+            tokens: f64::from(capacity),
+            updated_at: now,
+        }
+    }
+
+    /// Bucket for the global limit of a bot, see [`TELEGRAM_GLOBAL_MESSAGES_PER_SECOND`].
+    pub fn telegram_global(now: DateTime<Utc>) -> Self {
+        Self::new(TELEGRAM_GLOBAL_MESSAGES_PER_SECOND, Duration::from_secs(1), now)
+    }
+
+    /// Bucket for the limit of a group chat, see [`TELEGRAM_GROUP_MESSAGES_PER_MINUTE`].
+    pub fn telegram_group(now: DateTime<Utc>) -> Self {
+        Self::new(TELEGRAM_GROUP_MESSAGES_PER_MINUTE, Duration::from_secs(60), now)
+    }
+
+    /// Returns the tokens added per second.
+    pub fn refill_rate(&self) -> f64 {
+        f64::from(self.capacity) / self.window.as_secs_f64()
+    }
+
+    /// Adds the tokens refilled since `updated_at`, up to the capacity.
+    pub fn refill(&mut self, now: DateTime<Utc>) {
+        let elapsed = (now - self.updated_at).to_std().unwrap_or_default();
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.refill_rate()).min(f64::from(self.capacity));
+        self.updated_at = self.updated_at.max(now);
+    }
+
+    /// Returns how long to wait at `now` before a message can be sent.
+    pub fn wait_time(&self, now: DateTime<Utc>) -> Duration {
+        let mut state = self.clone();
+        state.refill(now);
+        if state.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - state.tokens) / state.refill_rate())
+        }
+    }
+
+    /// Takes a token for one message at `now`, or returns how long to wait.
+    pub fn try_acquire(&mut self, now: DateTime<Utc>) -> Result<(), ThrottleHint> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(ThrottleHint::new(self.wait_time(now)))
+        }
+    }
+}
+
+/// Tells a producer to hold back, returned by bot workers on queue responses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ThrottleHint {
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+    #[cfg_attr(feature = "serde", serde(rename = "retryAfterMs", with = "crate::duration_ms", alias = "retry_after_ms"))]
+    pub retry_after: Duration,
+    /// The throttled chat, `None` when the whole bot is throttled.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "chat_id"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    pub chat_id: Option<i64>,
+}
+
+impl ThrottleHint {
+    pub fn new(retry_after: Duration) -> Self {
+        Self {
+            retry_after,
+            chat_id: None,
+        }
+    }
+
+    /// Creates the hint of a Telegram `429 Too Many Requests` answer, from its
+    /// `parameters.retry_after` in seconds.
+    pub fn from_retry_after_secs(retry_after: u64) -> Self {
+        Self::new(Duration::from_secs(retry_after))
+    }
+
+    pub fn with_chat_id(mut self, chat_id: i64) -> Self {
+        self.chat_id = Some(chat_id);
+        self
+    }
+}
+
+impl From<ThrottleHint> for ConsumeOutcome {
+    /// Requeues the message after the hinted delay.
+    fn from(hint: ThrottleHint) -> Self {
+        ConsumeOutcome::NackRequeue {
+            delay: hint.retry_after,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Formatting helpers shared by the channel message builders.
+
+use std::borrow::Cow;
+use std::time::Duration;
+
+/// Returns `{emoji} {job_name}: {summary}`, leaving out the parts that are empty.
+pub(crate) fn headline(emoji: &str, job_name: &str, summary: &str) -> String {
+    let title = match (job_name, summary) {
+        (job_name, "") => job_name.to_string(),
+        ("", summary) => summary.to_string(),
+        (job_name, summary) => format!("{}: {}", job_name, summary),
+    };
+    [emoji, title.as_str()]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Truncates `text` to `max_length` characters, ending it with `...` when cut.
+pub(crate) fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_length.saturating_sub(3)).collect();
+    format!("{}...", kept)
+}
+
+/// Returns `html` as a single line of plain text of at most `max_length`
+/// characters, ending it with `…` when cut.
+pub(crate) fn preview(html: &str, max_length: usize) -> String {
+    let text = html_to_text(html).split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_length {
+        return text;
+    }
+    let kept: String = text.chars().take(max_length.saturating_sub(1)).collect();
+    format!("{}…", kept.trim_end())
+}
+
+/// Converts an HTML fragment to plain text: line breaking tags become newlines,
+/// other tags are dropped and entities decoded.
+pub(crate) fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            text.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+        let name = tag.trim_start_matches('/').split([' ', '/']).next().unwrap_or("");
+        if matches!(name, "br" | "p" | "div" | "pre" | "blockquote" | "li" | "tr")
+            && (tag.starts_with('/') || name == "br")
+            && !text.ends_with('\n')
+        {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    decode_entities(text.trim()).into_owned()
+}
+
+/// Escapes `&`, `<` and `>` for the text of an HTML element.
+#[cfg(feature = "html-escape")]
+pub(crate) fn escape_text(text: &str) -> Cow<'_, str> {
+    html_escape::encode_text(text)
+}
+
+/// Escapes `&`, `<` and `>` for the text of an HTML element.
+#[cfg(not(feature = "html-escape"))]
+pub(crate) fn escape_text(text: &str) -> Cow<'_, str> {
+    escape(text, &['&', '<', '>'])
+}
+
+/// Escapes `&`, `<`, `>` and `"` for a double quoted HTML attribute value.
+#[cfg(feature = "html-escape")]
+pub(crate) fn escape_attribute(value: &str) -> Cow<'_, str> {
+    html_escape::encode_double_quoted_attribute(value)
+}
+
+/// Escapes `&`, `<`, `>` and `"` for a double quoted HTML attribute value.
+#[cfg(not(feature = "html-escape"))]
+pub(crate) fn escape_attribute(value: &str) -> Cow<'_, str> {
+    escape(value, &['&', '<', '>', '"'])
+}
+
+#[cfg(not(feature = "html-escape"))]
+fn escape<'a>(text: &'a str, special: &[char]) -> Cow<'a, str> {
+    if !text.contains(special) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 16);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if special.contains(&'"') => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Decodes the HTML entities of `text`.
+#[cfg(feature = "html-escape")]
+pub(crate) fn decode_entities(text: &str) -> Cow<'_, str> {
+    html_escape::decode_html_entities(text)
+}
+
+/// Decodes the numeric entities of `text` and the named ones of XML plus
+/// `&nbsp;`; the other named entities need the `html-escape` feature and are
+/// kept as they are.
+#[cfg(not(feature = "html-escape"))]
+pub(crate) fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+        let c = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => match entity.strip_prefix('#')? {
+                hex if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16).ok(),
+                decimal => decimal.parse().ok(),
+            }
+            .and_then(char::from_u32),
+        });
+        match (entity, c) {
+            (Some(entity), Some(c)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    Cow::Owned(decoded)
+}
+
+/// Formats a duration for humans: `450ms`, `12.3s`, `4m 05s` or `2h 03m`.
+pub(crate) fn duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0 => format!("{}ms", duration.as_millis()),
+        1..60 => format!("{:.1}s", duration.as_secs_f64()),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// Formats a byte count with binary units: `512 B`, `1.5 KiB`, `3.2 MiB`.
+pub(crate) fn bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
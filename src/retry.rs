@@ -0,0 +1,305 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Delay before the first retry.
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Upper bound of the delay between retries.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(15 * 60);
+
+/// How the delay grows between attempts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Backoff {
+    /// Always waits the initial delay.
+    Fixed,
+    /// Doubles the delay with every failed attempt.
+    #[default]
+    Exponential,
+}
+
+/// Randomization applied to delays so clients failing together do not retry together.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Jitter {
+    #[default]
+    None,
+    /// A random delay between zero and the computed delay.
+    Full,
+    /// Half the computed delay plus a random part of the other half.
+    Equal,
+}
+
+impl Jitter {
+    /// Returns true for `Jitter::None`.
+    pub fn is_none(&self) -> bool {
+        matches!(self, Jitter::None)
+    }
+}
+
+/// How a sender retries failed work, shared by the queue consumer, the
+/// webhook sender and the FTP jobs.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use shared_types::retry::{Jitter, RetryPolicy};
+///
+/// let policy = RetryPolicy::exponential(5, Duration::from_secs(2), Duration::from_secs(10));
+/// assert_eq!(policy.delay_for_attempt(1), Duration::from_secs(2));
+/// assert_eq!(policy.delay_for_attempt(3), Duration::from_secs(8));
+/// assert_eq!(policy.delay_for_attempt(4), Duration::from_secs(10));
+/// assert!(policy.allows_retry(4));
+/// assert!(!policy.allows_retry(5));
+///
+/// let jittered = policy.with_jitter(Jitter::Full);
+/// assert!(jittered.delay_for_attempt(3) <= Duration::from_secs(8));
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let json = serde_json::to_value(RetryPolicy::fixed(3, Duration::from_secs(30))).unwrap();
+/// assert_eq!(json, serde_json::json!({
+///     "maxAttempts": 3, "backoff": "fixed", "initialDelayMs": 30000, "maxDelayMs": 30000
+/// }));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, the first one included.
+    #[cfg_attr(feature = "serde", serde(alias = "max_attempts"))]
+    pub max_attempts: u32,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backoff: Backoff,
+    /// Delay before the first retry.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+    #[cfg_attr(feature = "serde", serde(rename = "initialDelayMs", with = "crate::duration_ms", alias = "initial_delay_ms"))]
+    pub initial_delay: Duration,
+    /// Upper bound of the delay between attempts.
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+    #[cfg_attr(feature = "serde", serde(rename = "maxDelayMs", with = "crate::duration_ms", alias = "max_delay_ms"))]
+    pub max_delay: Duration,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Jitter::is_none"))]
+    pub jitter: Jitter,
+}
+
+impl RetryPolicy {
+    /// Retries after the same `delay` every time.
+    pub fn fixed(max_attempts: u32, delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            backoff: Backoff::Fixed,
+            initial_delay: delay,
+            max_delay: delay,
+            jitter: Jitter::None,
+        }
+    }
+
+    /// Doubles the delay from `initial_delay` with every failed attempt, up to `max_delay`.
+    pub fn exponential(max_attempts: u32, initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            backoff: Backoff::Exponential,
+            initial_delay,
+            max_delay,
+            jitter: Jitter::None,
+        }
+    }
+
+    /// Sets the jitter applied to every computed delay.
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Returns true when another attempt is allowed after `attempts` attempts.
+    pub fn allows_retry(&self, attempts: u32) -> bool {
+        attempts < self.max_attempts
+    }
+
+    /// Returns the delay before retry number `attempt`, counting from 1, with
+    /// the jitter applied.
+    ///
+    /// Jitter draws from `fastrand`'s thread-local wyrand generator, seeded
+    /// once per thread from the time and the thread id, so successive delays
+    /// are independent draws; it spreads retries out and is not meant to be
+    /// unpredictable.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let delay = self.base_delay(attempt);
+        match self.jitter {
+            Jitter::None => delay,
+            Jitter::Full => delay.mul_f64(fastrand::f64()),
+            Jitter::Equal => delay / 2 + (delay / 2).mul_f64(fastrand::f64()),
+        }
+    }
+
+    /// Returns the delay before retry number `attempt` without jitter, i.e. its upper bound.
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let delay = match self.backoff {
+            Backoff::Fixed => self.initial_delay,
+            Backoff::Exponential => {
+                let exponent = attempt.saturating_sub(1).min(31);
+                self.initial_delay.saturating_mul(1 << exponent)
+            }
+        };
+        delay.min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts with the queue's default backoff.
+    fn default() -> Self {
+        Self::exponential(3, DEFAULT_BASE_DELAY, DEFAULT_MAX_DELAY)
+    }
+}
+
+/// Retry state of a queued message, shared by the consumer and the DLQ handler.
+///
+/// `attempt` counts the delivery attempts already made, so a message that has
+/// never been processed has `attempt == 0`.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use chrono::Utc;
+/// use shared_types::retry::RetryInfo;
+///
+/// let mut retry = RetryInfo::new(3);
+/// retry.record_failure("timeout", Utc::now());
+/// assert!(retry.should_retry());
+/// assert_eq!(retry.backoff_delay(), Duration::from_secs(1));
+/// retry.record_failure("timeout", Utc::now());
+/// assert_eq!(retry.backoff_delay(), Duration::from_secs(2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct RetryInfo {
+    /// Attempts made so far.
+    pub attempt: u32,
+    /// Maximum number of attempts before the message is dead-lettered.
+    #[cfg_attr(feature = "serde", serde(alias = "max_attempts"))]
+    pub max_attempts: u32,
+    /// Error of the last failed attempt.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "last_error"))]
+    pub last_error: Option<String>,
+    /// When the next attempt should happen.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "next_retry_at"))]
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(
+        feature = "borsh",
+        borsh(
+            serialize_with = "crate::borsh_with::option_timestamp::serialize",
+            deserialize_with = "crate::borsh_with::option_timestamp::deserialize"
+        )
+    )]
+    pub next_retry_at: Option<DateTime<Utc>>,
+}
+
+impl RetryInfo {
+    /// Creates retry state for a message allowing `max_attempts` attempts.
+    pub const fn new(max_attempts: u32) -> Self {
+        Self {
+            attempt: 0,
+            max_attempts,
+            last_error: None,
+            next_retry_at: None,
+        }
+    }
+
+    /// Returns true while attempts remain.
+    pub fn should_retry(&self) -> bool {
+        self.attempt < self.max_attempts
+    }
+
+    /// Returns the delay before the next attempt with the default backoff.
+    ///
+    /// The delay doubles with every failed attempt, starting from
+    /// [`DEFAULT_BASE_DELAY`] and capped at [`DEFAULT_MAX_DELAY`]; see
+    /// [`backoff_delay_with`](Self::backoff_delay_with) for a configured policy.
+    pub fn backoff_delay(&self) -> Duration {
+        self.backoff_delay_with(&self.default_policy())
+    }
+
+    /// Returns the delay `policy` waits before the next attempt, jitter included.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use chrono::Utc;
+    /// use shared_types::retry::{RetryInfo, RetryPolicy};
+    ///
+    /// let policy = RetryPolicy::fixed(5, Duration::from_secs(30));
+    /// let mut retry = RetryInfo::new(policy.max_attempts);
+    /// let now = Utc::now();
+    /// retry.record_failure_with(&policy, "timeout", now);
+    /// retry.record_failure_with(&policy, "timeout", now);
+    /// assert_eq!(retry.backoff_delay_with(&policy), Duration::from_secs(30));
+    /// assert_eq!(retry.next_retry_at, Some(now + chrono::Duration::seconds(30)));
+    /// ```
+    pub fn backoff_delay_with(&self, policy: &RetryPolicy) -> Duration {
+        policy.delay_for_attempt(self.attempt)
+    }
+
+    /// Records a failed attempt at `now`, scheduling the next retry with the
+    /// default backoff if any remains.
+    pub fn record_failure(&mut self, error: impl Into<String>, now: DateTime<Utc>) {
+        self.record_failure_with(&self.default_policy(), error, now);
+    }
+
+    /// Records a failed attempt at `now`, scheduling the next retry after the
+    /// delay of `policy` if any remains.
+    ///
+    /// The number of attempts stays `max_attempts`, whatever the policy says.
+    pub fn record_failure_with(&mut self, policy: &RetryPolicy, error: impl Into<String>, now: DateTime<Utc>) {
+        self.attempt = self.attempt.saturating_add(1);
+        self.last_error = Some(error.into());
+        self.next_retry_at = if self.should_retry() {
+            chrono::Duration::from_std(self.backoff_delay_with(policy))
+                .ok()
+                .and_then(|delay| now.checked_add_signed(delay))
+        } else {
+            None
+        };
+    }
+
+    fn default_policy(&self) -> RetryPolicy {
+        RetryPolicy::exponential(self.max_attempts, DEFAULT_BASE_DELAY, DEFAULT_MAX_DELAY)
+    }
+
+    /// Returns true when the next attempt is due at `now`.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.should_retry() && self.next_retry_at.is_none_or(|at| at <= now)
+    }
+}
+
+impl Default for RetryInfo {
+    /// No attempts made, allowing as many as the default [`RetryPolicy`].
+    fn default() -> Self {
+        Self::new(RetryPolicy::default().max_attempts)
+    }
+}
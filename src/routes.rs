@@ -0,0 +1,24 @@
+//! Routing keys shared by producers and bot workers.
+//!
+//! Routes are dot separated and hierarchical: a worker subscribed to
+//! [`NOTIFY`] receives everything below it, e.g. [`TELEGRAM`].
+
+/// Root of all notification traffic.
+pub const NOTIFY: &str = "notify";
+
+/// Telegram messages.
+pub const TELEGRAM: &str = "notify.telegram";
+
+/// Returns true when `routing_key` equals `route` or lies below it in the hierarchy.
+///
+/// ```rust
+/// use shared_types::routes;
+///
+/// assert!(routes::matches(routes::TELEGRAM, routes::NOTIFY));
+/// assert!(!routes::matches("notify.telegramx", routes::TELEGRAM));
+/// ```
+pub fn matches(routing_key: &str, route: &str) -> bool {
+    routing_key
+        .strip_prefix(route)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
@@ -0,0 +1,310 @@
+#[cfg(feature = "serde")]
+use serde::de::{self, Deserializer};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde_json::{Map, Value};
+
+use crate::telegram::TelegramQueueMessage;
+
+/// Schema version written by this crate for [`TelegramQueueMessage`] and the
+/// queue envelope.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// Serde default for `schemaVersion`: payloads without the field predate
+/// versioning and are version 1.
+#[cfg(feature = "serde")]
+pub(crate) fn default_schema_version() -> u32 {
+    1
+}
+
+/// A Telegram queue message of any supported schema version.
+///
+/// Deserializing dispatches on the `schemaVersion` field (missing means 1), so
+/// consumers keep accepting older payloads while producers move to newer
+/// versions mid-deploy. Use [`VersionedMessage::into_latest`] to work with the
+/// current type.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use shared_types::schema::VersionedMessage;
+///
+/// let versioned: VersionedMessage =
+///     serde_json::from_str(r#"{"chatId": 1, "message": "hi", "forceSend": false}"#).unwrap();
+/// assert_eq!(versioned.version(), 1);
+/// assert_eq!(versioned.into_latest().message, "hi");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+#[non_exhaustive]
+pub enum VersionedMessage {
+    V1(TelegramQueueMessage),
+}
+
+impl VersionedMessage {
+    /// Returns the schema version of the message.
+    pub fn version(&self) -> u32 {
+        match self {
+            VersionedMessage::V1(_) => 1,
+        }
+    }
+
+    /// Upgrades the message to the current schema.
+    pub fn into_latest(self) -> TelegramQueueMessage {
+        match self {
+            VersionedMessage::V1(message) => message,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for VersionedMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let version = match value.get("schemaVersion").or_else(|| value.get("schema_version")) {
+            Some(version) => version
+                .as_u64()
+                .ok_or_else(|| de::Error::custom("schemaVersion must be an unsigned integer"))?,
+            None => u64::from(default_schema_version()),
+        };
+
+        match version {
+            1 => serde_json::from_value(value)
+                .map(VersionedMessage::V1)
+                .map_err(de::Error::custom),
+            other => Err(de::Error::custom(format!("unsupported schema version {}", other))),
+        }
+    }
+}
+
+/// Legacy snake_case keys and the current key they map to.
+#[cfg(feature = "serde")]
+const LEGACY_KEYS: &[(&str, &str)] = &[
+    ("schema_version", "schemaVersion"),
+    ("chat_id", "chatId"),
+    ("force", "forceSend"),
+    ("force_send", "forceSend"),
+    ("send_policy", "sendPolicy"),
+    ("dedup_key", "dedupKey"),
+];
+
+/// Keys understood by the current [`TelegramQueueMessage`].
+#[cfg(feature = "serde")]
+const KNOWN_KEYS: &[&str] = &[
+    "schemaVersion",
+    "chatId",
+    "message",
+    "forceSend",
+    "sendPolicy",
+    "status",
+    "dedupKey",
+    "metadata",
+];
+
+/// Upgrades a JSON payload from an older producer to a [`TelegramQueueMessage`].
+///
+/// Renames snake_case keys (`chat_id`, `force`, ...), accepts chat ids sent as
+/// strings and `force` sent as `0`/`1` or a string, and treats a missing
+/// `force` as `false`. The migration is lossless: keys the current schema does
+/// not know are kept in `metadata` instead of being dropped.
+///
+/// ```rust
+/// use shared_types::schema::migrate_legacy;
+///
+/// let legacy = serde_json::json!({"chat_id": "-100", "message": "hi", "force": 1, "host": "ftp01"});
+/// let message = migrate_legacy(legacy).unwrap();
+/// assert_eq!(message.chat_id, -100);
+/// assert!(message.force_send);
+/// assert_eq!(message.metadata["host"], "ftp01");
+/// ```
+#[cfg(feature = "serde")]
+pub fn migrate_legacy(value: Value) -> Result<TelegramQueueMessage, serde_json::Error> {
+    let Value::Object(mut object) = value else {
+        return Err(de::Error::custom("legacy message must be a JSON object"));
+    };
+
+    for (legacy, current) in LEGACY_KEYS {
+        if let Some(value) = object.remove(*legacy) {
+            object.entry(*current).or_insert(value);
+        }
+    }
+
+    if let Some(Value::String(chat_id)) = object.get("chatId") {
+        let chat_id: i64 = chat_id
+            .trim()
+            .parse()
+            .map_err(|_| de::Error::custom(format!("invalid legacy chat id {:?}", chat_id)))?;
+        object.insert("chatId".to_owned(), Value::from(chat_id));
+    }
+
+    let force_send = match object.get("forceSend") {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(force)) => *force,
+        Some(Value::Number(force)) => force.as_f64() != Some(0.0),
+        Some(Value::String(force)) => matches!(
+            force.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes"
+        ),
+        Some(other) => {
+            return Err(de::Error::custom(format!("invalid legacy force flag {}", other)));
+        }
+    };
+    object.insert("forceSend".to_owned(), Value::Bool(force_send));
+
+    let unknown: Vec<String> = object
+        .keys()
+        .filter(|key| !KNOWN_KEYS.contains(&key.as_str()))
+        .cloned()
+        .collect();
+    let preserved: Vec<(String, Value)> = unknown
+        .into_iter()
+        .filter_map(|key| object.remove(&key).map(|value| (key, value)))
+        .collect();
+    if !preserved.is_empty() {
+        let metadata = object
+            .entry("metadata")
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Value::Object(metadata) = metadata else {
+            return Err(de::Error::custom("legacy metadata must be a JSON object"));
+        };
+        for (key, value) in preserved {
+            let value = match value {
+                Value::String(value) => value,
+                other => other.to_string(),
+            };
+            metadata.entry(key).or_insert(Value::String(value));
+        }
+    }
+
+    VersionedMessage::deserialize(Value::Object(object)).map(VersionedMessage::into_latest)
+}
+
+/// Returns the JSON Schema of every queue payload and report type, keyed by
+/// type name, for non-Rust producers to validate their payloads against.
+///
+/// ```rust
+/// let schemas = shared_types::schema::schemas();
+/// let telegram = serde_json::to_value(&schemas["TelegramQueueMessage"]).unwrap();
+/// assert!(telegram["required"].as_array().unwrap().contains(&"chatId".into()));
+/// assert!(schemas.contains_key("QueueEnvelope"));
+/// ```
+#[cfg(feature = "schemars")]
+pub fn schemas() -> std::collections::BTreeMap<&'static str, schemars::Schema> {
+    use crate::{
+        alert, audit, backup, cert, consumer, deploy, disk, document, email, envelope, error_report, ftp,
+        heartbeat, job, logging, maintenance, metric, notification, order, pagerduty, payload, preferences,
+        quota, rate_limit, sla, status, sync, telegram, transfer,
+    };
+
+    macro_rules! schemas {
+        ($($name:literal => $type:ty),* $(,)?) => {
+            std::collections::BTreeMap::from([$(($name, schemars::schema_for!($type))),*])
+        };
+    }
+
+    schemas!(
+        "QueueEnvelope" => envelope::QueueEnvelope<payload::QueueMessageKind>,
+        "QueueMessageKind" => payload::QueueMessageKind,
+        "Notification" => notification::Notification,
+        "VersionedMessage" => VersionedMessage,
+        "TelegramQueueMessage" => TelegramQueueMessage,
+        "TelegramBroadcastMessage" => telegram::TelegramBroadcastMessage,
+        "EmailQueueMessage" => email::EmailQueueMessage,
+        "SlackQueueMessage" => crate::SlackQueueMessage,
+        "DiscordQueueMessage" => crate::DiscordQueueMessage,
+        "SmsQueueMessage" => crate::SmsQueueMessage,
+        "WebhookQueueMessage" => crate::WebhookQueueMessage,
+        "TeamsQueueMessage" => crate::TeamsQueueMessage,
+        "PushQueueMessage" => crate::PushQueueMessage,
+        "PagerDutyEvent" => pagerduty::PagerDutyEvent,
+        "MatrixQueueMessage" => crate::MatrixQueueMessage,
+        "GotifyQueueMessage" => crate::GotifyQueueMessage,
+        "ConsumerResult" => consumer::ConsumerResult,
+        "Heartbeat" => heartbeat::Heartbeat,
+        "StaleHeartbeat" => heartbeat::StaleHeartbeat,
+        "MetricBatch" => metric::MetricBatch,
+        "LogEvent" => logging::LogEvent,
+        "AuditEvent" => audit::AuditEvent,
+        "JobRunReport" => job::JobRunReport,
+        "BackupReport" => backup::BackupReport,
+        "FtpTransferReport" => ftp::FtpTransferReport,
+        "FileTransferEvent" => transfer::FileTransferEvent,
+        "FileTransferDigest" => transfer::FileTransferDigest,
+        "SyncResult" => sync::SyncResult,
+        "DeploymentEvent" => deploy::DeploymentEvent,
+        "StatusBoard" => status::StatusBoard,
+        "DiskUsageReport" => disk::DiskUsageReport,
+        "CertExpiryAlert" => cert::CertExpiryAlert,
+        "DocumentEvent" => document::DocumentEvent,
+        "OrderEvent" => order::OrderEvent,
+        "AlertRule" => alert::AlertRule,
+        "FiringAlert" => alert::FiringAlert,
+        "SlaReport" => sla::SlaReport,
+        "ErrorReport" => error_report::ErrorReport,
+        "QuotaUsage" => quota::QuotaUsage,
+        "RateLimitState" => rate_limit::RateLimitState,
+        "ThrottleHint" => rate_limit::ThrottleHint,
+        "NotificationPreferences" => preferences::NotificationPreferences,
+        "MaintenanceWindow" => maintenance::MaintenanceWindow,
+    )
+}
+
+/// Returns the TypeScript declarations of every queue type and of the types
+/// they reference, as the contents of a `.d.ts` file.
+///
+/// The `export-typescript` binary writes them to a file:
+/// `cargo run --features ts-rs --bin export-typescript -- shared_types.d.ts`.
+///
+/// ```rust
+/// let declarations = shared_types::schema::typescript_declarations();
+/// assert!(declarations.contains("export type TelegramQueueMessage = {"));
+/// assert!(declarations.contains("chatId: number"));
+/// assert!(declarations.contains("export type QueueEnvelope<T> = {"));
+/// ```
+#[cfg(feature = "ts-rs")]
+pub fn typescript_declarations() -> String {
+    use std::collections::BTreeSet;
+
+    use ts_rs::{TS, TypeVisitor};
+
+    use crate::{consumer, document, envelope, heartbeat, notification, payload};
+
+    /// Appends the declaration of each visited type once, then of its dependencies.
+    #[derive(Default)]
+    struct Declarations {
+        seen: BTreeSet<String>,
+        output: String,
+    }
+
+    impl TypeVisitor for Declarations {
+        fn visit<T: TS + 'static + ?Sized>(&mut self) {
+            if T::output_path().is_none() || !self.seen.insert(T::ident()) {
+                return;
+            }
+            if let Some(docs) = T::docs() {
+                self.output.push_str(&docs);
+            }
+            self.output.push_str("export ");
+            self.output.push_str(&T::decl());
+            self.output.push_str("\n\n");
+            T::visit_dependencies(self);
+            T::visit_generics(self);
+        }
+    }
+
+    let mut declarations = Declarations::default();
+    declarations.visit::<envelope::QueueEnvelope<payload::QueueMessageKind>>();
+    declarations.visit::<VersionedMessage>();
+    declarations.visit::<notification::Notification>();
+    declarations.visit::<consumer::ConsumerResult>();
+    declarations.visit::<heartbeat::Heartbeat>();
+    declarations.visit::<document::DocumentEvent>();
+    declarations.output
+}
@@ -0,0 +1,134 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A payload signed with HMAC-SHA256 by a trusted producer.
+///
+/// The payload is kept as the exact JSON that was signed, so verification does
+/// not depend on re-serializing it identically. `signature` is the lowercase hex
+/// HMAC-SHA256 of `payload`. Signing and verifying require the `signing`
+/// feature.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SignedMessage<T> {
+    /// JSON serialization of the signed value.
+    pub payload: String,
+    /// Hex encoded HMAC-SHA256 of `payload`.
+    pub signature: String,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for SignedMessage<T> {
+    fn clone(&self) -> Self {
+        Self {
+            payload: self.payload.clone(),
+            signature: self.signature.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "signing")]
+impl<T: Serialize + serde::de::DeserializeOwned> SignedMessage<T> {
+    /// Serializes `value` to JSON and signs it with `key`.
+    ///
+    /// ```rust
+    /// use shared_types::signing::SignedMessage;
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let key = b"shared secret";
+    /// let msg = TelegramQueueMessage::new(123, "deploy done".into(), false);
+    /// let signed = SignedMessage::sign(&msg, key).unwrap();
+    ///
+    /// assert_eq!(signed.verify(key).unwrap().message, "deploy done");
+    /// assert!(signed.verify(b"wrong key").is_err());
+    /// ```
+    pub fn sign(value: &T, key: &[u8]) -> Result<Self, serde_json::Error> {
+        use hmac::Mac;
+
+        let payload = serde_json::to_string(value)?;
+        let signature = to_hex(&hmac_sha256(key, payload.as_bytes()).finalize().into_bytes());
+        Ok(Self {
+            payload,
+            signature,
+            marker: PhantomData,
+        })
+    }
+
+    /// Checks the signature with `key` and deserializes the payload.
+    ///
+    /// The comparison runs in constant time.
+    pub fn verify(&self, key: &[u8]) -> Result<T, SignatureError> {
+        use hmac::Mac;
+
+        let signature = from_hex(&self.signature).ok_or(SignatureError::InvalidSignature)?;
+        hmac_sha256(key, self.payload.as_bytes())
+            .verify_slice(&signature)
+            .map_err(|_| SignatureError::InvalidSignature)?;
+
+        serde_json::from_str(&self.payload).map_err(SignatureError::Payload)
+    }
+}
+
+#[cfg(feature = "signing")]
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> hmac::Hmac<sha2::Sha256> {
+    use hmac::Mac;
+
+    let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac
+}
+
+#[cfg(feature = "signing")]
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(feature = "signing")]
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+        .collect()
+}
+
+/// Error returned by [`SignedMessage::verify`].
+///
+/// Non-exhaustive, as `Payload` only exists with the `serde` feature.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SignatureError {
+    /// The signature is malformed or does not match the payload.
+    InvalidSignature,
+    /// The signature matched but the payload could not be deserialized.
+    #[cfg(feature = "serde")]
+    Payload(serde_json::Error),
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::InvalidSignature => f.write_str("invalid message signature"),
+            #[cfg(feature = "serde")]
+            SignatureError::Payload(err) => write!(f, "invalid signed payload: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SignatureError::InvalidSignature => None,
+            #[cfg(feature = "serde")]
+            SignatureError::Payload(err) => Some(err),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// An incident that exceeded the resolution time promised by the SLA.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SlaBreach {
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "started_at"))]
+    pub started_at: DateTime<Utc>,
+    #[cfg_attr(feature = "schemars", schemars(with = "u64"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = u64))]
+    #[cfg_attr(feature = "serde", serde(rename = "durationMs", with = "crate::duration_ms", alias = "duration_ms"))]
+    pub duration: Duration,
+    pub description: String,
+}
+
+/// Availability of a service over a period, usually a calendar month, produced
+/// by the reporting job and kept by the archive service.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use chrono::{TimeZone, Utc};
+/// use shared_types::MessageStatus;
+/// use shared_types::sla::SlaReport;
+///
+/// let start = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+/// let end = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+/// let report = SlaReport::new("api", start, end, 99.9, 99.95)
+///     .with_incidents(2, Duration::from_secs(14 * 60))
+///     .with_breach(start + chrono::Duration::days(2), Duration::from_secs(3720), "db failover");
+/// assert_eq!(report.severity(), MessageStatus::Warn);
+/// assert_eq!(
+///     report.to_telegram_message(123).message,
+///     "⚠️ - <i>sla</i>\napi: 99.95% uptime in May 2024, target 99.90%\n\
+///      2 incidents, MTTR 14m 00s, 1 breach\n<pre>2024-05-03 00:00  1h 02m  db failover</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SlaReport {
+    pub service: String,
+    /// Start of the period (inclusive).
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "period_start"))]
+    pub period_start: DateTime<Utc>,
+    /// End of the period (exclusive).
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "period_end"))]
+    pub period_end: DateTime<Utc>,
+    /// Promised uptime in percent, e.g. `99.9`.
+    #[cfg_attr(feature = "serde", serde(alias = "target_percent"))]
+    pub target_percent: f64,
+    /// Measured uptime in percent, from 0 to 100.
+    #[cfg_attr(feature = "serde", serde(alias = "uptime_percent"))]
+    pub uptime_percent: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub incidents: u32,
+    /// Mean time to recovery over the incidents of the period.
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u64>"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<u64>))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "mttrMs",
+            with = "crate::duration_ms::option",
+            skip_serializing_if = "Option::is_none",
+            alias = "mttr_ms"
+        )
+    )]
+    pub mttr: Option<Duration>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub breaches: Vec<SlaBreach>,
+}
+
+impl SlaReport {
+    pub fn new(
+        service: impl Into<String>,
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+        target_percent: f64,
+        uptime_percent: f64,
+    ) -> Self {
+        Self {
+            service: service.into(),
+            period_start,
+            period_end,
+            target_percent,
+            uptime_percent,
+            incidents: 0,
+            mttr: None,
+            breaches: Vec::new(),
+        }
+    }
+
+    /// Sets the number of incidents and their mean time to recovery.
+    pub fn with_incidents(mut self, incidents: u32, mttr: Duration) -> Self {
+        self.incidents = incidents;
+        self.mttr = Some(mttr);
+        self
+    }
+
+    pub fn with_breach(
+        mut self,
+        started_at: DateTime<Utc>,
+        duration: Duration,
+        description: impl Into<String>,
+    ) -> Self {
+        self.breaches.push(SlaBreach {
+            started_at,
+            duration,
+            description: description.into(),
+        });
+        self
+    }
+
+    /// Returns true when the uptime reached the target.
+    pub fn is_met(&self) -> bool {
+        self.uptime_percent >= self.target_percent
+    }
+
+    /// `Error` when the target was missed, `Warn` with breaches, `Ok` otherwise.
+    pub fn severity(&self) -> MessageStatus {
+        if !self.is_met() {
+            MessageStatus::Error
+        } else if !self.breaches.is_empty() {
+            MessageStatus::Warn
+        } else {
+            MessageStatus::Ok
+        }
+    }
+
+    /// Formats the monthly digest for the `sla` job, the breaches listed in a
+    /// `<pre>` block.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let summary = format!(
+            "{}: {:.2}% uptime in {}, target {:.2}%",
+            render::escape_text(&self.service),
+            self.uptime_percent,
+            self.period_start.format("%B %Y"),
+            self.target_percent
+        );
+        let mut content = match self.incidents {
+            0 => "no incidents".to_string(),
+            1 => "1 incident".to_string(),
+            incidents => format!("{} incidents", incidents),
+        };
+        if let Some(mttr) = self.mttr.filter(|_| self.incidents > 0) {
+            content.push_str(&format!(", MTTR {}", render::duration(mttr)));
+        }
+        match self.breaches.len() {
+            0 => {}
+            1 => content.push_str(", 1 breach"),
+            breaches => content.push_str(&format!(", {} breaches", breaches)),
+        }
+        let breaches = self
+            .breaches
+            .iter()
+            .map(|breach| {
+                format!(
+                    "{}  {}  {}",
+                    breach.started_at.format("%Y-%m-%d %H:%M"),
+                    render::duration(breach.duration),
+                    breach.description
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("sla")
+            .summary(summary)
+            .content(content)
+            .code_block(breaches)
+            .build()
+    }
+}
@@ -0,0 +1,364 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::job::JobName;
+use crate::render::{headline, truncate};
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// Maximum length of the text of a section or context element.
+pub const SLACK_MAX_TEXT_LENGTH: usize = 3000;
+
+/// Maximum number of fields in a section block.
+pub const SLACK_MAX_SECTION_FIELDS: usize = 10;
+
+/// A text object of Block Kit, serialized in Slack's own format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum SlackText {
+    /// Text using Slack's `mrkdwn` markup.
+    Mrkdwn { text: String },
+    PlainText { text: String },
+}
+
+impl SlackText {
+    /// Creates a `mrkdwn` text; escape literal text with [`escape_mrkdwn`].
+    pub fn mrkdwn(text: impl Into<String>) -> Self {
+        SlackText::Mrkdwn { text: text.into() }
+    }
+
+    /// Creates a `plain_text` text, shown as is.
+    pub fn plain(text: impl Into<String>) -> Self {
+        SlackText::PlainText { text: text.into() }
+    }
+
+    /// Returns the raw text.
+    pub fn text(&self) -> &str {
+        match self {
+            SlackText::Mrkdwn { text } | SlackText::PlainText { text } => text,
+        }
+    }
+}
+
+/// The subset of Block Kit blocks used by our alerts, serialized in Slack's own
+/// format so the worker can pass `blocks` to `chat.postMessage` unchanged.
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use shared_types::slack::{SlackBlock, SlackText};
+///
+/// let block = SlackBlock::Context { elements: vec![SlackText::mrkdwn("ftp01")] };
+/// assert_eq!(
+///     serde_json::to_string(&block).unwrap(),
+///     r#"{"type":"context","elements":[{"type":"mrkdwn","text":"ftp01"}]}"#
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum SlackBlock {
+    /// A text block, optionally with up to [`SLACK_MAX_SECTION_FIELDS`] two-column fields.
+    Section {
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+        text: Option<SlackText>,
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+        fields: Vec<SlackText>,
+    },
+    /// Small secondary text.
+    Context { elements: Vec<SlackText> },
+    Divider,
+}
+
+impl SlackBlock {
+    /// A section with `mrkdwn` text, truncated to [`SLACK_MAX_TEXT_LENGTH`].
+    pub fn section(text: impl Into<String>) -> Self {
+        SlackBlock::Section {
+            text: Some(SlackText::mrkdwn(truncate(&text.into(), SLACK_MAX_TEXT_LENGTH))),
+            fields: Vec::new(),
+        }
+    }
+
+    /// A context block with a single `mrkdwn` element, truncated to [`SLACK_MAX_TEXT_LENGTH`].
+    pub fn context(text: impl Into<String>) -> Self {
+        SlackBlock::Context {
+            elements: vec![SlackText::mrkdwn(truncate(&text.into(), SLACK_MAX_TEXT_LENGTH))],
+        }
+    }
+}
+
+/// Represents a message received from the queue to be posted to Slack.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SlackQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    /// Channel id or name.
+    pub channel: String,
+    /// Fallback shown in notifications and by clients that cannot render blocks.
+    pub text: String,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub blocks: Vec<SlackBlock>,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+}
+
+impl SlackQueueMessage {
+    /// Creates a plain text message without blocks.
+    pub fn new(channel: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            channel: channel.into(),
+            text: text.into(),
+            blocks: Vec::new(),
+            status: MessageStatus::None,
+            dedup_key: None,
+        }
+    }
+
+    /// Returns a builder for a formatted message.
+    pub fn builder(channel: impl Into<String>) -> SlackMessageBuilder {
+        SlackMessageBuilder::new(channel)
+    }
+}
+
+/// A builder for creating formatted SlackQueueMessage objects.
+///
+/// Mirrors [`crate::TelegramMessageBuilder`] with native Block Kit formatting:
+/// a header section, the content with code blocks, the fields, any extra
+/// blocks and the details, in that order.
+///
+/// ```rust
+/// use shared_types::{MessageStatus, SlackQueueMessage};
+/// use shared_types::slack::SlackBlock;
+///
+/// let msg = SlackQueueMessage::builder("#ops")
+///     .status(MessageStatus::Ok)
+///     .job_name("backup")
+///     .summary("snapshot done")
+///     .field("Size", "12 GB")
+///     .field("Duration", "4m")
+///     .divider()
+///     .context("host ftp01")
+///     .build();
+/// assert_eq!(msg.text, "✅ backup: snapshot done");
+/// assert_eq!(msg.blocks[0], SlackBlock::section("✅ *backup*\nsnapshot done"));
+/// assert_eq!(msg.blocks.len(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlackMessageBuilder {
+    channel: String,
+    status: MessageStatus,
+    emoji: Option<String>,
+    job_name: JobName,
+    summary: String,
+    content: String,
+    code_blocks: Vec<String>,
+    fields: Vec<(String, String)>,
+    blocks: Vec<SlackBlock>,
+    details: String,
+    dedup_key: Option<String>,
+}
+
+impl SlackMessageBuilder {
+    /// Initializes a new builder with the required channel.
+    pub fn new(channel: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            status: MessageStatus::None,
+            emoji: None,
+            job_name: JobName::default(),
+            summary: String::new(),
+            content: String::new(),
+            code_blocks: Vec::new(),
+            fields: Vec::new(),
+            blocks: Vec::new(),
+            details: String::new(),
+            dedup_key: None,
+        }
+    }
+
+    /// Sets the status level, which adds an emoji prefix.
+    pub fn status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Overrides the status-derived emoji prefix.
+    pub fn emoji(mut self, emoji: impl Into<String>) -> Self {
+        self.emoji = Some(emoji.into());
+        self
+    }
+
+    /// Sets the job name, shown in bold in the header.
+    pub fn job_name(mut self, job_name: impl Into<JobName>) -> Self {
+        self.job_name = job_name.into();
+        self
+    }
+
+    /// Sets a summary line, shown below the job name and used in the fallback text.
+    /// The summary is plain text and escaped.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = summary.into();
+        self
+    }
+
+    /// Sets the message content. Content is `mrkdwn` and used verbatim.
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    /// Appends a code block after the content.
+    pub fn code_block(mut self, snippet: impl Into<String>) -> Self {
+        self.code_blocks.push(snippet.into());
+        self
+    }
+
+    /// Adds a `*label*` / value field; the label and value are escaped.
+    pub fn field(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push((label.into(), value.into()));
+        self
+    }
+
+    /// Appends a block after the fields.
+    pub fn block(mut self, block: SlackBlock) -> Self {
+        self.blocks.push(block);
+        self
+    }
+
+    /// Appends a divider after the fields.
+    pub fn divider(self) -> Self {
+        self.block(SlackBlock::Divider)
+    }
+
+    /// Appends a context block after the fields. The text is `mrkdwn`.
+    pub fn context(self, text: impl Into<String>) -> Self {
+        self.block(SlackBlock::context(text))
+    }
+
+    /// Sets details (e.g. a log tail), rendered last in a code block.
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = details.into();
+        self
+    }
+
+    /// Sets an explicit dedup key for the message.
+    pub fn dedup_key(mut self, dedup_key: impl Into<String>) -> Self {
+        self.dedup_key = Some(dedup_key.into());
+        self
+    }
+
+    /// Builds the SlackQueueMessage.
+    ///
+    /// The job name and summary are escaped in the header and in the `text`
+    /// fallback alike:
+    ///
+    /// ```rust
+    /// use shared_types::SlackQueueMessage;
+    ///
+    /// let msg = SlackQueueMessage::builder("#ops").job_name("ftp").summary("disk <full> & slow").build();
+    /// assert_eq!(msg.text, "ftp: disk &lt;full&gt; &amp; slow");
+    /// ```
+    pub fn build(self) -> SlackQueueMessage {
+        let emoji = match &self.emoji {
+            Some(emoji) => emoji.as_str(),
+            None => self.status.emoji(),
+        };
+        let text = headline(emoji, &escape_mrkdwn(self.job_name.as_str()), &escape_mrkdwn(&self.summary));
+
+        let mut blocks = Vec::new();
+        let mut header = Vec::new();
+        if !emoji.is_empty() {
+            header.push(emoji.to_string());
+        }
+        if !self.job_name.as_str().is_empty() {
+            header.push(format!("*{}*", escape_mrkdwn(self.job_name.as_str())));
+        }
+        let mut header = header.join(" ");
+        if !self.summary.is_empty() {
+            if !header.is_empty() {
+                header.push('\n');
+            }
+            header.push_str(&escape_mrkdwn(&self.summary));
+        }
+        if !header.is_empty() {
+            blocks.push(SlackBlock::section(header));
+        }
+
+        let mut body = vec![self.content];
+        body.extend(self.code_blocks.iter().map(|snippet| code_block(snippet)));
+        let body = body
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !body.is_empty() {
+            blocks.push(SlackBlock::section(body));
+        }
+
+        for fields in self.fields.chunks(SLACK_MAX_SECTION_FIELDS) {
+            blocks.push(SlackBlock::Section {
+                text: None,
+                fields: fields
+                    .iter()
+                    .map(|(label, value)| {
+                        let field = format!("*{}*\n{}", escape_mrkdwn(label), escape_mrkdwn(value));
+                        SlackText::mrkdwn(truncate(&field, SLACK_MAX_TEXT_LENGTH))
+                    })
+                    .collect(),
+            });
+        }
+        blocks.extend(self.blocks);
+        if !self.details.is_empty() {
+            blocks.push(SlackBlock::section(code_block(&self.details)));
+        }
+
+        SlackQueueMessage {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            channel: self.channel,
+            text,
+            blocks,
+            status: self.status,
+            dedup_key: self.dedup_key,
+        }
+    }
+}
+
+/// Escapes the characters Slack treats as control sequences in `mrkdwn`: `&`, `<` and `>`.
+///
+/// ```rust
+/// use shared_types::slack::escape_mrkdwn;
+///
+/// assert_eq!(escape_mrkdwn("a <b> & c"), "a &lt;b&gt; &amp; c");
+/// ```
+pub fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Wraps `snippet` in a code block, escaping it and keeping the fences intact
+/// when the section is truncated.
+fn code_block(snippet: &str) -> String {
+    let snippet = truncate(&escape_mrkdwn(snippet), SLACK_MAX_TEXT_LENGTH - 8);
+    format!("```\n{}\n```", snippet)
+}
@@ -0,0 +1,239 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// Characters of the GSM 03.38 basic character set, one septet each.
+const GSM7_BASIC: &str = "@£$¥èéùìòÇ\nØø\rÅåΔ_ΦΓΛΩΠΨΣΘΞÆæßÉ !\"#¤%&'()*+,-./0123456789:;<=>?\
+¡ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÑÜ§¿abcdefghijklmnopqrstuvwxyzäöñüà";
+
+/// Characters of the GSM 03.38 extension table, two septets each (escape + char).
+const GSM7_EXTENDED: &str = "\u{c}^{}\\[~]|€";
+
+/// A phone number in E.164 format, e.g. `+393331234567`.
+///
+/// Parsing accepts common separators (spaces, dashes, dots, parentheses) and a
+/// leading `00` instead of `+`, and stores the normalized form.
+///
+/// ```rust
+/// use shared_types::sms::PhoneNumber;
+///
+/// let number = PhoneNumber::parse("+39 333 123-4567").unwrap();
+/// assert_eq!(number.as_str(), "+393331234567");
+/// assert!(PhoneNumber::parse("333 1234567").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    /// Parses and normalizes an E.164 number: `+`, a non-zero country code digit
+    /// and 7 to 15 digits in total.
+    pub fn parse(number: &str) -> Result<Self, PhoneNumberError> {
+        let compact: String = number
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+            .collect();
+        let digits = compact
+            .strip_prefix('+')
+            .or_else(|| compact.strip_prefix("00"))
+            .ok_or_else(|| PhoneNumberError(number.to_string()))?;
+        let valid = (7..=15).contains(&digits.len())
+            && digits.chars().all(|c| c.is_ascii_digit())
+            && !digits.starts_with('0');
+        if !valid {
+            return Err(PhoneNumberError(number.to_string()));
+        }
+        Ok(PhoneNumber(format!("+{}", digits)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for PhoneNumber {
+    type Error = PhoneNumberError;
+
+    fn try_from(number: String) -> Result<Self, Self::Error> {
+        PhoneNumber::parse(&number)
+    }
+}
+
+impl From<PhoneNumber> for String {
+    fn from(number: PhoneNumber) -> Self {
+        number.0
+    }
+}
+
+/// Error returned when a phone number is not a valid E.164 number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoneNumberError(pub String);
+
+impl fmt::Display for PhoneNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid E.164 phone number {:?}", self.0)
+    }
+}
+
+impl std::error::Error for PhoneNumberError {}
+
+/// Encoding an SMS body is sent with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum SmsEncoding {
+    /// GSM 03.38 7-bit alphabet: 160 characters per single SMS.
+    Gsm7,
+    /// UTF-16: 70 code units per single SMS, used as soon as one character is not GSM-7.
+    Ucs2,
+}
+
+impl SmsEncoding {
+    /// Returns the encoding needed for `body`.
+    pub fn detect(body: &str) -> Self {
+        if body.chars().all(|c| GSM7_BASIC.contains(c) || GSM7_EXTENDED.contains(c)) {
+            SmsEncoding::Gsm7
+        } else {
+            SmsEncoding::Ucs2
+        }
+    }
+
+    /// Returns the length of `body` in this encoding's units: septets for GSM-7,
+    /// UTF-16 code units for UCS-2.
+    pub fn length(self, body: &str) -> usize {
+        match self {
+            SmsEncoding::Gsm7 => body
+                .chars()
+                .map(|c| if GSM7_EXTENDED.contains(c) { 2 } else { 1 })
+                .sum(),
+            SmsEncoding::Ucs2 => body.encode_utf16().count(),
+        }
+    }
+
+    /// Units fitting in a single SMS.
+    pub fn single_capacity(self) -> usize {
+        match self {
+            SmsEncoding::Gsm7 => 160,
+            SmsEncoding::Ucs2 => 70,
+        }
+    }
+
+    /// Units fitting in each part of a concatenated SMS, the rest being taken by the UDH.
+    pub fn segment_capacity(self) -> usize {
+        match self {
+            SmsEncoding::Gsm7 => 153,
+            SmsEncoding::Ucs2 => 67,
+        }
+    }
+
+    /// Returns how many SMS parts `body` is billed as.
+    pub fn segments(self, body: &str) -> usize {
+        match self.length(body) {
+            0 => 0,
+            length if length <= self.single_capacity() => 1,
+            length => length.div_ceil(self.segment_capacity()),
+        }
+    }
+}
+
+/// Represents a message received from the queue to be sent by SMS.
+///
+/// ```rust
+/// use shared_types::SmsQueueMessage;
+/// use shared_types::sms::{PhoneNumber, SmsEncoding};
+///
+/// let to = PhoneNumber::parse("+393331234567").unwrap();
+/// let sms = SmsQueueMessage::new(to.clone(), "backup failed on ftp01");
+/// assert_eq!(sms.encoding(), SmsEncoding::Gsm7);
+/// assert_eq!(sms.segments(), 1);
+///
+/// let sms = SmsQueueMessage::new(to, "🚨 ".repeat(40));
+/// assert_eq!(sms.encoding(), SmsEncoding::Ucs2);
+/// assert_eq!(sms.segments(), 2);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SmsQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    pub to: PhoneNumber,
+    pub body: String,
+    /// Alphanumeric sender id (up to 11 characters) shown instead of a number,
+    /// when the destination country allows it.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "sender_id"))]
+    pub sender_id: Option<String>,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+}
+
+impl SmsQueueMessage {
+    /// Creates a message to `to`, sent with the provider's default sender.
+    pub fn new(to: PhoneNumber, body: impl Into<String>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            to,
+            body: body.into(),
+            sender_id: None,
+            status: MessageStatus::None,
+            dedup_key: None,
+        }
+    }
+
+    /// Sets the alphanumeric sender id, keeping only ASCII letters, digits and
+    /// spaces and truncating it to 11 characters.
+    pub fn with_sender_id(mut self, sender_id: impl Into<String>) -> Self {
+        let sender_id: String = sender_id
+            .into()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == ' ')
+            .take(11)
+            .collect();
+        self.sender_id = Some(sender_id.trim().to_string()).filter(|id| !id.is_empty());
+        self
+    }
+
+    /// Sets the status level.
+    pub fn with_status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Returns the encoding the body is sent with.
+    pub fn encoding(&self) -> SmsEncoding {
+        SmsEncoding::detect(&self.body)
+    }
+
+    /// Returns how many SMS parts the body is billed as.
+    pub fn segments(&self) -> usize {
+        self.encoding().segments(&self.body)
+    }
+}
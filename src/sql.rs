@@ -0,0 +1,221 @@
+//! Postgres row mapping of [`TelegramQueueMessage`] and [`QueueEnvelope`] for
+//! outbox tables, with the column names as constants.
+//!
+//! Scalars map to their natural column types; [`MessageStatus`] is stored as
+//! its lowercase name and [`Priority`] as a `smallint` from 0 (`Low`) to 3
+//! (`Critical`), both usable in `bind` through their sqlx impls. Nested types,
+//! the metadata and the envelope payload are `jsonb` columns, and
+//! `deliver_after` is stored in milliseconds.
+//!
+//! ```sql
+//! CREATE TABLE telegram_outbox (
+//!     schema_version integer NOT NULL DEFAULT 1,
+//!     chat_id bigint NOT NULL,
+//!     message text NOT NULL,
+//!     force_send boolean NOT NULL DEFAULT false,
+//!     send_policy jsonb,
+//!     status text NOT NULL DEFAULT 'none',
+//!     dedup_key text,
+//!     metadata jsonb NOT NULL DEFAULT '{}'
+//! );
+//! ```
+//!
+//! ```rust,no_run
+//! use shared_types::TelegramQueueMessage;
+//! use shared_types::sql::telegram;
+//!
+//! async fn pending(pool: &sqlx::PgPool) -> sqlx::Result<Vec<TelegramQueueMessage>> {
+//!     let query = format!("SELECT {} FROM telegram_outbox", telegram::COLUMNS.join(", "));
+//!     sqlx::query_as(&query).fetch_all(pool).await
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgRow, PgTypeInfo, PgValueRef};
+use sqlx::types::Json;
+use sqlx::{Decode, Encode, FromRow, Postgres, Row, Type};
+
+use crate::envelope::QueueEnvelope;
+use crate::priority::Priority;
+use crate::producer::ProducerInfo;
+use crate::retry::RetryInfo;
+use crate::telegram::{MessageStatus, TelegramQueueMessage};
+
+/// Columns of a [`TelegramQueueMessage`] row.
+pub mod telegram {
+    pub const SCHEMA_VERSION: &str = "schema_version";
+    pub const CHAT_ID: &str = "chat_id";
+    pub const MESSAGE: &str = "message";
+    pub const FORCE_SEND: &str = "force_send";
+    pub const SEND_POLICY: &str = "send_policy";
+    pub const STATUS: &str = "status";
+    pub const DEDUP_KEY: &str = "dedup_key";
+    pub const METADATA: &str = "metadata";
+
+    /// Every column, in field order.
+    pub const COLUMNS: [&str; 8] = [
+        SCHEMA_VERSION,
+        CHAT_ID,
+        MESSAGE,
+        FORCE_SEND,
+        SEND_POLICY,
+        STATUS,
+        DEDUP_KEY,
+        METADATA,
+    ];
+}
+
+/// Columns of a [`QueueEnvelope`] row.
+pub mod envelope {
+    pub const SCHEMA_VERSION: &str = "schema_version";
+    pub const MESSAGE_ID: &str = "message_id";
+    pub const CREATED_AT: &str = "created_at";
+    pub const CORRELATION_ID: &str = "correlation_id";
+    pub const PRODUCER: &str = "producer";
+    pub const PRODUCER_INFO: &str = "producer_info";
+    pub const TRACEPARENT: &str = "traceparent";
+    pub const TRACESTATE: &str = "tracestate";
+    pub const ROUTING_KEY: &str = "routing_key";
+    pub const IDEMPOTENCY_KEY: &str = "idempotency_key";
+    pub const PRIORITY: &str = "priority";
+    pub const EXPIRES_AT: &str = "expires_at";
+    pub const DELIVER_AFTER_MS: &str = "deliver_after_ms";
+    pub const DELIVER_AT: &str = "deliver_at";
+    pub const RETRY: &str = "retry";
+    pub const PAYLOAD: &str = "payload";
+
+    /// Every column, in field order.
+    pub const COLUMNS: [&str; 16] = [
+        SCHEMA_VERSION,
+        MESSAGE_ID,
+        CREATED_AT,
+        CORRELATION_ID,
+        PRODUCER,
+        PRODUCER_INFO,
+        TRACEPARENT,
+        TRACESTATE,
+        ROUTING_KEY,
+        IDEMPOTENCY_KEY,
+        PRIORITY,
+        EXPIRES_AT,
+        DELIVER_AFTER_MS,
+        DELIVER_AT,
+        RETRY,
+        PAYLOAD,
+    ];
+}
+
+fn column_error(column: &str, source: impl Into<BoxDynError>) -> sqlx::Error {
+    sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source: source.into(),
+    }
+}
+
+fn schema_version(row: &PgRow, column: &str) -> sqlx::Result<u32> {
+    u32::try_from(row.try_get::<i32, _>(column)?).map_err(|err| column_error(column, err))
+}
+
+fn json<T: DeserializeOwned>(row: &PgRow, column: &str) -> sqlx::Result<Option<T>> {
+    Ok(row.try_get::<Option<Json<T>>, _>(column)?.map(|Json(value)| value))
+}
+
+impl<'r> FromRow<'r, PgRow> for TelegramQueueMessage {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            schema_version: schema_version(row, telegram::SCHEMA_VERSION)?,
+            chat_id: row.try_get(telegram::CHAT_ID)?,
+            message: row.try_get(telegram::MESSAGE)?,
+            force_send: row.try_get(telegram::FORCE_SEND)?,
+            send_policy: json(row, telegram::SEND_POLICY)?,
+            status: row
+                .try_get::<Option<MessageStatus>, _>(telegram::STATUS)?
+                .unwrap_or_default(),
+            dedup_key: row.try_get(telegram::DEDUP_KEY)?,
+            metadata: json::<HashMap<String, String>>(row, telegram::METADATA)?.unwrap_or_default(),
+        })
+    }
+}
+
+/// Reads the payload from the `jsonb` column [`envelope::PAYLOAD`].
+impl<'r, T: DeserializeOwned> FromRow<'r, PgRow> for QueueEnvelope<T> {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let deliver_after = row
+            .try_get::<Option<i64>, _>(envelope::DELIVER_AFTER_MS)?
+            .map(|millis| u64::try_from(millis).map(Duration::from_millis))
+            .transpose()
+            .map_err(|err| column_error(envelope::DELIVER_AFTER_MS, err))?;
+        Ok(Self {
+            schema_version: schema_version(row, envelope::SCHEMA_VERSION)?,
+            message_id: row.try_get(envelope::MESSAGE_ID)?,
+            created_at: row.try_get(envelope::CREATED_AT)?,
+            correlation_id: row.try_get(envelope::CORRELATION_ID)?,
+            producer: row.try_get(envelope::PRODUCER)?,
+            producer_info: json::<ProducerInfo>(row, envelope::PRODUCER_INFO)?,
+            traceparent: row.try_get(envelope::TRACEPARENT)?,
+            tracestate: row.try_get(envelope::TRACESTATE)?,
+            routing_key: row.try_get(envelope::ROUTING_KEY)?,
+            idempotency_key: row.try_get(envelope::IDEMPOTENCY_KEY)?,
+            priority: row
+                .try_get::<Option<Priority>, _>(envelope::PRIORITY)?
+                .unwrap_or_default(),
+            expires_at: row.try_get(envelope::EXPIRES_AT)?,
+            deliver_after,
+            deliver_at: row.try_get(envelope::DELIVER_AT)?,
+            retry: json::<RetryInfo>(row, envelope::RETRY)?,
+            payload: row.try_get::<Json<T>, _>(envelope::PAYLOAD)?.0,
+        })
+    }
+}
+
+/// Stored as text, see [`MessageStatus::as_str`].
+impl Type<Postgres> for MessageStatus {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for MessageStatus {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for MessageStatus {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+    }
+}
+
+/// Stored as a `smallint` ordered like the priorities, so outbox queries can
+/// `ORDER BY priority DESC`.
+impl Type<Postgres> for Priority {
+    fn type_info() -> PgTypeInfo {
+        <i16 as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for Priority {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <i16 as Encode<Postgres>>::encode(i16::from(self.rank()), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Priority {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let rank = <i16 as Decode<Postgres>>::decode(value)?;
+        u8::try_from(rank)
+            .ok()
+            .and_then(Priority::from_rank)
+            .ok_or_else(|| format!("invalid priority {}", rank).into())
+    }
+}
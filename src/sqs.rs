@@ -0,0 +1,218 @@
+//! SQS message attributes of queue envelopes.
+//!
+//! The envelope is sent as the message body and its metadata is mirrored in
+//! message attributes, so consumers and SNS filter policies can route on it
+//! without parsing the body. Ids, names and the priority are `String`
+//! attributes, times are `Number` attributes in epoch milliseconds.
+//!
+//! SQS allows [`SQS_MAX_MESSAGE_ATTRIBUTES`] attributes, so only the most
+//! useful metadata is mirrored: the payload kind, message id, producer,
+//! priority, creation time and, when set, the correlation id, routing key,
+//! idempotency key, expiry and `traceparent`. The body carries the rest.
+//!
+//! Attribute names, data types and values count towards
+//! [`SQS_MAX_MESSAGE_BYTES`]; see [`QueueEnvelope::validate_sqs_size`].
+
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::envelope::QueueEnvelope;
+use crate::error::SharedTypesError;
+use crate::limits::{SQS_MAX_MESSAGE_BYTES, SizeError};
+use crate::payload::QueuePayload;
+
+/// Largest number of message attributes accepted by SQS.
+pub const SQS_MAX_MESSAGE_ATTRIBUTES: usize = 10;
+
+/// Attribute holding the [`QueuePayload::KIND`] of the payload.
+pub const KIND_ATTRIBUTE: &str = "kind";
+pub const MESSAGE_ID_ATTRIBUTE: &str = "messageId";
+pub const PRODUCER_ATTRIBUTE: &str = "producer";
+pub const PRIORITY_ATTRIBUTE: &str = "priority";
+pub const CREATED_AT_ATTRIBUTE: &str = "createdAt";
+pub const CORRELATION_ID_ATTRIBUTE: &str = "correlationId";
+pub const ROUTING_KEY_ATTRIBUTE: &str = "routingKey";
+pub const IDEMPOTENCY_KEY_ATTRIBUTE: &str = "idempotencyKey";
+pub const EXPIRES_AT_ATTRIBUTE: &str = "expiresAt";
+pub const TRACEPARENT_ATTRIBUTE: &str = "traceparent";
+
+/// Value of an SQS message attribute, like the SDK `MessageAttributeValue`
+/// with a `String` or `Number` data type.
+///
+/// ```rust
+/// use shared_types::sqs::SqsAttributeValue;
+///
+/// let value = SqsAttributeValue::Number("1715914800000".into());
+/// assert_eq!(value.data_type(), "Number");
+/// assert_eq!(value.size(), "Number".len() + "1715914800000".len());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqsAttributeValue {
+    String(String),
+    Number(String),
+}
+
+impl SqsAttributeValue {
+    /// Returns the SQS data type, `"String"` or `"Number"`.
+    pub fn data_type(&self) -> &'static str {
+        match self {
+            SqsAttributeValue::String(_) => "String",
+            SqsAttributeValue::Number(_) => "Number",
+        }
+    }
+
+    /// Returns the value as sent in `StringValue`.
+    pub fn value(&self) -> &str {
+        match self {
+            SqsAttributeValue::String(value) | SqsAttributeValue::Number(value) => value,
+        }
+    }
+
+    /// Returns the bytes SQS counts for the data type and value.
+    pub fn size(&self) -> usize {
+        self.data_type().len() + self.value().len()
+    }
+
+    fn millis(time: DateTime<Utc>) -> Self {
+        SqsAttributeValue::Number(time.timestamp_millis().to_string())
+    }
+}
+
+impl fmt::Display for SqsAttributeValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.value())
+    }
+}
+
+/// Returns the bytes SQS counts for `attributes`: each name, data type and value.
+pub fn sqs_attributes_size(attributes: &HashMap<String, SqsAttributeValue>) -> usize {
+    attributes
+        .iter()
+        .map(|(name, value)| name.len() + value.size())
+        .sum()
+}
+
+fn mismatch(attribute: &str, value: impl fmt::Display, expected: impl fmt::Display) -> SharedTypesError {
+    SharedTypesError::Validation(format!(
+        "SQS attribute {} is {} but the body says {}",
+        attribute, value, expected
+    ))
+}
+
+impl<T: QueuePayload> QueueEnvelope<T> {
+    /// Returns the message attributes mirroring the envelope metadata.
+    ///
+    /// ```rust
+    /// use shared_types::{Priority, QueueEnvelope, TelegramQueueMessage};
+    /// use shared_types::sqs::SqsAttributeValue;
+    ///
+    /// let envelope = QueueEnvelope::new("ftp-sync", TelegramQueueMessage::new(123, "done".into(), false))
+    ///     .with_correlation_id("run-42")
+    ///     .with_priority(Priority::High);
+    /// let attributes = envelope.to_sqs_attributes();
+    /// assert_eq!(attributes["kind"], SqsAttributeValue::String("telegram".into()));
+    /// assert_eq!(attributes["priority"].value(), "high");
+    /// assert_eq!(attributes["createdAt"].data_type(), "Number");
+    /// assert_eq!(attributes["correlationId"].value(), "run-42");
+    /// assert!(!attributes.contains_key("routingKey"));
+    ///
+    /// let envelope = QueueEnvelope::new("", TelegramQueueMessage::new(123, "done".into(), false))
+    ///     .with_correlation_id("");
+    /// let attributes = envelope.to_sqs_attributes();
+    /// assert!(!attributes.contains_key("producer") && !attributes.contains_key("correlationId"));
+    /// ```
+    pub fn to_sqs_attributes(&self) -> HashMap<String, SqsAttributeValue> {
+        let mut attributes = HashMap::with_capacity(SQS_MAX_MESSAGE_ATTRIBUTES);
+        // SQS rejects empty String attributes.
+        let mut string = |name: &str, value: &str| {
+            if !value.is_empty() {
+                attributes.insert(name.to_string(), SqsAttributeValue::String(value.to_string()));
+            }
+        };
+        string(KIND_ATTRIBUTE, T::KIND);
+        string(MESSAGE_ID_ATTRIBUTE, &self.message_id.to_string());
+        string(PRODUCER_ATTRIBUTE, &self.producer);
+        string(PRIORITY_ATTRIBUTE, self.priority.as_str());
+        for (name, value) in [
+            (CORRELATION_ID_ATTRIBUTE, &self.correlation_id),
+            (ROUTING_KEY_ATTRIBUTE, &self.routing_key),
+            (IDEMPOTENCY_KEY_ATTRIBUTE, &self.idempotency_key),
+            (TRACEPARENT_ATTRIBUTE, &self.traceparent),
+        ] {
+            if let Some(value) = value {
+                string(name, value);
+            }
+        }
+        attributes.insert(CREATED_AT_ATTRIBUTE.to_string(), SqsAttributeValue::millis(self.created_at));
+        if let Some(expires_at) = self.expires_at {
+            attributes.insert(EXPIRES_AT_ATTRIBUTE.to_string(), SqsAttributeValue::millis(expires_at));
+        }
+        attributes
+    }
+
+    /// Checks that the body and attributes of the envelope fit in
+    /// [`SQS_MAX_MESSAGE_BYTES`], returning their size.
+    ///
+    /// ```rust
+    /// use shared_types::{QueueEnvelope, TelegramQueueMessage};
+    /// use shared_types::limits::SQS_MAX_MESSAGE_BYTES;
+    /// use shared_types::sqs::sqs_attributes_size;
+    ///
+    /// let envelope = QueueEnvelope::new("ftp-sync", TelegramQueueMessage::new(123, "done".into(), false));
+    /// let body = envelope.validate_size(SQS_MAX_MESSAGE_BYTES).unwrap();
+    /// let attributes = sqs_attributes_size(&envelope.to_sqs_attributes());
+    /// assert_eq!(envelope.validate_sqs_size().unwrap(), body + attributes);
+    /// ```
+    pub fn validate_sqs_size(&self) -> Result<usize, SizeError> {
+        let body = serde_json::to_vec(self).map_err(SizeError::Serialize)?.len();
+        let size = body + sqs_attributes_size(&self.to_sqs_attributes());
+        if size > SQS_MAX_MESSAGE_BYTES {
+            return Err(SizeError::TooLarge {
+                size,
+                limit: SQS_MAX_MESSAGE_BYTES,
+            });
+        }
+        Ok(size)
+    }
+
+    /// Parses a received message, checking its attributes against the body.
+    ///
+    /// Fails when the kind, message id, correlation id or priority attributes
+    /// disagree with the body; missing and unknown attributes are ignored.
+    ///
+    /// ```rust
+    /// use shared_types::{QueueEnvelope, TelegramQueueMessage};
+    /// use shared_types::sqs::SqsAttributeValue;
+    ///
+    /// let envelope = QueueEnvelope::new("ftp-sync", TelegramQueueMessage::new(123, "done".into(), false));
+    /// let body = serde_json::to_string(&envelope).unwrap();
+    /// let mut attributes = envelope.to_sqs_attributes();
+    /// let restored = QueueEnvelope::<TelegramQueueMessage>::from_sqs(&body, &attributes).unwrap();
+    /// assert_eq!(restored.message_id, envelope.message_id);
+    ///
+    /// attributes.insert("kind".into(), SqsAttributeValue::String("email".into()));
+    /// assert!(QueueEnvelope::<TelegramQueueMessage>::from_sqs(&body, &attributes).is_err());
+    /// ```
+    pub fn from_sqs(body: &str, attributes: &HashMap<String, SqsAttributeValue>) -> Result<Self, SharedTypesError> {
+        let attribute = |name: &str| attributes.get(name).map(SqsAttributeValue::value);
+        if let Some(kind) = attribute(KIND_ATTRIBUTE).filter(|kind| *kind != T::KIND) {
+            return Err(mismatch(KIND_ATTRIBUTE, kind, T::KIND));
+        }
+
+        let envelope: Self = serde_json::from_str(body)?;
+        let message_id = envelope.message_id.to_string();
+        if let Some(value) = attribute(MESSAGE_ID_ATTRIBUTE).filter(|value| *value != message_id) {
+            return Err(mismatch(MESSAGE_ID_ATTRIBUTE, value, message_id));
+        }
+        let correlation_id = envelope.correlation_id.as_deref().unwrap_or_default();
+        if let Some(value) = attribute(CORRELATION_ID_ATTRIBUTE).filter(|value| *value != correlation_id) {
+            return Err(mismatch(CORRELATION_ID_ATTRIBUTE, value, correlation_id));
+        }
+        if let Some(value) = attribute(PRIORITY_ATTRIBUTE).filter(|value| value.parse() != Ok(envelope.priority)) {
+            return Err(mismatch(PRIORITY_ATTRIBUTE, value, envelope.priority.as_str()));
+        }
+        Ok(envelope)
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramQueueMessage};
+
+/// State of a service on the status board.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ServiceState {
+    Operational,
+    Degraded,
+    Down,
+    /// Down on purpose for planned maintenance.
+    Maintenance,
+    /// Not checked yet, or the check itself failed.
+    #[default]
+    Unknown,
+}
+
+impl ServiceState {
+    /// Returns the colored marker shown on the board.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            ServiceState::Operational => "🟢",
+            ServiceState::Degraded => "🟡",
+            ServiceState::Down => "🔴",
+            ServiceState::Maintenance => "🔧",
+            ServiceState::Unknown => "⚪",
+        }
+    }
+
+    /// Returns the state name shown on the board.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceState::Operational => "operational",
+            ServiceState::Degraded => "degraded",
+            ServiceState::Down => "down",
+            ServiceState::Maintenance => "maintenance",
+            ServiceState::Unknown => "unknown",
+        }
+    }
+
+    /// Returns the message status of the state.
+    pub fn message_status(&self) -> MessageStatus {
+        match self {
+            ServiceState::Operational => MessageStatus::Ok,
+            ServiceState::Degraded | ServiceState::Unknown => MessageStatus::Warn,
+            ServiceState::Down => MessageStatus::Error,
+            ServiceState::Maintenance => MessageStatus::Info,
+        }
+    }
+}
+
+/// Latest check of a service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ServiceStatus {
+    pub name: String,
+    pub state: ServiceState,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "last_check"))]
+    pub last_check: DateTime<Utc>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub message: Option<String>,
+}
+
+impl ServiceStatus {
+    pub fn new(name: impl Into<String>, state: ServiceState, last_check: DateTime<Utc>) -> Self {
+        Self {
+            name: name.into(),
+            state,
+            last_check,
+            message: None,
+        }
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+/// Status page style overview of every service, rendered as one message the
+/// status bot pins and edits instead of posting updates.
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use shared_types::MessageStatus;
+/// use shared_types::status::{ServiceState, ServiceStatus, StatusBoard};
+///
+/// let at = Utc.with_ymd_and_hms(2024, 5, 17, 3, 0, 0).unwrap();
+/// let board = StatusBoard::new("Production", at)
+///     .with_service(ServiceStatus::new("api", ServiceState::Operational, at))
+///     .with_service(ServiceStatus::new("ftp-sync", ServiceState::Degraded, at).with_message("slow <uploads>"));
+/// assert_eq!(board.severity(), MessageStatus::Warn);
+/// assert_eq!(
+///     board.render_telegram(),
+///     "<b>Production</b>\n🟢 <b>api</b> operational · 03:00\n\
+///      🟡 <b>ftp-sync</b> degraded: slow &lt;uploads&gt; · 03:00\n<i>updated 2024-05-17 03:00 UTC</i>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct StatusBoard {
+    pub title: String,
+    pub services: Vec<ServiceStatus>,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    #[cfg_attr(feature = "serde", serde(alias = "updated_at"))]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StatusBoard {
+    pub fn new(title: impl Into<String>, updated_at: DateTime<Utc>) -> Self {
+        Self {
+            title: title.into(),
+            services: Vec::new(),
+            updated_at,
+        }
+    }
+
+    pub fn with_service(mut self, service: ServiceStatus) -> Self {
+        self.upsert(service);
+        self
+    }
+
+    /// Adds a service or replaces the status of the service with the same name.
+    pub fn upsert(&mut self, service: ServiceStatus) {
+        match self.services.iter_mut().find(|existing| existing.name == service.name) {
+            Some(existing) => *existing = service,
+            None => self.services.push(service),
+        }
+    }
+
+    /// Returns the status of the worst service, `Ok` for an empty board.
+    pub fn severity(&self) -> MessageStatus {
+        self.services
+            .iter()
+            .map(|service| service.state.message_status())
+            .max()
+            .unwrap_or(MessageStatus::Ok)
+    }
+
+    /// Renders the board as Telegram HTML, one line per service, suitable for
+    /// `editMessageText` on the pinned message.
+    pub fn render_telegram(&self) -> String {
+        let mut lines = vec![format!("<b>{}</b>", render::escape_text(&self.title))];
+        for service in &self.services {
+            let mut line = format!(
+                "{} <b>{}</b> {}",
+                service.state.marker(),
+                render::escape_text(&service.name),
+                service.state.as_str()
+            );
+            if let Some(message) = &service.message {
+                line.push_str(&format!(": {}", render::escape_text(message)));
+            }
+            line.push_str(&format!(" · {}", service.last_check.format("%H:%M")));
+            lines.push(line);
+        }
+        lines.push(format!("<i>updated {}</i>", self.updated_at.format("%Y-%m-%d %H:%M UTC")));
+        lines.join("\n")
+    }
+
+    /// Returns the board as a new message, to post and pin the first time.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let mut message = TelegramQueueMessage::new(chat_id, self.render_telegram(), false);
+        message.status = self.severity();
+        message
+    }
+}
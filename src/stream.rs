@@ -0,0 +1,93 @@
+//! Redis Streams entries of queue envelopes.
+//!
+//! An entry is a flat map of strings: the envelope metadata is split into one
+//! field per key, named like the JSON keys (`messageId`, `createdAt`, …) and
+//! left out when unset, the payload kind is in [`KIND_FIELD`] so consumers can
+//! dispatch before parsing, and the payload is JSON text in [`PAYLOAD_FIELD`].
+//! Nested metadata (`producerInfo`, `retry`) is JSON text as well.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::envelope::QueueEnvelope;
+use crate::error::SharedTypesError;
+use crate::payload::QueuePayload;
+
+/// Field holding the [`QueuePayload::KIND`] of the payload.
+pub const KIND_FIELD: &str = "kind";
+
+/// Field holding the payload as JSON text.
+pub const PAYLOAD_FIELD: &str = "payload";
+
+/// Fields holding JSON text rather than a plain string.
+const JSON_FIELDS: &[&str] = &[PAYLOAD_FIELD, "producerInfo", "retry"];
+
+/// Fields holding a number.
+const NUMBER_FIELDS: &[&str] = &["schemaVersion", "deliverAfterMs"];
+
+impl<T: QueuePayload> QueueEnvelope<T> {
+    /// Returns the Redis Streams entry of the envelope, for `XADD`.
+    ///
+    /// ```rust
+    /// use shared_types::{QueueEnvelope, TelegramQueueMessage};
+    ///
+    /// let envelope = QueueEnvelope::new("ftp-sync", TelegramQueueMessage::new(123, "done".into(), false))
+    ///     .with_correlation_id("run-42");
+    /// let fields = envelope.to_stream_fields().unwrap();
+    /// assert_eq!(fields["kind"], "telegram");
+    /// assert_eq!(fields["producer"], "ftp-sync");
+    /// assert_eq!(fields["correlationId"], "run-42");
+    /// assert_eq!(fields["messageId"], envelope.message_id.to_string());
+    /// assert!(fields["payload"].starts_with(r#"{"schemaVersion":1,"chatId":123"#));
+    /// assert!(!fields.contains_key("routingKey"));
+    ///
+    /// let restored = QueueEnvelope::<TelegramQueueMessage>::from_stream_fields(&fields).unwrap();
+    /// assert_eq!(restored.message_id, envelope.message_id);
+    /// assert_eq!(restored.created_at, envelope.created_at);
+    /// assert_eq!(restored.payload.message, "done");
+    /// ```
+    pub fn to_stream_fields(&self) -> Result<HashMap<String, String>, SharedTypesError> {
+        let Value::Object(object) = serde_json::to_value(self)? else {
+            unreachable!("an envelope serializes as a JSON object");
+        };
+        let mut fields = HashMap::with_capacity(object.len() + 1);
+        fields.insert(KIND_FIELD.to_string(), T::KIND.to_string());
+        fields.insert(PAYLOAD_FIELD.to_string(), serde_json::to_string(&self.payload)?);
+        for (key, value) in object {
+            let value = match value {
+                Value::Null => continue,
+                _ if key == PAYLOAD_FIELD => continue,
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            fields.insert(key, value);
+        }
+        Ok(fields)
+    }
+
+    /// Parses an entry written by [`QueueEnvelope::to_stream_fields`].
+    ///
+    /// Fails when the `kind` field is present and names another payload kind.
+    pub fn from_stream_fields(fields: &HashMap<String, String>) -> Result<Self, SharedTypesError> {
+        if let Some(kind) = fields.get(KIND_FIELD).filter(|kind| kind.as_str() != T::KIND) {
+            return Err(SharedTypesError::Validation(format!(
+                "stream entry holds a {} payload, expected {}",
+                kind,
+                T::KIND
+            )));
+        }
+        let mut object = Map::with_capacity(fields.len());
+        for (key, value) in fields {
+            let value = if JSON_FIELDS.contains(&key.as_str()) || NUMBER_FIELDS.contains(&key.as_str()) {
+                serde_json::from_str(value)?
+            } else if key == KIND_FIELD {
+                continue;
+            } else {
+                Value::String(value.clone())
+            };
+            object.insert(key.clone(), value);
+        }
+        Ok(serde_json::from_value(Value::Object(object))?)
+    }
+}
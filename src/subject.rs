@@ -0,0 +1,160 @@
+//! NATS subjects of notifications.
+//!
+//! Subjects follow the [`routes`] hierarchy and add the severity and the job:
+//! `notify.{channel}.{severity}.{job}`, e.g. `notify.telegram.error.ftp`, so a
+//! worker subscribes to `notify.telegram.>` for every Telegram message or to
+//! `notify.*.error.>` for failures on any channel. The job token is left out
+//! when the message has no job.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::job::JobName;
+use crate::routes;
+use crate::telegram::{MessageStatus, TelegramQueueMessage};
+
+/// Channel token of Telegram messages.
+pub const TELEGRAM_CHANNEL: &str = "telegram";
+
+/// A notification subject.
+///
+/// Characters NATS reserves in tokens (`.`, `*`, `>` and whitespace) are
+/// replaced by `_` when the subject is rendered.
+///
+/// ```rust
+/// use shared_types::MessageStatus;
+/// use shared_types::subject::Subject;
+///
+/// let subject = Subject::telegram().with_severity(MessageStatus::Error).with_job("ftp sync");
+/// assert_eq!(subject.to_string(), "notify.telegram.error.ftp_sync");
+///
+/// let parsed: Subject = "notify.telegram.error.ftp_sync".parse().unwrap();
+/// assert_eq!(parsed.severity, MessageStatus::Error);
+/// assert_eq!(parsed.job.unwrap().as_str(), "ftp_sync");
+/// assert!("notify.telegram.fatal".parse::<Subject>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subject {
+    pub channel: String,
+    pub severity: MessageStatus,
+    pub job: Option<JobName>,
+}
+
+impl Subject {
+    /// Creates the subject of `channel`, without severity or job.
+    pub fn new(channel: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            severity: MessageStatus::None,
+            job: None,
+        }
+    }
+
+    /// Creates the subject of Telegram messages.
+    pub fn telegram() -> Self {
+        Self::new(TELEGRAM_CHANNEL)
+    }
+
+    /// Returns the subject of a Telegram message, from its status and job name.
+    ///
+    /// ```rust
+    /// use shared_types::{MessageStatus, TelegramQueueMessage};
+    /// use shared_types::subject::Subject;
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .status(MessageStatus::Warn)
+    ///     .job_name("backup")
+    ///     .content("slow")
+    ///     .build();
+    /// assert_eq!(Subject::for_message(&msg).to_string(), "notify.telegram.warn.backup");
+    /// ```
+    pub fn for_message(message: &TelegramQueueMessage) -> Self {
+        let subject = Self::telegram().with_severity(message.status);
+        match message.job_name() {
+            Some(job_name) => subject.with_job(job_name),
+            None => subject,
+        }
+    }
+
+    /// Sets the severity token.
+    pub fn with_severity(mut self, severity: MessageStatus) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Sets the job token; an empty job name leaves the token out.
+    ///
+    /// ```rust
+    /// use shared_types::subject::Subject;
+    ///
+    /// assert_eq!(Subject::telegram().with_job("").to_string(), "notify.telegram.none");
+    /// ```
+    pub fn with_job(mut self, job: impl Into<JobName>) -> Self {
+        self.job = Some(job.into()).filter(|job| !job.as_str().is_empty());
+        self
+    }
+}
+
+fn write_token(f: &mut fmt::Formatter<'_>, token: &str) -> fmt::Result {
+    for c in token.chars() {
+        let c = if matches!(c, '.' | '*' | '>') || c.is_whitespace() { '_' } else { c };
+        write!(f, "{}", c)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.", routes::NOTIFY)?;
+        write_token(f, &self.channel)?;
+        write!(f, ".{}", self.severity.as_str())?;
+        if let Some(job) = &self.job {
+            f.write_str(".")?;
+            write_token(f, job.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Subject {
+    type Err = SubjectError;
+
+    fn from_str(subject: &str) -> Result<Self, Self::Err> {
+        let error = || SubjectError(subject.to_string());
+        let mut tokens = subject.split('.');
+        if tokens.next() != Some(routes::NOTIFY) {
+            return Err(error());
+        }
+        let channel = tokens.next().filter(|channel| !channel.is_empty()).ok_or_else(error)?;
+        let severity = tokens.next().ok_or_else(error)?.parse().map_err(|_| error())?;
+        let job = tokens.next().map(JobName::new);
+        if tokens.next().is_some() || job.as_ref().is_some_and(|job| job.as_str().is_empty()) {
+            return Err(error());
+        }
+        Ok(Self {
+            channel: channel.to_string(),
+            severity,
+            job,
+        })
+    }
+}
+
+/// Error returned when parsing a string that is not a notification [`Subject`].
+///
+/// ```rust
+/// use shared_types::error::{ErrorCode, SharedTypesError};
+/// use shared_types::subject::Subject;
+///
+/// let err = SharedTypesError::from("notify".parse::<Subject>().unwrap_err());
+/// assert_eq!(err.code(), ErrorCode::SubjectInvalid);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubjectError(pub String);
+
+impl fmt::Display for SubjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid notification subject {:?}", self.0)
+    }
+}
+
+impl std::error::Error for SubjectError {}
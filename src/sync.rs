@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::job::JobName;
+use crate::render;
+use crate::telegram::{MessageStatus, TELEGRAM_MAX_MESSAGE_LENGTH, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Longest error line in detailed messages.
+const MAX_ERROR_LENGTH: usize = 300;
+
+/// Room left in detailed messages for the header around the error list.
+const HEADER_LENGTH: usize = 200;
+
+/// An entity the sync could not apply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct EntityError {
+    /// Identifier of the entity in the source system, e.g. a SKU.
+    #[cfg_attr(feature = "serde", serde(alias = "entity_id"))]
+    pub entity_id: String,
+    pub message: String,
+}
+
+/// Outcome of a catalog or CRM sync run: what changed on the target.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use shared_types::sync::SyncResult;
+///
+/// let result = SyncResult::new("catalog-sync")
+///     .with_counts(12, 3, 1, 40)
+///     .with_error("SKU-1", "price <0")
+///     .with_duration(Duration::from_millis(4200));
+/// assert_eq!(
+///     result.to_telegram_message(123).message,
+///     "⚠️ - <i>catalog-sync</i>\n12 created, 3 updated, 1 deleted, 40 skipped, 1 error in 4.2s"
+/// );
+///
+/// let messages = result.to_telegram_messages(123);
+/// assert_eq!(messages.len(), 2);
+/// assert_eq!(
+///     messages[1].message,
+///     "⚠️ - <i>catalog-sync</i>\nerrors 1-1 of 1\n<pre>SKU-1: price &lt;0</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SyncResult {
+    #[cfg_attr(feature = "serde", serde(alias = "job_name"))]
+    pub job_name: JobName,
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub created: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub updated: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub deleted: u64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub skipped: u64,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub errors: Vec<EntityError>,
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<u64>"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number | null"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<u64>))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            rename = "durationMs",
+            with = "crate::duration_ms::option",
+            skip_serializing_if = "Option::is_none",
+            alias = "duration_ms"
+        )
+    )]
+    pub duration: Option<Duration>,
+}
+
+impl SyncResult {
+    pub fn new(job_name: impl Into<JobName>) -> Self {
+        Self {
+            job_name: job_name.into(),
+            created: 0,
+            updated: 0,
+            deleted: 0,
+            skipped: 0,
+            errors: Vec::new(),
+            duration: None,
+        }
+    }
+
+    /// Sets the created, updated, deleted and skipped counts.
+    pub fn with_counts(mut self, created: u64, updated: u64, deleted: u64, skipped: u64) -> Self {
+        self.created = created;
+        self.updated = updated;
+        self.deleted = deleted;
+        self.skipped = skipped;
+        self
+    }
+
+    pub fn with_error(mut self, entity_id: impl Into<String>, message: impl Into<String>) -> Self {
+        self.errors.push(EntityError {
+            entity_id: entity_id.into(),
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// `Error` when every entity failed, `Warn` when some did, `Ok` otherwise.
+    pub fn severity(&self) -> MessageStatus {
+        let applied = self.created + self.updated + self.deleted + self.skipped;
+        match (self.errors.len(), applied) {
+            (0, _) => MessageStatus::Ok,
+            (_, 0) => MessageStatus::Error,
+            _ => MessageStatus::Warn,
+        }
+    }
+
+    /// Formats the counts on a single line.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let mut summary = format!(
+            "{} created, {} updated, {} deleted, {} skipped",
+            self.created, self.updated, self.deleted, self.skipped
+        );
+        match self.errors.len() {
+            0 => {}
+            1 => summary.push_str(", 1 error"),
+            errors => summary.push_str(&format!(", {} errors", errors)),
+        }
+        if let Some(duration) = self.duration {
+            summary.push_str(&format!(" in {}", render::duration(duration)));
+        }
+        self.builder(chat_id).summary(summary).build()
+    }
+
+    /// Returns the summary message followed by the per-entity errors, split into
+    /// as many messages as needed to stay under [`TELEGRAM_MAX_MESSAGE_LENGTH`].
+    pub fn to_telegram_messages(&self, chat_id: i64) -> Vec<TelegramQueueMessage> {
+        let budget = TELEGRAM_MAX_MESSAGE_LENGTH - HEADER_LENGTH;
+        let mut pages: Vec<Vec<String>> = Vec::new();
+        let mut page_length = 0;
+        for error in &self.errors {
+            let line = render::truncate(&format!("{}: {}", error.entity_id, error.message), MAX_ERROR_LENGTH);
+            let length = render::escape_text(&line).chars().count() + 1;
+            match pages.last_mut() {
+                Some(page) if page_length + length <= budget => page.push(line),
+                _ => {
+                    pages.push(vec![line]);
+                    page_length = 0;
+                }
+            }
+            page_length += length;
+        }
+
+        let mut messages = vec![self.to_telegram_message(chat_id)];
+        let mut first = 1;
+        for page in pages {
+            let last = first + page.len() - 1;
+            messages.push(
+                self.builder(chat_id)
+                    .summary(format!("errors {}-{} of {}", first, last, self.errors.len()))
+                    .code_block(page.join("\n"))
+                    .build(),
+            );
+            first = last + 1;
+        }
+        messages
+    }
+
+    fn builder(&self, chat_id: i64) -> TelegramMessageBuilder {
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name(&self.job_name)
+    }
+}
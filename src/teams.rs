@@ -0,0 +1,413 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::job::JobName;
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// Adaptive Card schema URL.
+pub const ADAPTIVE_CARD_SCHEMA: &str = "http://adaptivecards.io/schemas/adaptive-card.json";
+
+/// Adaptive Card version supported by Teams.
+pub const ADAPTIVE_CARD_VERSION: &str = "1.4";
+
+/// Content type of an Adaptive Card attachment.
+pub const ADAPTIVE_CARD_CONTENT_TYPE: &str = "application/vnd.microsoft.card.adaptive";
+
+/// Color shared by text colors and container styles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AccentColor {
+    #[default]
+    Default,
+    Accent,
+    Good,
+    Warning,
+    Attention,
+}
+
+impl From<MessageStatus> for AccentColor {
+    /// Maps `Error` to attention (red), `Warn` to warning, `Ok` to good and
+    /// `Info` to accent.
+    fn from(status: MessageStatus) -> Self {
+        match status {
+            MessageStatus::None => AccentColor::Default,
+            MessageStatus::Info => AccentColor::Accent,
+            MessageStatus::Ok => AccentColor::Good,
+            MessageStatus::Warn => AccentColor::Warning,
+            MessageStatus::Error => AccentColor::Attention,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum TextWeight {
+    Lighter,
+    Default,
+    Bolder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum TextSize {
+    Small,
+    Default,
+    Medium,
+    Large,
+    ExtraLarge,
+}
+
+/// A `title`/`value` row of a fact set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Fact {
+    pub title: String,
+    pub value: String,
+}
+
+/// The subset of Adaptive Card elements used by our alerts, serialized in the
+/// Adaptive Card format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum CardElement {
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    TextBlock {
+        text: String,
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+        weight: Option<TextWeight>,
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+        size: Option<TextSize>,
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+        color: Option<AccentColor>,
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+        is_subtle: bool,
+        #[cfg_attr(feature = "serde", serde(default))]
+        wrap: bool,
+    },
+    FactSet { facts: Vec<Fact> },
+    Container {
+        #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+        style: Option<AccentColor>,
+        items: Vec<CardElement>,
+    },
+}
+
+impl CardElement {
+    /// A wrapping text block with default styling.
+    pub fn text(text: impl Into<String>) -> Self {
+        CardElement::TextBlock {
+            text: text.into(),
+            weight: None,
+            size: None,
+            color: None,
+            is_subtle: false,
+            wrap: true,
+        }
+    }
+}
+
+/// An action button of a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum CardAction {
+    #[cfg_attr(feature = "serde", serde(rename = "Action.OpenUrl"))]
+    OpenUrl { title: String, url: String },
+}
+
+/// An Adaptive Card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct AdaptiveCard {
+    #[cfg_attr(feature = "serde", serde(rename = "$schema"))]
+    pub schema: String,
+    /// Always `AdaptiveCard`.
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub card_type: String,
+    pub version: String,
+    pub body: Vec<CardElement>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub actions: Vec<CardAction>,
+}
+
+impl AdaptiveCard {
+    /// Creates an empty card of [`ADAPTIVE_CARD_VERSION`].
+    pub fn new() -> Self {
+        Self {
+            schema: ADAPTIVE_CARD_SCHEMA.to_string(),
+            card_type: "AdaptiveCard".to_string(),
+            version: ADAPTIVE_CARD_VERSION.to_string(),
+            body: Vec::new(),
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl Default for AdaptiveCard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Represents a message received from the queue to be posted to Microsoft Teams.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TeamsQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    /// Name of the incoming webhook configured on the worker side; the URL is never queued.
+    pub webhook: String,
+    pub card: AdaptiveCard,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+}
+
+impl TeamsQueueMessage {
+    pub fn new(webhook: impl Into<String>, card: AdaptiveCard) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            webhook: webhook.into(),
+            card,
+            status: MessageStatus::None,
+            dedup_key: None,
+        }
+    }
+
+    /// Returns a builder for a formatted card.
+    pub fn builder(webhook: impl Into<String>) -> TeamsMessageBuilder {
+        TeamsMessageBuilder::new(webhook)
+    }
+
+    /// Returns the body to post to the Teams webhook: a `message` with the card as attachment.
+    #[cfg(feature = "serde")]
+    pub fn to_webhook_payload(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "message",
+            "attachments": [{
+                "contentType": ADAPTIVE_CARD_CONTENT_TYPE,
+                "content": self.card,
+            }],
+        })
+    }
+}
+
+/// A builder for creating TeamsQueueMessage objects.
+///
+/// Mirrors [`crate::TelegramMessageBuilder`]: the job name becomes the card
+/// title in a container whose accent color follows the status, followed by the
+/// content, the facts, the details and the actions.
+///
+/// ```rust
+/// use shared_types::{MessageStatus, TeamsQueueMessage};
+/// use shared_types::teams::{AccentColor, CardElement};
+///
+/// let msg = TeamsQueueMessage::builder("ops")
+///     .status(MessageStatus::Error)
+///     .job_name("backup")
+///     .summary("snapshot failed")
+///     .fact("Host", "ftp01")
+///     .action("Open logs", "https://logs.example.com/backup")
+///     .build();
+/// let CardElement::Container { style, .. } = &msg.card.body[0] else { panic!() };
+/// assert_eq!(*style, Some(AccentColor::Attention));
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let payload = msg.to_webhook_payload();
+/// assert_eq!(payload["attachments"][0]["content"]["actions"][0]["type"], "Action.OpenUrl");
+/// # }
+/// ```
+pub struct TeamsMessageBuilder {
+    webhook: String,
+    status: MessageStatus,
+    emoji: Option<String>,
+    job_name: JobName,
+    summary: String,
+    content: String,
+    facts: Vec<Fact>,
+    details: String,
+    actions: Vec<CardAction>,
+    dedup_key: Option<String>,
+}
+
+impl TeamsMessageBuilder {
+    /// Initializes a new builder with the required webhook name.
+    pub fn new(webhook: impl Into<String>) -> Self {
+        Self {
+            webhook: webhook.into(),
+            status: MessageStatus::None,
+            emoji: None,
+            job_name: JobName::default(),
+            summary: String::new(),
+            content: String::new(),
+            facts: Vec::new(),
+            details: String::new(),
+            actions: Vec::new(),
+            dedup_key: None,
+        }
+    }
+
+    /// Sets the status level, which sets the accent color and adds an emoji to the title.
+    pub fn status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Overrides the status-derived emoji of the title. The color still follows the status.
+    pub fn emoji(mut self, emoji: impl Into<String>) -> Self {
+        self.emoji = Some(emoji.into());
+        self
+    }
+
+    /// Sets the job name, used as the card title.
+    pub fn job_name(mut self, job_name: impl Into<JobName>) -> Self {
+        self.job_name = job_name.into();
+        self
+    }
+
+    /// Sets a summary line, shown below the title.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = summary.into();
+        self
+    }
+
+    /// Sets the card content, a wrapping text block.
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    /// Adds a `title`/`value` fact.
+    pub fn fact(mut self, title: impl Into<String>, value: impl Into<String>) -> Self {
+        self.facts.push(Fact {
+            title: title.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Adds a button opening `url`.
+    pub fn action(mut self, title: impl Into<String>, url: impl Into<String>) -> Self {
+        self.actions.push(CardAction::OpenUrl {
+            title: title.into(),
+            url: url.into(),
+        });
+        self
+    }
+
+    /// Sets details (e.g. a log tail), rendered last in subtle text.
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = details.into();
+        self
+    }
+
+    /// Sets an explicit dedup key for the message.
+    pub fn dedup_key(mut self, dedup_key: impl Into<String>) -> Self {
+        self.dedup_key = Some(dedup_key.into());
+        self
+    }
+
+    /// Builds the TeamsQueueMessage.
+    pub fn build(self) -> TeamsQueueMessage {
+        let emoji = match &self.emoji {
+            Some(emoji) => emoji.as_str(),
+            None => self.status.emoji(),
+        };
+        let title = [emoji, self.job_name.as_str()]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut header = Vec::new();
+        if !title.is_empty() {
+            header.push(CardElement::TextBlock {
+                text: title,
+                weight: Some(TextWeight::Bolder),
+                size: Some(TextSize::Medium),
+                color: None,
+                is_subtle: false,
+                wrap: true,
+            });
+        }
+        if !self.summary.is_empty() {
+            header.push(CardElement::text(self.summary));
+        }
+
+        let mut card = AdaptiveCard::new();
+        if !header.is_empty() {
+            card.body.push(CardElement::Container {
+                style: Some(AccentColor::from(self.status)).filter(|style| *style != AccentColor::Default),
+                items: header,
+            });
+        }
+        if !self.content.is_empty() {
+            card.body.push(CardElement::text(self.content));
+        }
+        if !self.facts.is_empty() {
+            card.body.push(CardElement::FactSet { facts: self.facts });
+        }
+        if !self.details.is_empty() {
+            card.body.push(CardElement::TextBlock {
+                text: self.details,
+                weight: None,
+                size: Some(TextSize::Small),
+                color: None,
+                is_subtle: true,
+                wrap: true,
+            });
+        }
+        card.actions = self.actions;
+
+        TeamsQueueMessage {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            webhook: self.webhook,
+            card,
+            status: self.status,
+            dedup_key: self.dedup_key,
+        }
+    }
+}
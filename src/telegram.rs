@@ -1,19 +1,180 @@
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use html_escape::encode_text;
+
+use crate::chat_directory::ChatDirectory;
+use crate::error::SharedTypesError;
+use crate::hash::stable_hash_hex;
+use crate::job::JobName;
+use crate::policy::SendPolicy;
+use crate::render;
+use crate::schema::CURRENT_SCHEMA_VERSION;
+
+/// Maximum length of a Telegram message text, in characters.
+pub const TELEGRAM_MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// Default length of the text in the `Display` preview of a message, in characters.
+pub const PREVIEW_LENGTH: usize = 80;
+
+/// Options applied by [`TelegramQueueMessage::sanitize_message`] when building sanitized messages.
+///
+/// Missing fields take their [`Default`] value when deserializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(default, rename_all = "camelCase"))]
+pub struct SanitizeOptions {
+    /// Maximum message length before truncation.
+    #[cfg_attr(feature = "serde", serde(alias = "max_length"))]
+    pub max_length: usize,
+}
+
+impl SanitizeOptions {
+    /// Creates options truncating messages to `max_length`.
+    pub const fn new(max_length: usize) -> Self {
+        Self { max_length }
+    }
+}
+
+/// Truncates to [`TELEGRAM_MAX_MESSAGE_LENGTH`], so configuration structs can
+/// leave the options out:
+///
+/// ```rust
+/// use shared_types::MessageStatus;
+/// use shared_types::policy::SendPolicy;
+/// use shared_types::retry::RetryInfo;
+/// use shared_types::telegram::{SanitizeOptions, TELEGRAM_MAX_MESSAGE_LENGTH};
+///
+/// #[derive(Default)]
+/// struct AlertConfig {
+///     min_status: MessageStatus,
+///     sanitize: SanitizeOptions,
+///     send_policy: SendPolicy,
+///     retry: RetryInfo,
+/// }
+///
+/// let config = AlertConfig::default();
+/// assert_eq!(config.min_status, MessageStatus::None);
+/// assert_eq!(config.sanitize.max_length, TELEGRAM_MAX_MESSAGE_LENGTH);
+/// assert_eq!(config.send_policy, SendPolicy::Never);
+/// assert_eq!(config.retry.max_attempts, 3);
+/// ```
+///
+/// With `serde`, the options can be left out of a deserialized config or
+/// given partially:
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use serde::Deserialize;
+/// use shared_types::telegram::{SanitizeOptions, TELEGRAM_MAX_MESSAGE_LENGTH};
+///
+/// #[derive(Deserialize)]
+/// struct AlertConfig {
+///     #[serde(default)]
+///     sanitize: SanitizeOptions,
+/// }
+///
+/// let config: AlertConfig = serde_json::from_str("{}").unwrap();
+/// assert_eq!(config.sanitize.max_length, TELEGRAM_MAX_MESSAGE_LENGTH);
+/// let config: AlertConfig = serde_json::from_str(r#"{"sanitize": {"maxLength": 1000}}"#).unwrap();
+/// assert_eq!(config.sanitize.max_length, 1000);
+/// # }
+/// ```
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        Self::new(TELEGRAM_MAX_MESSAGE_LENGTH)
+    }
+}
 
 /// Represents the status level of a message for visual formatting.
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+///
+/// Statuses are ordered by severity: `None < Info < Ok < Warn < Error`. The
+/// discriminants are the severity ranks, while the binary encodings keep using
+/// the declaration index.
+///
+/// The order agrees with [`MessageStatus::severity`] and `Hash` with `Eq` on
+/// every pair of statuses:
+///
+/// ```rust
+/// use std::collections::HashSet;
+/// use shared_types::MessageStatus;
+///
+/// let all = [
+///     MessageStatus::None,
+///     MessageStatus::Info,
+///     MessageStatus::Warn,
+///     MessageStatus::Error,
+///     MessageStatus::Ok,
+/// ];
+/// for a in all {
+///     for b in all {
+///         assert_eq!(a.cmp(&b), a.severity().cmp(&b.severity()));
+///         assert_eq!(a.at_least(b), a.severity() >= b.severity());
+///     }
+/// }
+/// let mut sorted = all;
+/// sorted.sort();
+/// assert_eq!(sorted.map(|status| status.as_str()), ["none", "info", "ok", "warn", "error"]);
+/// assert_eq!(all.iter().collect::<HashSet<_>>().len(), all.len());
+/// ```
+///
+/// With serde a status is written as its variant name, e.g. `"Error"`, which
+/// existing consumers expect; the lowercase [`as_str`](Self::as_str) form used
+/// by every other enum of the crate is accepted when reading:
+///
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use shared_types::MessageStatus;
+///
+/// assert_eq!(serde_json::to_string(&MessageStatus::Error).unwrap(), r#""Error""#);
+/// assert_eq!(serde_json::from_str::<MessageStatus>(r#""error""#).unwrap(), MessageStatus::Error);
+/// assert_eq!(serde_json::from_str::<MessageStatus>(r#""Error""#).unwrap(), MessageStatus::Error);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "borsh", borsh(use_discriminant = false))]
 pub enum MessageStatus {
-    None,
-    Info,
-    Warn,
-    Error,
-    Ok,
+    #[default]
+    #[cfg_attr(feature = "serde", serde(alias = "none"))]
+    None = 0,
+    #[cfg_attr(feature = "serde", serde(alias = "info"))]
+    Info = 1,
+    #[cfg_attr(feature = "serde", serde(alias = "warn"))]
+    Warn = 3,
+    #[cfg_attr(feature = "serde", serde(alias = "error"))]
+    Error = 4,
+    #[cfg_attr(feature = "serde", serde(alias = "ok"))]
+    Ok = 2,
 }
 
 impl MessageStatus {
     /// Returns the emoji associated with the status.
-    pub fn emoji(&self) -> &'static str {
+    ///
+    /// ```rust
+    /// use shared_types::MessageStatus;
+    ///
+    /// const RESTART_EMOJI: &str = MessageStatus::Ok.emoji();
+    /// assert_eq!(RESTART_EMOJI, "✅");
+    /// ```
+    pub const fn emoji(&self) -> &'static str {
         match self {
             MessageStatus::None => "",
             MessageStatus::Info => "ℹ️",
@@ -22,16 +183,140 @@ impl MessageStatus {
             MessageStatus::Ok => "✅",
         }
     }
+
+    /// Returns the severity rank of the status, `0` for `None` up to `4` for `Error`.
+    pub const fn severity(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Returns true when the status is at least as severe as `min`.
+    ///
+    /// ```rust
+    /// use shared_types::MessageStatus;
+    ///
+    /// assert!(MessageStatus::Error.at_least(MessageStatus::Warn));
+    /// assert!(!MessageStatus::Ok.at_least(MessageStatus::Warn));
+    /// ```
+    pub const fn at_least(&self, min: MessageStatus) -> bool {
+        self.severity() >= min.severity()
+    }
+
+    /// Returns the lowercase name of the status, as accepted by `from_str`.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            MessageStatus::None => "none",
+            MessageStatus::Info => "info",
+            MessageStatus::Warn => "warn",
+            MessageStatus::Error => "error",
+            MessageStatus::Ok => "ok",
+        }
+    }
+
+    /// Returns true for `MessageStatus::None`.
+    pub const fn is_none(&self) -> bool {
+        matches!(self, MessageStatus::None)
+    }
+
+    /// Returns the status whose emoji matches `emoji`, if any.
+    ///
+    /// `MessageStatus::None` has no emoji and is never returned.
+    pub fn from_emoji(emoji: &str) -> Option<MessageStatus> {
+        [
+            MessageStatus::Info,
+            MessageStatus::Warn,
+            MessageStatus::Error,
+            MessageStatus::Ok,
+        ]
+        .into_iter()
+        .find(|status| status.emoji() == emoji)
+    }
+}
+
+impl fmt::Display for MessageStatus {
+    /// Writes the human label of the status, e.g. `Warning`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MessageStatus::None => "None",
+            MessageStatus::Info => "Info",
+            MessageStatus::Warn => "Warning",
+            MessageStatus::Error => "Error",
+            MessageStatus::Ok => "OK",
+        })
+    }
+}
+
+/// Parses a status case-insensitively from its name or a common synonym, for
+/// CLI flags and configuration files.
+///
+/// ```rust
+/// use shared_types::MessageStatus;
+///
+/// assert_eq!("warning".parse(), Ok(MessageStatus::Warn));
+/// assert_eq!("WARN".parse(), Ok(MessageStatus::Warn));
+/// assert_eq!(" Error ".parse(), Ok(MessageStatus::Error));
+/// assert_eq!("success".parse(), Ok(MessageStatus::Ok));
+/// assert!("fatal".parse::<MessageStatus>().is_err());
+/// assert_eq!(MessageStatus::Warn.to_string(), "Warning");
+/// assert_eq!(MessageStatus::Warn.as_str(), "warn");
+/// ```
+impl FromStr for MessageStatus {
+    type Err = MessageStatusError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(MessageStatus::None),
+            "info" | "information" => Ok(MessageStatus::Info),
+            "ok" | "success" => Ok(MessageStatus::Ok),
+            "warn" | "warning" => Ok(MessageStatus::Warn),
+            "error" | "err" => Ok(MessageStatus::Error),
+            _ => Err(MessageStatusError(value.to_string())),
+        }
+    }
+}
+
+/// Error returned when parsing an unknown [`MessageStatus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageStatusError(pub String);
+
+impl fmt::Display for MessageStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown message status {:?}", self.0)
+    }
 }
 
+impl std::error::Error for MessageStatusError {}
+
 /// Represents a message received from the queue to be sent to Telegram.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct TelegramQueueMessage {
-    #[serde(rename = "chatId")]
+    /// Schema version of the payload, see [`crate::schema::VersionedMessage`].
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    #[cfg_attr(feature = "serde", serde(alias = "chat_id"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
     pub chat_id: i64,
     pub message: String,
-    #[serde(rename = "forceSend")]
+    #[cfg_attr(feature = "serde", serde(alias = "force_send", alias = "force"))]
     pub force_send: bool,
+    /// Policy deciding `force_send` at dispatch time; `force_send` is the fallback.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "send_policy"))]
+    pub send_policy: Option<SendPolicy>,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+    /// Free-form routing hints and trace ids for the bot worker.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub metadata: HashMap<String, String>,
 }
 
 impl TelegramQueueMessage {
@@ -39,17 +324,164 @@ impl TelegramQueueMessage {
     /// Creates a new TelegramQueueMessage.
     pub fn new(chat_id: i64, message: String, force_send: bool) -> Self {
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             chat_id,
             message,
-            force_send
+            force_send,
+            send_policy: None,
+            status: MessageStatus::None,
+            dedup_key: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Returns whether the message should be force sent at time `now`.
+    ///
+    /// Evaluates the send policy when present, otherwise returns `force_send`.
+    pub fn should_force_send(&self, now: DateTime<Utc>) -> bool {
+        match &self.send_policy {
+            Some(policy) => policy.should_force_send(self.status, now),
+            None => self.force_send,
         }
     }
 
+    /// Returns true when the message status is at least as severe as `min`.
+    ///
+    /// Messages created without a status (e.g. via [`TelegramQueueMessage::new`])
+    /// have `MessageStatus::None` and only pass a `None` threshold.
+    pub fn is_at_least(&self, min: MessageStatus) -> bool {
+        self.status.at_least(min)
+    }
+
+    /// Defines a message in a `const` item, for fixed alerts like "service
+    /// restarted"; [`StaticTelegramMessage::to_message`] creates the message.
+    ///
+    /// ```rust
+    /// use shared_types::{MessageStatus, TelegramQueueMessage};
+    /// use shared_types::telegram::StaticTelegramMessage;
+    ///
+    /// const RESTARTED: StaticTelegramMessage =
+    ///     TelegramQueueMessage::const_new(-100123, "✅ - <i>api</i>\nservice restarted", false)
+    ///         .with_status(MessageStatus::Ok);
+    ///
+    /// let msg = RESTARTED.to_message();
+    /// assert_eq!(msg.chat_id, -100123);
+    /// assert_eq!(msg.status, MessageStatus::Ok);
+    /// assert_eq!(msg.job_name(), Some("api"));
+    /// ```
+    pub const fn const_new(chat_id: i64, message: &'static str, force_send: bool) -> StaticTelegramMessage {
+        StaticTelegramMessage::new(chat_id, message, force_send)
+    }
+
     /// Returns a builder for creating a formatted TelegramQueueMessage.
     pub fn builder(chat_id: i64) -> TelegramMessageBuilder {
         TelegramMessageBuilder::new(chat_id)
     }
 
+    /// Parses a message in the standard builder format back into a builder.
+    ///
+    /// The message must look like `{emoji} - <i>{job}</i>\n{content}` (the emoji
+    /// prefix is optional). An emoji that does not belong to the message status is
+    /// restored as an emoji override. A trailing line made only of hashtags is
+    /// restored as tags, and the chat id, force send settings, dedup key and metadata
+    /// are carried over.
+    ///
+    /// Returns `None` when the message does not follow the standard format.
+    ///
+    /// ```rust
+    /// use shared_types::{MessageStatus, TelegramQueueMessage};
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .status(MessageStatus::Error)
+    ///     .job_name("ftp")
+    ///     .content("connection failed")
+    ///     .build();
+    ///
+    /// let amended = msg
+    ///     .to_builder()
+    ///     .unwrap()
+    ///     .job_name("[prod] ftp")
+    ///     .build();
+    /// assert_eq!(amended.message, "🚨 - <i>[prod] ftp</i>\nconnection failed");
+    /// ```
+    pub fn to_builder(&self) -> Option<TelegramMessageBuilder> {
+        let (emoji, rest) = match self.message.split_once(" - <i>") {
+            Some((emoji, rest)) if is_emoji_prefix(emoji) => (Some(emoji), rest),
+            Some(_) => return None,
+            None => (None, self.message.strip_prefix("<i>")?),
+        };
+        let status = match emoji.and_then(MessageStatus::from_emoji) {
+            Some(parsed) if self.status.is_none() => parsed,
+            _ => self.status,
+        };
+        let emoji_override = emoji.filter(|emoji| *emoji != status.emoji());
+        let (job_name, body) = rest.split_once("</i>\n")?;
+
+        let (content, tags) = match body.rsplit_once('\n') {
+            Some((content, last_line)) if is_tag_line(last_line) => {
+                (content, last_line.split(' ').collect::<Vec<_>>())
+            }
+            _ => (body, Vec::new()),
+        };
+
+        let mut builder = TelegramMessageBuilder::new(self.chat_id)
+            .status(status)
+            .job_name(job_name)
+            .content(content)
+            .force_send(self.force_send)
+            .tags(tags);
+        if let Some(send_policy) = &self.send_policy {
+            builder.send_policy = Some(send_policy.clone());
+        }
+        builder.metadata = self.metadata.clone();
+        if let Some(emoji) = emoji_override {
+            builder = builder.emoji(emoji);
+        }
+        if let Some(dedup_key) = &self.dedup_key {
+            builder = builder.dedup_key(dedup_key.clone());
+        }
+
+        Some(builder)
+    }
+
+    /// Returns the job name of a message in the standard builder format, as it
+    /// appears in the message (HTML escaped).
+    ///
+    /// ```rust
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let msg = TelegramQueueMessage::builder(123).job_name("ftp").content("done").build();
+    /// assert_eq!(msg.job_name(), Some("ftp"));
+    /// assert_eq!(TelegramQueueMessage::new(123, "plain".into(), false).job_name(), None);
+    /// ```
+    pub fn job_name(&self) -> Option<&str> {
+        let rest = match self.message.split_once(" - <i>") {
+            Some((emoji, rest)) if is_emoji_prefix(emoji) => rest,
+            Some(_) => return None,
+            None => self.message.strip_prefix("<i>")?,
+        };
+        rest.split_once("</i>\n").map(|(job_name, _)| job_name)
+    }
+
+    /// Checks that the message fits in [`TELEGRAM_MAX_MESSAGE_LENGTH`] characters.
+    ///
+    /// ```rust
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// assert!(TelegramQueueMessage::new(123, "ok".into(), false).check_length().is_ok());
+    /// assert!(TelegramQueueMessage::new(123, "x".repeat(5000), false).check_length().is_err());
+    /// ```
+    pub fn check_length(&self) -> Result<(), SharedTypesError> {
+        let length = self.message.chars().count();
+        if length > TELEGRAM_MAX_MESSAGE_LENGTH {
+            return Err(SharedTypesError::TelegramMessageTooLong {
+                length,
+                max: TELEGRAM_MAX_MESSAGE_LENGTH,
+            });
+        }
+        Ok(())
+    }
+
     /// Sanitizes the message content for safe Telegram display.
     ///
     /// This function performs the following steps:
@@ -57,52 +489,188 @@ impl TelegramQueueMessage {
     ///    - If the message is longer than allowed, appends "..." to indicate truncation.
     /// 2. Escapes all HTML special characters to prevent injection of arbitrary HTML or scripts.
     /// 3. Re-enables a limited set of allowed Telegram HTML tags for basic formatting:
-    ///    `b, strong, i, em, u, ins, s, strike, del, code, pre, blockquote, tg-spoiler`.
+    ///    `b, strong, i, em, u, ins, s, strike, del, code, pre, blockquote, tg-spoiler`,
+    ///    plus `<blockquote expandable>` as produced by the builder for details and
+    ///    `<a href="tg://user?id=N">…</a>` user mentions.
     ///
     /// Notes:
-    /// - Attributes on tags are not allowed, and unsupported tags remain escaped.
+    /// - Other attributes on tags are not allowed, and unsupported tags remain escaped.
     /// ```
     pub fn sanitize_message(&mut self, max_message_length: usize) {
-        let overflow_length = self.message.len() > max_message_length;
+        let overflow_length = self.message.chars().count() > max_message_length;
         let trimmed: String = self
             .message
             .chars()
             .take(max_message_length)
             .collect();
 
-        let mut escaped = encode_text(&trimmed).to_string();
-        escaped = if overflow_length {
-            format!("{}...", escaped)
-        } else {
-            escaped
-        };
+        let mut escaped = escape_allowing_tags(&trimmed);
+        if overflow_length {
+            escaped.push_str(ELLIPSIS);
+        }
 
-        let allowed_simple = [
-            "b", "strong", "i", "em", "u", "ins",
-            "s", "strike", "del", "code", "pre",
-            "blockquote", "tg-spoiler",
-        ];
+        self.message = escaped;
+    }
+}
 
-        for tag in &allowed_simple {
-            let open = format!("&lt;{}&gt;", tag);
-            let close = format!("&lt;/{}&gt;", tag);
+/// Writes a one-line preview for log lines and spans:
+/// `[{chat_id} {STATUS} {job}] {text}`, with the tags stripped and the text
+/// truncated to [`PREVIEW_LENGTH`] characters, or to the precision when given.
+///
+/// ```rust
+/// use shared_types::{MessageStatus, TelegramQueueMessage};
+///
+/// let msg = TelegramQueueMessage::builder(123456)
+///     .status(MessageStatus::Error)
+///     .job_name("ftp")
+///     .content("<b>failed</b>: timeout after 30s\nretrying")
+///     .build();
+/// assert_eq!(msg.to_string(), "[123456 ERROR ftp] failed: timeout after 30s retrying");
+/// assert_eq!(format!("{:.16}", msg), "[123456 ERROR ftp] failed: timeout…");
+/// assert_eq!(TelegramQueueMessage::new(42, "a &amp; b".into(), false).to_string(), "[42] a & b");
+/// ```
+impl fmt::Display for TelegramQueueMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}", self.chat_id)?;
+        if !self.status.is_none() {
+            write!(f, " {}", self.status.as_str().to_ascii_uppercase())?;
+        }
+        let body = match self.job_name() {
+            Some(job_name) => {
+                write!(f, " {}", render::decode_entities(job_name))?;
+                self.message.split_once("</i>\n").map_or("", |(_, body)| body)
+            }
+            None => self.message.as_str(),
+        };
+        let text = render::preview(body, f.precision().unwrap_or(PREVIEW_LENGTH));
+        if text.is_empty() {
+            f.write_str("]")
+        } else {
+            write!(f, "] {}", text)
+        }
+    }
+}
+
+/// A [`TelegramQueueMessage`] defined at compile time.
+///
+/// A message owns its text and metadata, so it cannot be built in a `const`;
+/// this holds the `'static` text and flags until [`to_message`](Self::to_message).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticTelegramMessage {
+    pub chat_id: i64,
+    pub message: &'static str,
+    pub force_send: bool,
+    pub status: MessageStatus,
+}
 
-            escaped = escaped
-                .replace(&open, &format!("<{}>", tag))
-                .replace(&close, &format!("</{}>", tag));
+impl StaticTelegramMessage {
+    /// Creates a message definition without status.
+    pub const fn new(chat_id: i64, message: &'static str, force_send: bool) -> Self {
+        Self {
+            chat_id,
+            message,
+            force_send,
+            status: MessageStatus::None,
         }
+    }
 
-        self.message = escaped;
+    /// Sets the status the message is built with.
+    pub const fn with_status(mut self, status: MessageStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Creates the queue message.
+    pub fn to_message(&self) -> TelegramQueueMessage {
+        let mut message = TelegramQueueMessage::new(self.chat_id, self.message.to_string(), self.force_send);
+        message.status = self.status;
+        message
+    }
+}
+
+impl From<StaticTelegramMessage> for TelegramQueueMessage {
+    fn from(message: StaticTelegramMessage) -> Self {
+        message.to_message()
+    }
+}
+
+/// A message addressed to several chats, e.g. the team channel and the on-call DM.
+///
+/// Serializes as a regular [`TelegramQueueMessage`] for the primary chat plus an
+/// `alsoTo` list, so consumers unaware of broadcasts still deliver to the primary chat.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TelegramBroadcastMessage {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub message: TelegramQueueMessage,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty", alias = "also_to"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "Array<number>"))]
+    pub also_to: Vec<i64>,
+}
+
+impl TelegramBroadcastMessage {
+    /// Returns every recipient chat id, the primary chat first.
+    pub fn chat_ids(&self) -> Vec<i64> {
+        std::iter::once(self.message.chat_id)
+            .chain(self.also_to.iter().copied())
+            .collect()
+    }
+
+    /// Splits the broadcast into one message per recipient.
+    pub fn into_messages(self) -> Vec<TelegramQueueMessage> {
+        let mut messages = Vec::with_capacity(self.also_to.len() + 1);
+        for chat_id in self.also_to {
+            messages.push(TelegramQueueMessage {
+                chat_id,
+                ..self.message.clone()
+            });
+        }
+        messages.insert(0, self.message);
+        messages
     }
 }
 
 /// A builder for creating formatted TelegramQueueMessage objects.
+///
+/// The [`Default`] builder has chat id `0`, for templates kept in config
+/// structs; set the chat with [`chat_id`](Self::chat_id) before building.
+///
+/// ```rust
+/// use shared_types::{MessageStatus, TelegramMessageBuilder};
+///
+/// let template = TelegramMessageBuilder::default().status(MessageStatus::Warn).job_name("backup");
+/// let msg = template.chat_id(-100123).content("slow").build();
+/// assert_eq!(msg.chat_id, -100123);
+/// assert_eq!(msg.status, MessageStatus::Warn);
+/// ```
+#[derive(Debug, Clone, Default)]
 pub struct TelegramMessageBuilder {
     chat_id: i64,
+    also_to: Vec<i64>,
     status: MessageStatus,
-    job_name: String,
-    content: String,
+    emoji: Option<String>,
+    job_name: JobName,
+    summary: String,
+    body: Vec<Fragment>,
+    details: String,
     force_send: bool,
+    send_policy: Option<SendPolicy>,
+    tags: Vec<String>,
+    dedup_key: Option<DedupKey>,
+    metadata: HashMap<String, String>,
+    sanitize: Option<SanitizeOptions>,
+}
+
+/// How the builder fills in the message dedup key.
+#[derive(Debug, Clone)]
+enum DedupKey {
+    Explicit(String),
+    Derived,
 }
 
 impl TelegramMessageBuilder {
@@ -110,11 +678,44 @@ impl TelegramMessageBuilder {
     pub fn new(chat_id: i64) -> Self {
         Self {
             chat_id,
-            status: MessageStatus::None,
-            job_name: String::new(),
-            content: String::new(),
-            force_send: false,
+            ..Self::default()
+        }
+    }
+
+    /// Initializes a new builder for the chat mapped to `channel` in the directory.
+    ///
+    /// Returns `None` when the channel is not in the directory.
+    ///
+    /// ```rust
+    /// use shared_types::{ChatDirectory, TelegramMessageBuilder};
+    ///
+    /// let directory = ChatDirectory::new().with_channel("ops", -100123);
+    /// let msg = TelegramMessageBuilder::for_channel(&directory, "ops")
+    ///     .unwrap()
+    ///     .job_name("ftp")
+    ///     .content("done")
+    ///     .build();
+    /// assert_eq!(msg.chat_id, -100123);
+    /// ```
+    pub fn for_channel(directory: &ChatDirectory, channel: &str) -> Option<Self> {
+        directory.chat_id(channel).map(Self::new)
+    }
+
+    /// Sets the primary chat.
+    pub fn chat_id(mut self, chat_id: i64) -> Self {
+        self.chat_id = chat_id;
+        self
+    }
+
+    /// Adds another recipient chat, delivered by [`build_all`](Self::build_all)
+    /// and [`build_broadcast`](Self::build_broadcast).
+    ///
+    /// [`build`](Self::build) only targets the primary chat.
+    pub fn also_to(mut self, chat_id: i64) -> Self {
+        if chat_id != self.chat_id && !self.also_to.contains(&chat_id) {
+            self.also_to.push(chat_id);
         }
+        self
     }
 
     /// Sets the status level, which adds an emoji prefix.
@@ -123,15 +724,123 @@ impl TelegramMessageBuilder {
         self
     }
 
+    /// Overrides the status-derived emoji prefix, e.g. `🛰️` for deploys.
+    ///
+    /// Only the displayed emoji changes: the status is still stored on the
+    /// message and used for severity filtering.
+    pub fn emoji(mut self, emoji: impl Into<String>) -> Self {
+        self.emoji = Some(emoji.into());
+        self
+    }
+
     /// Sets the job name, which will be formatted in italics.
-    pub fn job_name(mut self, job_name: impl Into<String>) -> Self {
+    pub fn job_name(mut self, job_name: impl Into<JobName>) -> Self {
         self.job_name = job_name.into();
         self
     }
 
     /// Sets the message content.
+    ///
+    /// Content is HTML: it is used verbatim, or sanitized with the allowed tags
+    /// when the builder sanitizes. Calling it again replaces the content but keeps
+    /// its position relative to code snippets.
     pub fn content(mut self, content: impl Into<String>) -> Self {
-        self.content = content.into();
+        let content = content.into();
+        match self.body.iter_mut().find(|fragment| matches!(fragment, Fragment::Content(_))) {
+            Some(fragment) => *fragment = Fragment::Content(content),
+            None => self.body.push(Fragment::Content(content)),
+        }
+        self
+    }
+
+    /// Appends an inline `<code>` snippet after the content, escaping its text.
+    ///
+    /// Use it for command names, paths or short output that must not be parsed as HTML.
+    pub fn code(mut self, snippet: impl Into<String>) -> Self {
+        self.body.push(Fragment::Code(snippet.into()));
+        self
+    }
+
+    /// Appends an inline mention of a Telegram user linking to `tg://user?id={user_id}`.
+    ///
+    /// The display name is escaped. Telegram notifies the user even when they have
+    /// no public username.
+    ///
+    /// ```rust
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .job_name("backup")
+    ///     .content("on call:")
+    ///     .mention_user(42, "Ada <ops>")
+    ///     .mention_username("@grace_h")
+    ///     .build();
+    /// assert_eq!(
+    ///     msg.message,
+    ///     "<i>backup</i>\non call: <a href=\"tg://user?id=42\">Ada &lt;ops&gt;</a> @grace_h"
+    /// );
+    /// ```
+    pub fn mention_user(mut self, user_id: i64, display_name: impl Into<String>) -> Self {
+        self.body.push(Fragment::MentionUser(user_id, display_name.into()));
+        self
+    }
+
+    /// Appends an inline `@username` mention.
+    ///
+    /// A leading `@` is optional; characters not allowed in Telegram usernames are
+    /// removed and an empty result is ignored.
+    pub fn mention_username(mut self, username: impl Into<String>) -> Self {
+        let username: String = username
+            .into()
+            .trim()
+            .trim_start_matches('@')
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if !username.is_empty() {
+            self.body.push(Fragment::MentionUsername(username));
+        }
+        self
+    }
+
+    /// Appends a `<pre>` block on its own lines, escaping its text.
+    ///
+    /// Use it for raw command output. When the builder sanitizes with a maximum
+    /// length, code blocks are truncated right after the details.
+    ///
+    /// ```rust
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .job_name("ftp")
+    ///     .content("upload failed running")
+    ///     .code("put a.csv")
+    ///     .code_block("550 <a.csv>: permission denied")
+    ///     .build();
+    /// assert_eq!(
+    ///     msg.message,
+    ///     "<i>ftp</i>\nupload failed running <code>put a.csv</code>\n<pre>550 &lt;a.csv&gt;: permission denied</pre>"
+    /// );
+    /// ```
+    pub fn code_block(mut self, snippet: impl Into<String>) -> Self {
+        self.body.push(Fragment::CodeBlock(snippet.into()));
+        self
+    }
+
+    /// Sets a summary line, shown right below the job name before any other content.
+    ///
+    /// Use it for the conclusion of the alert so it is visible on the first screen.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = summary.into();
+        self
+    }
+
+    /// Sets details (e.g. a log tail), rendered last in a collapsed expandable blockquote.
+    ///
+    /// When the builder sanitizes with a maximum length, details are truncated
+    /// first so the summary and content stay intact.
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = details.into();
         self
     }
 
@@ -141,48 +850,674 @@ impl TelegramMessageBuilder {
         self
     }
 
+    /// Attaches a send policy evaluated by the dispatcher.
+    ///
+    /// Also sets `force_send` to the policy fallback, see
+    /// [`SendPolicy::fallback_force_send`].
+    pub fn send_policy(mut self, send_policy: SendPolicy) -> Self {
+        self.force_send = send_policy.fallback_force_send();
+        self.send_policy = Some(send_policy);
+        self
+    }
+
+    /// Adds a single hashtag, rendered at the end of the message.
+    ///
+    /// A leading `#` is optional. Characters Telegram does not accept in hashtags
+    /// are replaced with `_`; tags that end up empty are ignored.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = normalize_tag(&tag.into());
+        if !tag.is_empty() && !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+
+    /// Adds several hashtags at once, e.g. `.tags(["backup", "prod"])` renders `#backup #prod`.
+    pub fn tags<I, S>(self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        tags.into_iter().fold(self, |builder, tag| builder.tag(tag))
+    }
+
+    /// Sets an explicit dedup key for the message.
+    pub fn dedup_key(mut self, dedup_key: impl Into<String>) -> Self {
+        self.dedup_key = Some(DedupKey::Explicit(dedup_key.into()));
+        self
+    }
+
+    /// Derives the dedup key from the job name, status and content at build time.
+    ///
+    /// See [`derive_dedup_key`] for the exact derivation.
+    pub fn derived_dedup_key(mut self) -> Self {
+        self.dedup_key = Some(DedupKey::Derived);
+        self
+    }
+
+    /// Attaches a metadata entry (routing hint, trace id, ...) to the message.
+    pub fn meta(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sanitizes the message at build time, truncating it to `max_length`.
+    ///
+    /// See [`TelegramQueueMessage::sanitize_message`] for what sanitizing does.
+    /// Details, then code blocks, then content, then the summary are shortened
+    /// first, then the tags and the job name; a part with no room left besides
+    /// the ellipsis is dropped along with its line. The message never exceeds
+    /// `max_length` characters before escaping:
+    ///
+    /// ```rust
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .job_name("ftp")
+    ///     .code_block("x".repeat(50))
+    ///     .content("done")
+    ///     .sanitize(30)
+    ///     .build();
+    /// assert_eq!(msg.message, "<i>ftp</i>\ndone");
+    ///
+    /// for max_length in [20, 100, 4096] {
+    ///     let msg = TelegramQueueMessage::builder(1)
+    ///         .job_name("j".repeat(5000))
+    ///         .content("done")
+    ///         .tag("backup")
+    ///         .sanitize(max_length)
+    ///         .build();
+    ///     assert!(msg.message.chars().count() <= max_length);
+    /// }
+    /// ```
+    pub fn sanitize(mut self, max_length: usize) -> Self {
+        self.sanitize = Some(SanitizeOptions::new(max_length));
+        self
+    }
+
+    /// Builds the message and sanitizes it with the given options.
+    ///
+    /// ```rust
+    /// use shared_types::TelegramQueueMessage;
+    /// use shared_types::telegram::{SanitizeOptions, TELEGRAM_MAX_MESSAGE_LENGTH};
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .job_name("ftp")
+    ///     .content("<b>failed</b> <script>x</script>")
+    ///     .build_sanitized(SanitizeOptions::new(TELEGRAM_MAX_MESSAGE_LENGTH));
+    /// assert!(msg.message.contains("&lt;script&gt;"));
+    /// ```
+    pub fn build_sanitized(mut self, options: SanitizeOptions) -> TelegramQueueMessage {
+        self.sanitize = Some(options);
+        self.build()
+    }
+
+    /// Builds a broadcast message for the primary chat and every `also_to` chat.
+    pub fn build_broadcast(mut self) -> TelegramBroadcastMessage {
+        let also_to = std::mem::take(&mut self.also_to);
+        TelegramBroadcastMessage {
+            message: self.build(),
+            also_to,
+        }
+    }
+
+    /// Builds one message per recipient, the primary chat first.
+    ///
+    /// ```rust
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let messages = TelegramQueueMessage::builder(100)
+    ///     .also_to(200)
+    ///     .job_name("ftp")
+    ///     .content("down")
+    ///     .build_all();
+    /// assert_eq!(messages.iter().map(|m| m.chat_id).collect::<Vec<_>>(), [100, 200]);
+    /// ```
+    pub fn build_all(self) -> Vec<TelegramQueueMessage> {
+        self.build_broadcast().into_messages()
+    }
+
     /// Builds the TelegramQueueMessage with the specified formatting.
     ///
     /// The resulting message format is:
     /// `{emoji} - <i>{job_name}</i>\n{content}`
+    ///
+    /// A summary is placed on the line before the content and details are
+    /// appended in a `<blockquote expandable>`. When tags are set, a final line
+    /// `#tag1 #tag2` is appended. When sanitizing was requested, the message is
+    /// sanitized last.
+    ///
+    /// ```rust
+    /// use shared_types::TelegramQueueMessage;
+    ///
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .job_name("backup")
+    ///     .summary("snapshot failed")
+    ///     .details("line 1\nline 2")
+    ///     .build();
+    /// assert_eq!(
+    ///     msg.message,
+    ///     "<i>backup</i>\nsnapshot failed\n<blockquote expandable>line 1\nline 2</blockquote>"
+    /// );
+    /// ```
     pub fn build(self) -> TelegramQueueMessage {
-        let status_prefix = if matches!(self.status, MessageStatus::None) {
-            "".to_string()
-        } else {
-            format!("{} - ", self.status.emoji())
-        };
+        let message = render_segments(self.segments(), self.sanitize);
 
-        let message = format!("{}<i>{}</i>\n{}", status_prefix, self.job_name, self.content);
+        let dedup_key = match &self.dedup_key {
+            Some(DedupKey::Explicit(key)) => Some(key.clone()),
+            Some(DedupKey::Derived) => {
+                Some(derive_dedup_key(self.job_name.as_str(), self.status, self.content_text()))
+            }
+            None => None,
+        };
 
         TelegramQueueMessage {
+            schema_version: CURRENT_SCHEMA_VERSION,
             chat_id: self.chat_id,
             message,
             force_send: self.force_send,
+            send_policy: self.send_policy,
+            status: self.status,
+            dedup_key,
+            metadata: self.metadata,
+        }
+    }
+
+    /// Returns the text set with [`content`](Self::content), or an empty string.
+    fn content_text(&self) -> &str {
+        self.body
+            .iter()
+            .find_map(|fragment| match fragment {
+                Fragment::Content(content) => Some(content.as_str()),
+                _ => None,
+            })
+            .unwrap_or("")
+    }
+
+    /// Lays out the message as segments, in rendering order.
+    fn segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+
+        match &self.emoji {
+            Some(emoji) => segments.push(Segment::markup(emoji, Escape::Text, " - ")),
+            None if self.status.is_none() => {}
+            None => segments.push(Segment::markup(self.status.emoji(), Escape::Text, " - ")),
+        }
+        segments.push(Segment::truncatable("<i>", self.job_name.as_str(), "</i>\n", Escape::Html, 5));
+
+        // Whether the body already has a line, and whether that line can take inline fragments.
+        let mut has_body = false;
+        let mut line_open = false;
+        let mut separator = |inline: bool, line_open: &mut bool| {
+            let separator = if inline && *line_open {
+                " "
+            } else if has_body {
+                "\n"
+            } else {
+                ""
+            };
+            has_body = true;
+            *line_open = inline;
+            separator
+        };
+
+        if !self.summary.is_empty() {
+            let before = separator(false, &mut line_open);
+            segments.push(Segment::truncatable(before, &self.summary, "", Escape::Html, 3));
+        }
+        for fragment in &self.body {
+            let segment = match fragment {
+                Fragment::Content(content) if !content.is_empty() => {
+                    let before = separator(true, &mut line_open);
+                    Segment::truncatable(before, content, "", Escape::Html, 2)
+                }
+                Fragment::Code(snippet) if !snippet.is_empty() => {
+                    let before = format!("{}<code>", separator(true, &mut line_open));
+                    Segment::truncatable(&before, snippet, "</code>", Escape::Text, 2)
+                }
+                Fragment::MentionUser(user_id, display_name) => {
+                    let before = format!(
+                        "{}{}{}{}",
+                        separator(true, &mut line_open),
+                        MENTION_OPEN,
+                        user_id,
+                        MENTION_OPEN_END
+                    );
+                    Segment {
+                        before,
+                        ..Segment::markup(display_name, Escape::Text, MENTION_CLOSE)
+                    }
+                }
+                Fragment::MentionUsername(username) => {
+                    let before = format!("{}@", separator(true, &mut line_open));
+                    Segment {
+                        before,
+                        ..Segment::markup(username, Escape::Text, "")
+                    }
+                }
+                Fragment::CodeBlock(snippet) if !snippet.is_empty() => {
+                    let before = format!("{}<pre>", separator(false, &mut line_open));
+                    Segment::truncatable(&before, snippet, "</pre>", Escape::Text, 1)
+                }
+                _ => continue,
+            };
+            segments.push(segment);
+        }
+        if !self.details.is_empty() {
+            let before = format!("{}{}", separator(false, &mut line_open), DETAILS_OPEN);
+            segments.push(Segment::truncatable(&before, &self.details, DETAILS_CLOSE, Escape::Html, 0));
+        }
+
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| format!("#{}", tag)).collect();
+            segments.push(Segment::truncatable("\n", &tags.join(" "), "", Escape::Text, 4));
+        }
+
+        segments
+    }
+}
+
+/// Longest error block rendered by [`TelegramMessageBuilder::anyhow_error`].
+#[cfg(feature = "anyhow")]
+const ANYHOW_MAX_LENGTH: usize = 3000;
+
+#[cfg(feature = "anyhow")]
+impl TelegramMessageBuilder {
+    /// Appends `err` as a `<pre>` block: the error, each cause on a `caused by:`
+    /// line, then the backtrace when one was captured (`RUST_BACKTRACE=1`),
+    /// truncated to stay well within a Telegram message.
+    ///
+    /// ```rust
+    /// use anyhow::Context;
+    /// use shared_types::{MessageStatus, TelegramQueueMessage};
+    ///
+    /// let err = Err::<(), _>(anyhow::anyhow!("connection refused"))
+    ///     .context("uploading a.csv")
+    ///     .unwrap_err();
+    /// let msg = TelegramQueueMessage::builder(123)
+    ///     .status(MessageStatus::Error)
+    ///     .job_name("ftp")
+    ///     .anyhow_error(&err)
+    ///     .build();
+    /// assert!(msg.message.starts_with("🚨 - <i>ftp</i>\n<pre>uploading a.csv\ncaused by: connection refused"));
+    /// ```
+    pub fn anyhow_error(self, err: &anyhow::Error) -> Self {
+        let mut text = err
+            .chain()
+            .enumerate()
+            .map(|(index, cause)| match index {
+                0 => cause.to_string(),
+                _ => format!("caused by: {}", cause),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let backtrace = err.backtrace();
+        if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+            text.push_str(&format!("\n\nbacktrace:\n{}", backtrace));
         }
+        self.code_block(crate::render::truncate(&text, ANYHOW_MAX_LENGTH))
     }
 }
 
+/// Part of the message body added through the builder, in call order.
+#[derive(Debug, Clone)]
+enum Fragment {
+    /// HTML content set with `content`.
+    Content(String),
+    /// Plain text rendered inline in `<code>`.
+    Code(String),
+    /// Plain text rendered as a `<pre>` block.
+    CodeBlock(String),
+    /// Inline `tg://user` link with the user id and display name.
+    MentionUser(i64, String),
+    /// Inline `@username`, already normalized.
+    MentionUsername(String),
+}
+
+/// How the caller supplied text of a [`Segment`] is escaped.
+#[derive(Clone, Copy)]
+enum Escape {
+    /// HTML, kept verbatim or sanitized with the allowed tags when the builder sanitizes.
+    Html,
+    /// Plain text, always escaped.
+    Text,
+}
+
+/// A piece of a built message: caller supplied text wrapped in builder markup.
+struct Segment {
+    before: String,
+    text: String,
+    after: String,
+    escape: Escape,
+    /// Order in which segments are truncated to fit the maximum length, lowest
+    /// first; `None` is never truncated.
+    truncate_rank: Option<u8>,
+}
+
+impl Segment {
+    fn markup(text: &str, escape: Escape, after: &str) -> Self {
+        Self {
+            before: String::new(),
+            text: text.to_string(),
+            after: after.to_string(),
+            escape,
+            truncate_rank: None,
+        }
+    }
+
+    fn truncatable(before: &str, text: &str, after: &str, escape: Escape, rank: u8) -> Self {
+        Self {
+            before: before.to_string(),
+            text: text.to_string(),
+            after: after.to_string(),
+            escape,
+            truncate_rank: Some(rank),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.before.chars().count() + self.text.chars().count() + self.after.chars().count()
+    }
+}
+
+/// Renders segments into the final message, truncating and sanitizing when requested.
+///
+/// When the markup left after truncating still does not fit, e.g. with many
+/// mentions, the message falls back to its plain text cut to the maximum length.
+fn render_segments(mut segments: Vec<Segment>, sanitize: Option<SanitizeOptions>) -> String {
+    let hard_cut = sanitize
+        .map(|options| options.max_length)
+        .filter(|&max_length| !truncate_segments(&mut segments, max_length));
+
+    let mut message = String::new();
+    for segment in &segments {
+        message.push_str(&segment.before);
+        match (segment.escape, sanitize) {
+            (Escape::Text, _) => message.push_str(&render::escape_text(&segment.text)),
+            (Escape::Html, Some(_)) => message.push_str(&escape_allowing_tags(&segment.text)),
+            (Escape::Html, None) => message.push_str(&segment.text),
+        }
+        message.push_str(&segment.after);
+    }
+    match hard_cut {
+        Some(max_length) => {
+            let text = render::truncate(&render::html_to_text(&message), max_length);
+            render::escape_text(&text).into_owned()
+        }
+        None => message,
+    }
+}
+
+/// Shortens truncatable segments, in rank order, until the raw text fits
+/// `max_length`, returning whether it does.
+///
+/// Lengths are counted in characters before escaping, like in
+/// [`TelegramQueueMessage::sanitize_message`]. Segments that cannot keep any text
+/// besides the ellipsis are dropped together with their markup and separator.
+fn truncate_segments(segments: &mut [Segment], max_length: usize) -> bool {
+    let total: usize = segments.iter().map(Segment::len).sum();
+    let mut excess = total.saturating_sub(max_length);
+    let ellipsis_length = ELLIPSIS.chars().count();
+    let mut dropped = vec![false; segments.len()];
+
+    let mut order: Vec<usize> = (0..segments.len())
+        .filter(|&index| segments[index].truncate_rank.is_some())
+        .collect();
+    order.sort_by_key(|&index| (segments[index].truncate_rank, Reverse(index)));
+
+    for index in order {
+        if excess == 0 {
+            break;
+        }
+
+        let segment = &mut segments[index];
+        let text_length = segment.text.chars().count();
+        let keep = text_length.saturating_sub(excess + ellipsis_length);
+        if keep == 0 {
+            excess = excess.saturating_sub(segment.len());
+            dropped[index] = true;
+        } else {
+            let kept: String = segment.text.chars().take(keep).collect();
+            segment.text = format!("{}{}", kept, ELLIPSIS);
+            excess = 0;
+        }
+    }
+    drop_segments(segments, &dropped);
+    excess == 0
+}
+
+/// Empties the dropped segments, giving the next kept body segment the
+/// separator of the dropped run so no blank line or stray space is left.
+///
+/// At the start of the body the run has no separator, which removes the one of
+/// the next segment; otherwise a line break wins over a space.
+fn drop_segments(segments: &mut [Segment], dropped: &[bool]) {
+    // Separators from strongest to weakest.
+    const SEPARATORS: [&str; 3] = ["", "\n", " "];
+    let strength = |separator: &str| SEPARATORS.iter().position(|s| *s == separator);
+
+    let mut pending: Option<&str> = None;
+    for (segment, &dropped) in segments.iter_mut().zip(dropped) {
+        let separator = SEPARATORS[1..]
+            .iter()
+            .find(|separator| segment.before.starts_with(*separator))
+            .copied()
+            .unwrap_or("");
+        if dropped {
+            pending = Some(match pending {
+                Some(pending) if strength(pending) <= strength(separator) => pending,
+                _ => separator,
+            });
+            *segment = Segment::markup("", Escape::Text, "");
+        } else if let Some(pending) = pending.take() {
+            // Segments without separator open the body, e.g. after a dropped job name.
+            if !separator.is_empty() {
+                segment.before = format!("{}{}", pending, &segment.before[separator.len()..]);
+            }
+        }
+    }
+}
+
+/// Escapes all HTML, then re-enables the tags allowed by [`TelegramQueueMessage::sanitize_message`].
+fn escape_allowing_tags(text: &str) -> String {
+    let mut escaped = render::escape_text(text).to_string();
+
+    let allowed_simple = [
+        "b", "strong", "i", "em", "u", "ins",
+        "s", "strike", "del", "code", "pre",
+        "blockquote", "tg-spoiler",
+    ];
+
+    for tag in &allowed_simple {
+        let open = format!("&lt;{}&gt;", tag);
+        let close = format!("&lt;/{}&gt;", tag);
+
+        escaped = escaped
+            .replace(&open, &format!("<{}>", tag))
+            .replace(&close, &format!("</{}>", tag));
+    }
+
+    escaped = escaped.replace("&lt;blockquote expandable&gt;", DETAILS_OPEN);
+    restore_user_mentions(&escaped)
+}
+
+/// Re-enables escaped `<a href="tg://user?id=N">…</a>` user mentions.
+///
+/// Only links with a numeric user id and a matching closing tag are restored;
+/// anything else stays escaped.
+fn restore_user_mentions(escaped: &str) -> String {
+    const ESCAPED_OPEN: &str = "&lt;a href=\"tg://user?id=";
+    const ESCAPED_OPEN_END: &str = "\"&gt;";
+    const ESCAPED_CLOSE: &str = "&lt;/a&gt;";
+
+    let mut restored = String::with_capacity(escaped.len());
+    let mut rest = escaped;
+    while let Some(start) = rest.find(ESCAPED_OPEN) {
+        let after_open = &rest[start + ESCAPED_OPEN.len()..];
+        let digits = after_open
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after_open.len());
+        let user_id = &after_open[..digits];
+        let mention = after_open[digits..]
+            .strip_prefix(ESCAPED_OPEN_END)
+            .filter(|_| !user_id.is_empty())
+            .and_then(|inner| inner.split_once(ESCAPED_CLOSE));
+
+        match mention {
+            Some((display_name, tail)) => {
+                restored.push_str(&rest[..start]);
+                restored.push_str(&format!(
+                    "{}{}{}{}{}",
+                    MENTION_OPEN, user_id, MENTION_OPEN_END, display_name, MENTION_CLOSE
+                ));
+                rest = tail;
+            }
+            None => {
+                let skip = start + ESCAPED_OPEN.len();
+                restored.push_str(&rest[..skip]);
+                rest = &rest[skip..];
+            }
+        }
+    }
+    restored.push_str(rest);
+    restored
+}
+
+/// Derives a stable dedup key from the job name, status and content of a message.
+///
+/// The key is a 16 character hex string that is identical across processes and
+/// releases for the same inputs, so independent producers agree on it. The
+/// status enters the hash by its [`as_str`](MessageStatus::as_str) wire name.
+///
+/// ```rust
+/// use shared_types::MessageStatus;
+/// use shared_types::telegram::derive_dedup_key;
+///
+/// let key = derive_dedup_key("backup", MessageStatus::Error, "disk full");
+/// assert_eq!(key, "46d5b9696d9daa5f");
+/// assert_ne!(key, derive_dedup_key("backup", MessageStatus::Warn, "disk full"));
+/// ```
+pub fn derive_dedup_key(job_name: &str, status: MessageStatus, content: &str) -> String {
+    stable_hash_hex([job_name, status.as_str(), content])
+}
+
+const DETAILS_OPEN: &str = "<blockquote expandable>";
+const DETAILS_CLOSE: &str = "</blockquote>";
+const ELLIPSIS: &str = "...";
+const MENTION_OPEN: &str = "<a href=\"tg://user?id=";
+const MENTION_OPEN_END: &str = "\">";
+const MENTION_CLOSE: &str = "</a>";
+
+/// Normalizes a tag into a valid Telegram hashtag body (without the leading `#`).
+fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// Returns true when `prefix` can be an emoji prefix: short and without spaces or markup.
+fn is_emoji_prefix(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && prefix.chars().count() <= 8
+        && !prefix.chars().any(|c| c.is_whitespace() || c.is_ascii_alphanumeric() || c == '<')
+}
+
+/// Returns true when `line` is a space separated list of normalized hashtags.
+fn is_tag_line(line: &str) -> bool {
+    !line.is_empty()
+        && line.split(' ').all(|token| {
+            token
+                .strip_prefix('#')
+                .is_some_and(|tag| !tag.is_empty() && normalize_tag(tag) == tag)
+        })
+}
+
+/// Escapes `&`, `<` and `>` so `text` shows verbatim in a Telegram HTML message.
+///
+/// ```rust
+/// use shared_types::telegram::escape_html;
+///
+/// assert_eq!(escape_html("a < b && c"), "a &lt; b &amp;&amp; c");
+/// ```
+pub fn escape_html(text: &str) -> Cow<'_, str> {
+    render::escape_text(text)
+}
+
+/// Escapes `text` for a double quoted attribute value, e.g. a link `href`.
+pub fn escape_html_attribute(value: &str) -> Cow<'_, str> {
+    render::escape_attribute(value)
+}
+
+/// Builds a Telegram HTML string from a template literal checked at compile
+/// time, with the `derive` feature.
+///
+/// The template takes `format!` placeholders and arguments. It may only use
+/// the tags Telegram accepts, properly nested, and must escape a literal `&`,
+/// `<` or `>`; otherwise the build fails. Interpolated values are escaped, so
+/// they always show as text.
+///
+/// ```rust
+/// use shared_types::telegram_html;
+///
+/// let job = "ftp <prod>";
+/// let error = "timeout & retry";
+/// let html = telegram_html!("<b>{}</b> failed: <code>{error}</code>", job);
+/// assert_eq!(html, "<b>ftp &lt;prod&gt;</b> failed: <code>timeout &amp; retry</code>");
+///
+/// let link = telegram_html!(r#"<a href="{url}">{n:>3} logs</a>"#, url = "https://x.io/?a=1&b=\"", n = 12);
+/// assert_eq!(link, r#"<a href="https://x.io/?a=1&amp;b=&quot;"> 12 logs</a>"#);
+/// ```
+///
+/// Unbalanced or unsupported tags do not compile:
+///
+/// ```compile_fail
+/// let html = shared_types::telegram_html!("<b>{}</i>", "oops");
+/// ```
+///
+/// ```compile_fail
+/// let html = shared_types::telegram_html!("<div>{}</div>", "oops");
+/// ```
+#[cfg(feature = "derive")]
+pub use shared_types_derive::telegram_html;
+
 /// Macro to easily create a formatted TelegramQueueMessage.
 ///
+/// `chat_id` comes first and `content` (a format string and its arguments)
+/// last; `status`, `job` and `force_send` are optional and may appear in any
+/// order in between.
+///
 /// Usage:
 /// ```rust
-/// telegram_msg!(chat_id: 123, status: Error, job: "ftp", content: "failed: {}", e)
+/// use shared_types::{telegram_msg, MessageStatus};
+///
+/// let e = "timeout";
+/// let msg = telegram_msg!(chat_id: 123, status: Error, job: "ftp", content: "failed: {}", e);
+/// assert_eq!(msg.message, "🚨 - <i>ftp</i>\nfailed: timeout");
+///
+/// let msg = telegram_msg!(chat_id: 123, force_send: true, job: "ftp", status: Ok, content: "done");
+/// assert!(msg.force_send);
+/// assert_eq!(msg.status, MessageStatus::Ok);
+///
+/// let msg = telegram_msg!(chat_id: 123, content: "plain");
+/// assert_eq!(msg.status, MessageStatus::None);
 /// ```
 #[macro_export]
 macro_rules! telegram_msg {
-    (chat_id: $chat_id:expr, status: $status:ident, job: $job:expr, content: $($arg:tt)*) => {
-        $crate::telegram::TelegramMessageBuilder::new($chat_id)
-            .status($crate::telegram::MessageStatus::$status)
-            .job_name($job)
-            .content(format!($($arg)*))
-            .build()
+    (@option $builder:expr; status: $status:ident, $($rest:tt)*) => {
+        $crate::telegram_msg!(@option $builder.status($crate::telegram::MessageStatus::$status); $($rest)*)
+    };
+    (@option $builder:expr; job: $job:expr, $($rest:tt)*) => {
+        $crate::telegram_msg!(@option $builder.job_name($job); $($rest)*)
+    };
+    (@option $builder:expr; force_send: $force_send:expr, $($rest:tt)*) => {
+        $crate::telegram_msg!(@option $builder.force_send($force_send); $($rest)*)
+    };
+    (@option $builder:expr; content: $($arg:tt)*) => {
+        $builder.content(format!($($arg)*)).build()
     };
-    (chat_id: $chat_id:expr, status: $status:ident, job: $job:expr, force_send: $force_send:expr, content: $($arg:tt)*) => {
-        $crate::telegram::TelegramMessageBuilder::new($chat_id)
-            .status($crate::telegram::MessageStatus::$status)
-            .job_name($job)
-            .content(format!($($arg)*))
-            .force_send($force_send)
-            .build()
+    (chat_id: $chat_id:expr, $($rest:tt)*) => {
+        $crate::telegram_msg!(@option $crate::telegram::TelegramMessageBuilder::new($chat_id); $($rest)*)
     };
 }
@@ -0,0 +1,131 @@
+use std::fmt;
+
+/// Parsed W3C `traceparent` header (`{version}-{trace id}-{parent id}-{flags}`).
+///
+/// Envelopes store the header as a string; parse it with [`TraceParent::parse`]
+/// to read the ids. With the `opentelemetry` feature it converts to and from
+/// OpenTelemetry span contexts.
+///
+/// ```rust
+/// use shared_types::trace::TraceParent;
+///
+/// let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+/// let parent = TraceParent::parse(header).unwrap();
+/// assert!(parent.is_sampled());
+/// assert_eq!(parent.to_string(), header);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceParent {
+    /// Trace id, 16 bytes, never all zero.
+    pub trace_id: [u8; 16],
+    /// Id of the parent span, 8 bytes, never all zero.
+    pub parent_id: [u8; 8],
+    /// Trace flags, bit 0 is the sampled flag.
+    pub flags: u8,
+}
+
+impl TraceParent {
+    /// The only `traceparent` version this crate writes.
+    pub const VERSION: u8 = 0;
+
+    /// Flag marking the trace as sampled.
+    pub const FLAG_SAMPLED: u8 = 0x01;
+
+    /// Creates a TraceParent, returning `None` for the invalid all-zero ids.
+    pub fn new(trace_id: [u8; 16], parent_id: [u8; 8], flags: u8) -> Option<Self> {
+        let valid = trace_id != [0; 16] && parent_id != [0; 8];
+        valid.then_some(Self {
+            trace_id,
+            parent_id,
+            flags,
+        })
+    }
+
+    /// Parses a `traceparent` header value.
+    ///
+    /// Future versions are accepted as long as they start with the version 00
+    /// fields, as required by the W3C specification.
+    pub fn parse(header: &str) -> Option<Self> {
+        let header = header.trim();
+        let mut parts = header.splitn(5, '-');
+        let version = parse_hex::<1>(parts.next()?)?[0];
+        let trace_id = parse_hex::<16>(parts.next()?)?;
+        let parent_id = parse_hex::<8>(parts.next()?)?;
+        let flags = parse_hex::<1>(parts.next()?)?[0];
+        let rest = parts.next();
+
+        match (version, rest) {
+            (0xff, _) => return None,
+            (Self::VERSION, Some(_)) => return None,
+            _ => {}
+        }
+
+        Self::new(trace_id, parent_id, flags)
+    }
+
+    /// Returns true when the sampled flag is set.
+    pub fn is_sampled(&self) -> bool {
+        self.flags & Self::FLAG_SAMPLED != 0
+    }
+}
+
+impl fmt::Display for TraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}-", Self::VERSION)?;
+        for byte in self.trace_id {
+            write!(f, "{:02x}", byte)?;
+        }
+        f.write_str("-")?;
+        for byte in self.parent_id {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, "-{:02x}", self.flags)
+    }
+}
+
+/// Parses exactly `N` bytes of lowercase hex.
+fn parse_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    if hex.len() != N * 2 || !hex.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b)) {
+        return None;
+    }
+
+    let mut bytes = [0; N];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+#[cfg(feature = "opentelemetry")]
+mod otel {
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+
+    use super::TraceParent;
+
+    impl TraceParent {
+        /// Builds the traceparent of an OpenTelemetry span context, `None` when it is invalid.
+        pub fn from_span_context(span_context: &SpanContext) -> Option<Self> {
+            Self::new(
+                span_context.trace_id().to_bytes(),
+                span_context.span_id().to_bytes(),
+                span_context.trace_flags().to_u8(),
+            )
+        }
+
+        /// Builds a remote OpenTelemetry span context, parsing `tracestate` when present.
+        ///
+        /// An invalid `tracestate` is dropped, as required by the W3C specification.
+        pub fn to_span_context(&self, tracestate: Option<&str>) -> SpanContext {
+            let trace_state = tracestate
+                .and_then(|tracestate| tracestate.parse::<TraceState>().ok())
+                .unwrap_or_default();
+            SpanContext::new(
+                TraceId::from_bytes(self.trace_id),
+                SpanId::from_bytes(self.parent_id),
+                TraceFlags::new(self.flags),
+                true,
+                trace_state,
+            )
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// Protocol a file was moved with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum TransferProtocol {
+    Sftp,
+    Ftp,
+    S3,
+    Http,
+}
+
+impl TransferProtocol {
+    /// Returns the protocol name as shown in messages, e.g. `SFTP`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferProtocol::Sftp => "SFTP",
+            TransferProtocol::Ftp => "FTP",
+            TransferProtocol::S3 => "S3",
+            TransferProtocol::Http => "HTTP",
+        }
+    }
+}
+
+/// Direction of a transfer, seen from the machine running the job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum TransferDirection {
+    Upload,
+    Download,
+}
+
+/// Outcome of a single file transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum TransferOutcome {
+    Transferred,
+    /// Not transferred on purpose, e.g. already present on the other side.
+    Skipped,
+    Failed,
+}
+
+impl TransferOutcome {
+    /// Returns the marker shown in file tables.
+    pub(crate) fn marker(&self) -> &'static str {
+        match self {
+            TransferOutcome::Transferred => "✓",
+            TransferOutcome::Skipped => "-",
+            TransferOutcome::Failed => "✗",
+        }
+    }
+}
+
+/// A file moved by any mover (SFTP, FTP, S3 or HTTP), emitted once per file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct FileTransferEvent {
+    pub protocol: TransferProtocol,
+    pub path: String,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    #[cfg_attr(feature = "serde", serde(alias = "size_bytes"))]
+    pub size_bytes: u64,
+    /// Checksum prefixed with its algorithm, e.g. `sha256:9f86d0…`.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub checksum: Option<String>,
+    pub direction: TransferDirection,
+    pub outcome: TransferOutcome,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub error: Option<String>,
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub timestamp: DateTime<Utc>,
+}
+
+impl FileTransferEvent {
+    pub fn new(
+        protocol: TransferProtocol,
+        path: impl Into<String>,
+        size_bytes: u64,
+        direction: TransferDirection,
+        outcome: TransferOutcome,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            protocol,
+            path: path.into(),
+            size_bytes,
+            checksum: None,
+            direction,
+            outcome,
+            error: None,
+            timestamp,
+        }
+    }
+
+    pub fn with_checksum(mut self, checksum: impl Into<String>) -> Self {
+        self.checksum = Some(checksum.into());
+        self
+    }
+
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+}
+
+/// Totals of the transfers of one protocol.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TransferTotals {
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub transferred: u64,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub skipped: u64,
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub failed: u64,
+    /// Bytes of the transferred files.
+    #[cfg_attr(feature = "ts-rs", ts(type = "number"))]
+    pub bytes: u64,
+}
+
+impl TransferTotals {
+    fn add(&mut self, event: &FileTransferEvent) {
+        match event.outcome {
+            TransferOutcome::Transferred => {
+                self.transferred += 1;
+                self.bytes = self.bytes.saturating_add(event.size_bytes);
+            }
+            TransferOutcome::Skipped => self.skipped += 1,
+            TransferOutcome::Failed => self.failed += 1,
+        }
+    }
+}
+
+/// Daily aggregate of [`FileTransferEvent`]s, sent by the reporting job.
+///
+/// ```rust
+/// use chrono::{TimeZone, Utc};
+/// use shared_types::transfer::{
+///     FileTransferDigest, FileTransferEvent, TransferDirection, TransferOutcome, TransferProtocol,
+/// };
+///
+/// let at = Utc.with_ymd_and_hms(2024, 5, 17, 3, 0, 0).unwrap();
+/// let events = [
+///     FileTransferEvent::new(TransferProtocol::Sftp, "in/a.csv", 2048, TransferDirection::Download, TransferOutcome::Transferred, at),
+///     FileTransferEvent::new(TransferProtocol::S3, "archive/a.csv", 2048, TransferDirection::Upload, TransferOutcome::Failed, at),
+/// ];
+/// let digest = FileTransferDigest::for_day(at.date_naive(), &events);
+/// assert_eq!(digest.totals.transferred, 1);
+/// assert_eq!(
+///     digest.to_telegram_message(123).message,
+///     "⚠️ - <i>transfers</i>\n2024-05-17: 1 transferred (2.0 KiB), 0 skipped, 1 failed\n\
+///      <pre>SFTP: 1 ok, 0 skipped, 0 failed, 2.0 KiB\nS3: 0 ok, 0 skipped, 1 failed, 0 B</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct FileTransferDigest {
+    #[cfg_attr(feature = "bincode", bincode(with_serde))]
+    pub day: NaiveDate,
+    pub totals: TransferTotals,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "BTreeMap::is_empty", alias = "by_protocol"))]
+    pub by_protocol: BTreeMap<TransferProtocol, TransferTotals>,
+}
+
+impl FileTransferDigest {
+    /// Aggregates the events of `day` (UTC), ignoring the others.
+    pub fn for_day<'a>(day: NaiveDate, events: impl IntoIterator<Item = &'a FileTransferEvent>) -> Self {
+        let mut digest = Self {
+            day,
+            totals: TransferTotals::default(),
+            by_protocol: BTreeMap::new(),
+        };
+        for event in events.into_iter().filter(|event| event.timestamp.date_naive() == day) {
+            digest.totals.add(event);
+            digest.by_protocol.entry(event.protocol).or_default().add(event);
+        }
+        digest
+    }
+
+    /// `Warn` when a transfer failed, `Ok` otherwise.
+    pub fn severity(&self) -> MessageStatus {
+        if self.totals.failed > 0 {
+            MessageStatus::Warn
+        } else {
+            MessageStatus::Ok
+        }
+    }
+
+    /// Formats the digest for the `transfers` job: the day totals as summary
+    /// and one line per protocol in a `<pre>` block.
+    pub fn to_telegram_message(&self, chat_id: i64) -> TelegramQueueMessage {
+        let summary = format!(
+            "{}: {} transferred ({}), {} skipped, {} failed",
+            self.day,
+            self.totals.transferred,
+            render::bytes(self.totals.bytes),
+            self.totals.skipped,
+            self.totals.failed
+        );
+        let lines = self
+            .by_protocol
+            .iter()
+            .map(|(protocol, totals)| {
+                format!(
+                    "{}: {} ok, {} skipped, {} failed, {}",
+                    protocol.as_str(),
+                    totals.transferred,
+                    totals.skipped,
+                    totals.failed,
+                    render::bytes(totals.bytes)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        TelegramMessageBuilder::new(chat_id)
+            .status(self.severity())
+            .job_name("transfers")
+            .summary(summary)
+            .code_block(lines)
+            .build()
+    }
+}
@@ -0,0 +1,205 @@
+//! JavaScript bindings of [`TelegramQueueMessage`] and its builder, so the
+//! browser admin tool builds and sanitizes messages with the backend logic.
+//!
+//! The crate is not a `cdylib` by default, so the module is built with
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm
+//! --crate-type cdylib`, then `wasm-bindgen --target web --out-dir pkg
+//! target/wasm32-unknown-unknown/release/shared_types.wasm`. The classes
+//! keep their Rust names on the JavaScript side; chat ids are `bigint`,
+//! statuses are strings parsed like [`MessageStatus::from_str`], and builder
+//! methods consume the builder and return a new one, so calls are chained.
+//!
+//! ```js
+//! import init, { TelegramMessageBuilder } from "./pkg/shared_types.js";
+//!
+//! await init();
+//! const message = new TelegramMessageBuilder(-100123n)
+//!     .status("warn")
+//!     .jobName("ftp")
+//!     .content("slow upload")
+//!     .sanitize(4096)
+//!     .build();
+//! queue.publish(message.toJson());
+//! ```
+
+use std::str::FromStr;
+
+use wasm_bindgen::prelude::*;
+
+use crate::telegram::{MessageStatus, TelegramMessageBuilder, TelegramQueueMessage};
+
+/// A [`TelegramQueueMessage`] exported to JavaScript.
+///
+/// ```rust
+/// use shared_types::wasm::JsTelegramQueueMessage;
+///
+/// let mut message = JsTelegramQueueMessage::new(123, "<b>hi</b> <script>".into(), false);
+/// message.sanitize_message(4096);
+/// assert_eq!(message.message(), "<b>hi</b> &lt;script&gt;");
+/// assert_eq!(message.to_json().unwrap(), r#"{"schemaVersion":1,"chatId":123,"message":"<b>hi</b> &lt;script&gt;","forceSend":false}"#);
+/// ```
+#[wasm_bindgen(js_name = TelegramQueueMessage)]
+#[derive(Debug, Clone)]
+pub struct JsTelegramQueueMessage(TelegramQueueMessage);
+
+#[wasm_bindgen(js_class = TelegramQueueMessage)]
+impl JsTelegramQueueMessage {
+    #[wasm_bindgen(constructor)]
+    pub fn new(chat_id: i64, message: String, force_send: bool) -> Self {
+        Self(TelegramQueueMessage::new(chat_id, message, force_send))
+    }
+
+    /// Parses a queued message, accepting the same payloads as the consumers.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(json: &str) -> Result<Self, JsError> {
+        Ok(Self(serde_json::from_str(json)?))
+    }
+
+    /// Returns the JSON payload to publish on the queue.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(&self.0)?)
+    }
+
+    #[wasm_bindgen(getter, js_name = chatId)]
+    pub fn chat_id(&self) -> i64 {
+        self.0.chat_id
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> String {
+        self.0.message.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = forceSend)]
+    pub fn force_send(&self) -> bool {
+        self.0.force_send
+    }
+
+    /// Returns the status as its lowercase name, e.g. `"warn"`.
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> String {
+        self.0.status.as_str().to_string()
+    }
+
+    /// See [`TelegramQueueMessage::sanitize_message`].
+    #[wasm_bindgen(js_name = sanitizeMessage)]
+    pub fn sanitize_message(&mut self, max_message_length: usize) {
+        self.0.sanitize_message(max_message_length);
+    }
+
+    /// Throws when the message exceeds the Telegram length limit.
+    #[wasm_bindgen(js_name = checkLength)]
+    pub fn check_length(&self) -> Result<(), JsError> {
+        Ok(self.0.check_length()?)
+    }
+}
+
+impl From<TelegramQueueMessage> for JsTelegramQueueMessage {
+    fn from(message: TelegramQueueMessage) -> Self {
+        Self(message)
+    }
+}
+
+impl From<JsTelegramQueueMessage> for TelegramQueueMessage {
+    fn from(message: JsTelegramQueueMessage) -> Self {
+        message.0
+    }
+}
+
+/// A [`TelegramMessageBuilder`] exported to JavaScript.
+///
+/// ```rust
+/// use shared_types::wasm::JsTelegramMessageBuilder;
+///
+/// let message = JsTelegramMessageBuilder::new(123)
+///     .status("warn")
+///     .unwrap()
+///     .job_name("ftp".into())
+///     .content("slow upload".into())
+///     .build();
+/// assert_eq!(message.message(), "⚠️ - <i>ftp</i>\nslow upload");
+/// assert_eq!(message.status(), "warn");
+/// ```
+#[wasm_bindgen(js_name = TelegramMessageBuilder)]
+pub struct JsTelegramMessageBuilder(TelegramMessageBuilder);
+
+#[wasm_bindgen(js_class = TelegramMessageBuilder)]
+impl JsTelegramMessageBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new(chat_id: i64) -> Self {
+        Self(TelegramMessageBuilder::new(chat_id))
+    }
+
+    /// Sets the status from its name, throwing on unknown statuses.
+    pub fn status(self, status: &str) -> Result<Self, JsError> {
+        Ok(Self(self.0.status(MessageStatus::from_str(status)?)))
+    }
+
+    pub fn emoji(self, emoji: String) -> Self {
+        Self(self.0.emoji(emoji))
+    }
+
+    #[wasm_bindgen(js_name = jobName)]
+    pub fn job_name(self, job_name: String) -> Self {
+        Self(self.0.job_name(job_name))
+    }
+
+    pub fn summary(self, summary: String) -> Self {
+        Self(self.0.summary(summary))
+    }
+
+    pub fn content(self, content: String) -> Self {
+        Self(self.0.content(content))
+    }
+
+    pub fn code(self, snippet: String) -> Self {
+        Self(self.0.code(snippet))
+    }
+
+    #[wasm_bindgen(js_name = codeBlock)]
+    pub fn code_block(self, snippet: String) -> Self {
+        Self(self.0.code_block(snippet))
+    }
+
+    pub fn details(self, details: String) -> Self {
+        Self(self.0.details(details))
+    }
+
+    #[wasm_bindgen(js_name = mentionUser)]
+    pub fn mention_user(self, user_id: i64, display_name: String) -> Self {
+        Self(self.0.mention_user(user_id, display_name))
+    }
+
+    #[wasm_bindgen(js_name = mentionUsername)]
+    pub fn mention_username(self, username: String) -> Self {
+        Self(self.0.mention_username(username))
+    }
+
+    #[wasm_bindgen(js_name = forceSend)]
+    pub fn force_send(self, force_send: bool) -> Self {
+        Self(self.0.force_send(force_send))
+    }
+
+    pub fn tag(self, tag: String) -> Self {
+        Self(self.0.tag(tag))
+    }
+
+    #[wasm_bindgen(js_name = dedupKey)]
+    pub fn dedup_key(self, dedup_key: String) -> Self {
+        Self(self.0.dedup_key(dedup_key))
+    }
+
+    pub fn meta(self, key: String, value: String) -> Self {
+        Self(self.0.meta(key, value))
+    }
+
+    /// See [`TelegramMessageBuilder::sanitize`].
+    pub fn sanitize(self, max_length: usize) -> Self {
+        Self(self.0.sanitize(max_length))
+    }
+
+    pub fn build(self) -> JsTelegramQueueMessage {
+        JsTelegramQueueMessage(self.0.build())
+    }
+}
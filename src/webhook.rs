@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::retry::RetryPolicy;
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::telegram::MessageStatus;
+
+/// Header carrying the body signature unless configured otherwise.
+pub const DEFAULT_SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// HTTP method of a webhook call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum HttpMethod {
+    Get,
+    #[default]
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl HttpMethod {
+    /// Returns the method name as sent on the wire, e.g. `POST`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Patch => "PATCH",
+            HttpMethod::Delete => "DELETE",
+        }
+    }
+}
+
+/// How the webhook sender retries failed calls.
+#[deprecated(note = "use `retry::RetryPolicy`")]
+pub type WebhookRetryPolicy = RetryPolicy;
+
+/// HMAC-SHA256 signing of the request body, for receivers verifying the sender.
+///
+/// Only a reference to the secret is queued: the sender resolves `secret_ref`
+/// from its own configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct WebhookSignature {
+    /// Name of the secret in the sender's configuration.
+    #[cfg_attr(feature = "serde", serde(alias = "secret_ref"))]
+    pub secret_ref: String,
+    /// Header the signature is sent in.
+    #[cfg_attr(feature = "serde", serde(default = "default_signature_header"))]
+    pub header: String,
+    /// Prefix of the header value, e.g. `sha256=` as used by GitHub.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "String::is_empty"))]
+    pub prefix: String,
+}
+
+impl WebhookSignature {
+    /// Signs with the secret named `secret_ref`, in [`DEFAULT_SIGNATURE_HEADER`]
+    /// with a `sha256=` prefix.
+    pub fn new(secret_ref: impl Into<String>) -> Self {
+        Self {
+            secret_ref: secret_ref.into(),
+            header: default_signature_header(),
+            prefix: "sha256=".to_string(),
+        }
+    }
+}
+
+fn default_signature_header() -> String {
+    DEFAULT_SIGNATURE_HEADER.to_string()
+}
+
+/// Represents an HTTP call to a third-party system, made by the webhook sender.
+///
+/// ```rust
+/// use shared_types::WebhookQueueMessage;
+/// use shared_types::webhook::HttpMethod;
+///
+/// let hook = WebhookQueueMessage::new("https://erp.example.com/hooks/orders")
+///     .with_method(HttpMethod::Put)
+///     .with_header("X-Tenant", "damac")
+///     .with_json_body(r#"{"order":42,"status":"shipped"}"#);
+/// assert_eq!(hook.body_bytes(), br#"{"order":42,"status":"shipped"}"#);
+/// # #[cfg(feature = "serde")]
+/// # {
+/// assert_eq!(serde_json::to_value(&hook).unwrap()["body"]["order"], 42);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "ts-rs", derive(ts_rs::TS))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct WebhookQueueMessage {
+    /// Schema version of the payload.
+    #[cfg_attr(feature = "serde", serde(default = "crate::schema::default_schema_version", alias = "schema_version"))]
+    pub schema_version: u32,
+    pub url: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub method: HttpMethod,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "HashMap::is_empty"))]
+    pub headers: HashMap<String, String>,
+    /// JSON text of the body, sent with `Content-Type: application/json`;
+    /// serialized as embedded JSON.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none", with = "crate::json_text::option")
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<serde_json::Value>"))]
+    #[cfg_attr(feature = "ts-rs", ts(type = "unknown"))]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<Object>))]
+    pub body: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, alias = "retry_policy"))]
+    pub retry_policy: RetryPolicy,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub signature: Option<WebhookSignature>,
+    /// Status the message was built with, see [`Notification::severity`](crate::Notification::severity).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "MessageStatus::is_none"))]
+    pub status: MessageStatus,
+    /// Key suppressing duplicates, see [`Notification::dedup_key`](crate::Notification::dedup_key).
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none", alias = "dedup_key"))]
+    pub dedup_key: Option<String>,
+}
+
+impl WebhookQueueMessage {
+    /// Creates a `POST` to `url` without body, with the default retry policy.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            url: url.into(),
+            method: HttpMethod::default(),
+            headers: HashMap::new(),
+            body: None,
+            retry_policy: RetryPolicy::default(),
+            signature: None,
+            status: MessageStatus::None,
+            dedup_key: None,
+        }
+    }
+
+    pub fn with_method(mut self, method: HttpMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn with_body(mut self, body: serde_json::Value) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    /// Sets the body from JSON text, which is not validated until serialized.
+    pub fn with_json_body(mut self, json: impl Into<String>) -> Self {
+        self.body = Some(json.into());
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_signature(mut self, signature: WebhookSignature) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Returns the exact bytes to send as body, empty when there is none.
+    pub fn body_bytes(&self) -> Vec<u8> {
+        self.body.clone().map(String::into_bytes).unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "signing")]
+impl WebhookQueueMessage {
+    /// Returns the signature header name and value for the body, signed with
+    /// `key`, or `None` when the webhook is not signed.
+    ///
+    /// ```rust
+    /// use shared_types::WebhookQueueMessage;
+    /// use shared_types::webhook::WebhookSignature;
+    ///
+    /// let hook = WebhookQueueMessage::new("https://example.com/hook")
+    ///     .with_body(serde_json::json!({"ok": true}))
+    ///     .with_signature(WebhookSignature::new("erp-webhook"));
+    /// let (header, value) = hook.signature_header(b"secret").unwrap();
+    /// assert_eq!(header, "X-Signature-256");
+    /// assert!(value.starts_with("sha256=") && value.len() == 7 + 64);
+    /// ```
+    pub fn signature_header(&self, key: &[u8]) -> Option<(String, String)> {
+        use hmac::Mac;
+
+        use crate::signing::{hmac_sha256, to_hex};
+
+        let signature = self.signature.as_ref()?;
+        let digest = hmac_sha256(key, &self.body_bytes()).finalize().into_bytes();
+        Some((signature.header.clone(), format!("{}{}", signature.prefix, to_hex(&digest))))
+    }
+}